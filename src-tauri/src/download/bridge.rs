@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Error, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime::JoinHandle, AppHandle, Manager};
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+    time,
+};
+
+use crate::utils::output::Message;
+
+use super::scheduler::SchedulerState;
+use super::DownloadConfig;
+
+const BRIDGE_FILE_NAME: &str = "browser-bridge.json";
+const DEFAULT_PORT: u16 = 17321;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeConfig {
+    pub enabled: bool,
+    pub port: u16,
+    /// 请求需通过 `Authorization: Bearer <token>` 或 `X-Toolbox-Token` 携带
+    pub token: String,
+    /// 允许的 Origin，如 `chrome-extension://<id>`；不带 Origin 的请求只校验令牌
+    pub allowed_origins: Vec<String>,
+    pub dir_path: String,
+    pub concurrent: u64,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: generate_token(),
+            allowed_origins: vec![],
+            dir_path: String::new(),
+            concurrent: 5,
+        }
+    }
+}
+
+/// 浏览器发送的下载请求
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeRequest {
+    url: String,
+    referer: Option<String>,
+    cookies: Option<String>,
+    #[serde(alias = "fileName")]
+    filename: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct BridgeResponse {
+    task_id: String,
+}
+
+#[derive(Default)]
+pub struct BridgeState {
+    pub config: Mutex<BridgeConfig>,
+    server: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// 由操作系统的安全随机数生成 256 位令牌
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).expect("无法获取系统随机数");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 比较耗时与内容无关，避免通过响应时间逐字节猜出令牌
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn config_file_path<R: tauri::Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(BRIDGE_FILE_NAME))
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|v| v.as_str())
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> AnyResult<HttpRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_uppercase();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    let mut header_bytes = 0;
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line).await?;
+        header_bytes += n;
+        if header_bytes > MAX_HEADER_BYTES {
+            return Err(Error::msg("请求头过大"));
+        }
+        let trimmed = line.trim_end();
+        if n == 0 || trimmed.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = trimmed.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err(Error::msg("请求体过大"));
+    }
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+async fn write_response<T: Serialize>(
+    stream: &mut TcpStream,
+    status: &str,
+    origin: Option<&str>,
+    body: Option<&Message<T>>,
+) -> AnyResult<()> {
+    let body = match body {
+        Some(message) => serde_json::to_string(message)?,
+        None => String::new(),
+    };
+    let mut head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    if let Some(origin) = origin {
+        head.push_str(&format!(
+            "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\nAccess-Control-Allow-Methods: POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type, Authorization, X-Toolbox-Token\r\n",
+            origin
+        ));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// 只接受发往本机地址的请求，防止 DNS 重绑定
+fn is_local_host(host: Option<&str>, port: u16) -> bool {
+    let host = match host {
+        Some(h) => h.to_lowercase(),
+        None => return false,
+    };
+    ["127.0.0.1", "localhost", "[::1]"]
+        .iter()
+        .any(|name| host == *name || host == format!("{}:{}", name, port))
+}
+
+fn is_authorized(request: &HttpRequest, token: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let provided = request.header("x-toolbox-token").or_else(|| {
+        request
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+    });
+    provided.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes()))
+}
+
+fn to_download_config(request: BridgeRequest, config: &BridgeConfig) -> DownloadConfig {
+    let mut headers = HashMap::new();
+    if let Some(referer) = request.referer.filter(|v| !v.is_empty()) {
+        headers.insert(String::from("Referer"), referer);
+    }
+    if let Some(cookies) = request.cookies.filter(|v| !v.is_empty()) {
+        headers.insert(String::from("Cookie"), cookies);
+    }
+
+    DownloadConfig {
+        url: request.url,
+        dir_path: config.dir_path.clone(),
+        concurrent: config.concurrent.max(1),
+        plugin_name: String::from("browser-bridge"),
+        file_name: request.filename.filter(|v| !v.is_empty()),
+        event_type: None,
+        speed_limit_mbps: None,
+        post_actions: None,
+        checksum: None,
+        notify: None,
+        headers: Some(headers),
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, app: AppHandle, config: BridgeConfig) {
+    let request = match time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => {
+            let message = Message::<()>::failure(&e.to_string());
+            let _ = write_response(&mut stream, "400 Bad Request", None, Some(&message)).await;
+            return;
+        }
+        Err(_) => return,
+    };
+
+    if !is_local_host(request.header("host"), config.port) {
+        let message = Message::<()>::failure("无效的 Host");
+        let _ = write_response(&mut stream, "403 Forbidden", None, Some(&message)).await;
+        return;
+    }
+
+    let origin = request.header("origin").map(|o| o.to_string());
+    if let Some(origin) = &origin {
+        if !config.allowed_origins.iter().any(|o| o == origin) {
+            let message = Message::<()>::failure("来源不在允许列表中");
+            let _ = write_response(&mut stream, "403 Forbidden", None, Some(&message)).await;
+            return;
+        }
+    }
+    let origin = origin.as_deref();
+
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("OPTIONS", "/download") => {
+            write_response::<()>(&mut stream, "204 No Content", origin, None).await
+        }
+        ("POST", "/download") => {
+            if !is_authorized(&request, &config.token) {
+                let message = Message::<()>::failure("令牌无效");
+                write_response(&mut stream, "401 Unauthorized", origin, Some(&message)).await
+            } else {
+                match serde_json::from_slice::<BridgeRequest>(&request.body) {
+                    Ok(body) if !body.url.is_empty() => {
+                        let mut download_config = to_download_config(body, &config);
+                        if download_config.dir_path.is_empty() {
+                            if let Ok(dir) = app.path().download_dir() {
+                                download_config.dir_path = dir.to_string_lossy().to_string();
+                            }
+                        }
+                        let scheduler = app.state::<SchedulerState>();
+                        let task_id = scheduler.add(download_config, None, true).await;
+                        scheduler.save(&app).await;
+                        let message = Message::success(Some(BridgeResponse { task_id }));
+                        write_response(&mut stream, "200 OK", origin, Some(&message)).await
+                    }
+                    Ok(_) => {
+                        let message = Message::<()>::failure("缺少 url");
+                        write_response(&mut stream, "400 Bad Request", origin, Some(&message)).await
+                    }
+                    Err(e) => {
+                        let message = Message::<()>::failure(&format!("请求格式错误：{}", e));
+                        write_response(&mut stream, "400 Bad Request", origin, Some(&message)).await
+                    }
+                }
+            }
+        }
+        _ => {
+            let message = Message::<()>::failure("未找到");
+            write_response(&mut stream, "404 Not Found", origin, Some(&message)).await
+        }
+    };
+
+    if let Err(e) = result {
+        println!("[browser-bridge] 响应失败：{}", e);
+    }
+}
+
+async fn serve(app: AppHandle, config: BridgeConfig) -> AnyResult<JoinHandle<()>> {
+    let listener = TcpListener::bind(("127.0.0.1", config.port)).await?;
+    println!("[browser-bridge] 监听 127.0.0.1:{}", config.port);
+
+    Ok(tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(handle_connection(
+                        stream,
+                        app.clone(),
+                        config.clone(),
+                    ));
+                }
+                Err(e) => println!("[browser-bridge] 接受连接失败：{}", e),
+            }
+        }
+    }))
+}
+
+impl BridgeState {
+    /// 按当前配置重启服务，未启用时只停止
+    pub async fn restart(&self, app: &AppHandle) -> AnyResult<()> {
+        let config = self.config.lock().await.clone();
+        let mut server = self.server.lock().await;
+        if let Some(handle) = server.take() {
+            handle.abort();
+        }
+        if config.enabled {
+            *server = Some(serve(app.clone(), config).await?);
+        }
+        Ok(())
+    }
+
+    pub async fn save<R: tauri::Runtime>(&self, app: &AppHandle<R>) -> AnyResult<()> {
+        let path = config_file_path(app).ok_or(Error::msg("无法获取应用数据目录"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&*self.config.lock().await)?;
+        fs::write(&path, json).await?;
+        Ok(())
+    }
+
+    async fn load<R: tauri::Runtime>(&self, app: &AppHandle<R>) {
+        let content = match config_file_path(app) {
+            Some(path) => fs::read_to_string(path).await.ok(),
+            None => None,
+        };
+        if let Some(config) = content.and_then(|c| serde_json::from_str(&c).ok()) {
+            *self.config.lock().await = config;
+        }
+    }
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<BridgeState>();
+        state.load(&app).await;
+        if let Err(e) = state.restart(&app).await {
+            println!("[browser-bridge] 启动失败：{}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&str, &str)]) -> HttpRequest {
+        HttpRequest {
+            method: String::from("POST"),
+            path: String::from("/download"),
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: vec![],
+        }
+    }
+
+    #[test]
+    fn test_is_local_host() {
+        assert!(is_local_host(Some("127.0.0.1:17321"), 17321));
+        assert!(is_local_host(Some("localhost:17321"), 17321));
+        assert!(!is_local_host(Some("evil.example:17321"), 17321));
+        assert!(!is_local_host(Some("127.0.0.1:8080"), 17321));
+        assert!(!is_local_host(None, 17321));
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized(
+            &request(&[("x-toolbox-token", "abc")]),
+            "abc"
+        ));
+        assert!(is_authorized(
+            &request(&[("authorization", "Bearer abc")]),
+            "abc"
+        ));
+        assert!(!is_authorized(
+            &request(&[("x-toolbox-token", "abd")]),
+            "abc"
+        ));
+        assert!(!is_authorized(&request(&[]), "abc"));
+        assert!(!is_authorized(&request(&[]), ""));
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, generate_token());
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+}
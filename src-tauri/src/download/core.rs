@@ -1,14 +1,22 @@
 use std::sync::Arc;
 
 use anyhow::{Error, Result as AnyResult};
-use futures::{future::join_all, lock::Mutex};
+use futures::lock::Mutex;
 
-use tokio::fs::{remove_file, File};
+use tokio::{
+    fs::{remove_file, File},
+    task::JoinSet,
+};
 
 use crate::utils::output::MessageSender;
 
-use super::downloader::{download, handle_existing_files, load_download_progress, rename_file};
+use super::downloader::{
+    download, file_md5, handle_existing_files, load_download_progress, rename_file, DownloadRequest,
+};
+use super::host_policy::HostLimiter;
 use super::limiter::SpeedLimiter;
+use super::notify::ChecksumMismatch;
+use super::post_action::run_post_actions;
 use super::progress::{
     delete_progress_file, get_progress_file_path, get_temp_file_path, save_progress_file,
     start_periodic_progress_update,
@@ -17,7 +25,7 @@ use super::utils::report_progress;
 use super::{DownloadConfig, DownloadPayload, DownloadProgress, DownloadStatus};
 
 pub async fn perform_multithreaded_download(
-    url: String,
+    request: DownloadRequest,
     length: u64,
     concurrent: u64,
     progress: &mut Vec<(u64, u64)>,
@@ -25,7 +33,7 @@ pub async fn perform_multithreaded_download(
     file: Arc<Mutex<File>>,
     speed_limiter: Option<Arc<SpeedLimiter>>,
 ) -> AnyResult<bool> {
-    let mut handles = vec![];
+    let mut handles = JoinSet::new();
     let chunk_size = length / concurrent;
 
     for i in 0..concurrent {
@@ -42,31 +50,34 @@ pub async fn perform_multithreaded_download(
 
         let file = Arc::clone(&file);
         let limiter = speed_limiter.as_ref().map(|l| Arc::clone(l));
-        handles.push(tokio::spawn(download(
-            url.clone(),
+        handles.spawn(download(
+            request.clone(),
             (start, end),
             true,
             file.clone(),
             limiter,
-        )));
+        ));
 
         progress.push((start, end));
         super::downloader::save_progress(progress_path, progress.clone()).await?;
     }
 
-    let ret = join_all(handles).await;
+    // JoinSet 被丢弃时会中止所有分片任务，外层任务被取消时不会遗留后台下载
+    let mut err = false;
+    while let Some(ret) = handles.join_next().await {
+        err |= !matches!(ret, Ok(Ok(())));
+    }
     drop(file);
-    let err = ret.into_iter().flatten().any(|n| n.is_err());
     Ok(err)
 }
 
 pub async fn perform_singlethreaded_download(
-    url: String,
+    request: DownloadRequest,
     length: u64,
     file: Arc<Mutex<File>>,
     speed_limiter: Option<Arc<SpeedLimiter>>,
 ) -> AnyResult<bool> {
-    let err = download(url, (0, length - 1), false, file, speed_limiter)
+    let err = download(request, (0, length - 1), false, file, speed_limiter)
         .await
         .is_err();
     Ok(err)
@@ -77,10 +88,12 @@ pub async fn run(
     path: &str,
     sender: MessageSender,
     event_name: String,
+    hosts: Arc<HostLimiter>,
 ) -> AnyResult<()> {
+    let request = DownloadRequest::new(&payload.url, None, hosts);
     let (range, url, length, etag, last_modified) =
-        super::downloader::check_request_info(&payload.url, sender.clone(), event_name.clone())
-            .await?;
+        super::downloader::check_request_info(&request, sender.clone(), event_name.clone()).await?;
+    let request = request.with_url(url.clone());
     let temp_path = get_temp_file_path(path);
     let progress_path = get_progress_file_path(path);
     let file_path = path.to_string();
@@ -132,7 +145,7 @@ pub async fn run(
     let is_error = if range {
         sender.send(&event_name, format!("多线程下载中：{}", file_path), true);
         perform_multithreaded_download(
-            url.clone(),
+            request,
             length,
             payload.concurrent,
             &mut progress,
@@ -147,7 +160,7 @@ pub async fn run(
             format!("该文件不支持多线程下载，单线程下载中：{}", file_path),
             true,
         );
-        perform_singlethreaded_download(url.clone(), length, file.clone(), None).await?
+        perform_singlethreaded_download(request, length, file.clone(), None).await?
     };
 
     rename_file(&temp_path, path).await?;
@@ -168,11 +181,13 @@ pub async fn run_download(
     sender: MessageSender,
     event_name: String,
     progress_event: String,
-    speed_limiter: Option<SpeedLimiter>,
+    speed_limiter: Option<Arc<SpeedLimiter>>,
+    hosts: Arc<HostLimiter>,
 ) -> AnyResult<()> {
+    let request = DownloadRequest::new(&config.url, config.headers.as_ref(), hosts);
     let (range, url, length, etag, last_modified) =
-        super::downloader::check_request_info(&config.url, sender.clone(), event_name.clone())
-            .await?;
+        super::downloader::check_request_info(&request, sender.clone(), event_name.clone()).await?;
+    let request = request.with_url(url.clone());
     let temp_path = get_temp_file_path(path);
     let progress_path = get_progress_file_path(path);
     let file_path = path.to_string();
@@ -202,6 +217,7 @@ pub async fn run_download(
                 percentage: 100.0,
                 speed_mbps: 0.0,
                 status: DownloadStatus::Completed,
+                post_actions: vec![],
             },
         )
         .await;
@@ -210,7 +226,6 @@ pub async fn run_download(
 
     let mut progress = load_download_progress(range, &progress_path).await?;
     let file = Arc::new(Mutex::new(File::create(&temp_path).await?));
-    let limiter = speed_limiter.map(Arc::new);
 
     // Start periodic progress update (runs in background, updates every 5 seconds)
     let file_path_for_update = file_path.clone();
@@ -235,13 +250,13 @@ pub async fn run_download(
     let is_error = if range {
         sender.send(&event_name, format!("多线程下载中：{}", file_path), true);
         perform_multithreaded_download(
-            url.clone(),
+            request,
             length,
             config.concurrent,
             &mut progress,
             &progress_path,
             file.clone(),
-            limiter,
+            speed_limiter,
         )
         .await?
     } else {
@@ -250,7 +265,7 @@ pub async fn run_download(
             format!("该文件不支持多线程下载，单线程下载中：{}", file_path),
             true,
         );
-        perform_singlethreaded_download(url.clone(), length, file.clone(), limiter).await?
+        perform_singlethreaded_download(request, length, file.clone(), speed_limiter).await?
     };
 
     rename_file(&temp_path, path).await?;
@@ -259,7 +274,36 @@ pub async fn run_download(
         if range && super::downloader::check_file_exist(&progress_path).await {
             remove_file(&progress_path).await?;
         }
+        if let Some(expected) = &config.checksum {
+            let expected = expected.trim().to_lowercase();
+            let actual = file_md5(&file_path).await?;
+            if actual != expected {
+                sender.send(&event_name, format!("文件校验失败：{}", file_path), true);
+                report_progress(
+                    &sender,
+                    &progress_event,
+                    DownloadProgress {
+                        current: length,
+                        total: length,
+                        percentage: 100.0,
+                        speed_mbps: 0.0,
+                        status: DownloadStatus::ChecksumMismatch {
+                            expected: expected.clone(),
+                            actual: actual.clone(),
+                        },
+                        post_actions: vec![],
+                    },
+                )
+                .await;
+                return Err(ChecksumMismatch { expected, actual }.into());
+            }
+        }
+
         sender.send(&event_name, format!("下载完成：{}", file_path), true);
+        let post_action_results = match &config.post_actions {
+            Some(actions) if !actions.is_empty() => run_post_actions(&file_path, actions).await,
+            _ => vec![],
+        };
         report_progress(
             &sender,
             &progress_event,
@@ -269,6 +313,7 @@ pub async fn run_download(
                 percentage: 100.0,
                 speed_mbps: 0.0,
                 status: DownloadStatus::Completed,
+                post_actions: post_action_results,
             },
         )
         .await;
@@ -284,6 +329,7 @@ pub async fn run_download(
                 percentage: 0.0,
                 speed_mbps: 0.0,
                 status: DownloadStatus::Failed("下载失败".to_string()),
+                post_actions: vec![],
             },
         )
         .await;
@@ -1,13 +1,14 @@
+use std::collections::HashMap;
 use std::io::{ErrorKind, SeekFrom};
 use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{Error, Result as AnyResult};
-use futures::lock::Mutex;
+use futures::{lock::Mutex, StreamExt};
 
 use reqwest::{
-    header::{HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, RANGE},
-    Response,
+    header::{HeaderMap, HeaderName, HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, RANGE},
+    RequestBuilder, Response,
 };
 use tokio::{
     fs::File,
@@ -16,15 +17,115 @@ use tokio::{
 
 use crate::utils::output::MessageSender;
 
+use super::host_policy::{self, HostLimiter, HostPermit};
 use super::limiter::SpeedLimiter;
 
+/// 凭据类请求头，不写入计划文件，也不发送给重定向后的其他主机
+pub const CREDENTIAL_HEADERS: [&str; 4] =
+    ["cookie", "authorization", "proxy-authorization", "referer"];
+
+pub fn is_credential_header(name: &str) -> bool {
+    CREDENTIAL_HEADERS
+        .iter()
+        .any(|h| h.eq_ignore_ascii_case(name.trim()))
+}
+
+fn same_host(a: &str, b: &str) -> bool {
+    match (reqwest::Url::parse(a), reqwest::Url::parse(b)) {
+        (Ok(a), Ok(b)) => {
+            a.host_str() == b.host_str() && a.port_or_known_default() == b.port_or_known_default()
+        }
+        _ => false,
+    }
+}
+
+/// 下载地址及需要附带的请求头（如浏览器传来的 Referer、Cookie）
+#[derive(Clone)]
+pub struct DownloadRequest {
+    pub url: String,
+    pub headers: HeaderMap,
+    hosts: Arc<HostLimiter>,
+}
+
+impl DownloadRequest {
+    pub fn new(
+        url: &str,
+        headers: Option<&HashMap<String, String>>,
+        hosts: Arc<HostLimiter>,
+    ) -> Self {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers.into_iter().flatten() {
+            match (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => {
+                    map.insert(name, value);
+                }
+                _ => println!("[download] 忽略无效的请求头：{}", name),
+            }
+        }
+        Self {
+            url: url.to_string(),
+            headers: map,
+            hosts,
+        }
+    }
+
+    /// 使用重定向后的地址，跳转到其他主机时去掉凭据类请求头
+    pub fn with_url(&self, url: String) -> Self {
+        let mut headers = self.headers.clone();
+        if !same_host(&self.url, &url) {
+            for name in CREDENTIAL_HEADERS {
+                headers.remove(name);
+            }
+        }
+        Self {
+            url,
+            headers,
+            hosts: self.hosts.clone(),
+        }
+    }
+
+    pub fn head(&self) -> RequestBuilder {
+        reqwest::Client::new()
+            .head(&self.url)
+            .headers(self.headers.clone())
+    }
+
+    pub fn get(&self) -> RequestBuilder {
+        reqwest::Client::new()
+            .get(&self.url)
+            .headers(self.headers.clone())
+    }
+}
+
+/// 按域名规则限制连接数与请求间隔，遇到 429/503 时按 Retry-After 退避重试
+async fn send_polite(
+    request: &DownloadRequest,
+    build: impl Fn() -> RequestBuilder,
+) -> AnyResult<(Response, HostPermit)> {
+    let url = &request.url;
+    let mut attempt = 0;
+    loop {
+        let permit = request.hosts.acquire(url).await?;
+        let resp = build().send().await?;
+        if !host_policy::should_retry(resp.status()) || attempt >= host_policy::MAX_RETRIES {
+            return Ok((resp, permit));
+        }
+        let delay = host_policy::retry_delay(resp.headers(), attempt);
+        drop(permit);
+        request.hosts.delay_host(url, delay).await;
+        attempt += 1;
+    }
+}
+
 pub async fn check_request_info(
-    url: &str,
+    request: &DownloadRequest,
     sender: MessageSender,
     event_name: String,
 ) -> AnyResult<(bool, String, u64, Option<String>, Option<String>)> {
-    let req = reqwest::Client::new().head(url);
-    let resp = req.send().await?;
+    let (resp, _permit) = send_polite(request, || request.head()).await?;
     if !resp.status().is_success() {
         return Err(Error::msg("请求失败"));
     }
@@ -34,7 +135,8 @@ pub async fn check_request_info(
         .get(ACCEPT_RANGES)
         .map(|val| (val == &HeaderValue::from_static("bytes")).then(|| ()))
         .flatten()
-        .is_some();
+        .is_some()
+        && request.hosts.allows_ranges(&url).await;
     let length = headers
         .get(CONTENT_LENGTH)
         .map(|val| val.to_str().ok())
@@ -80,36 +182,74 @@ pub async fn check_redirected_url(
     Ok(resp.url().to_string())
 }
 
+/// 连接中断后从已写入位置重新请求的最大连续次数
+const MAX_RESUME_RETRIES: u32 = 3;
+
+/// 下载并写入 `start..=end` 区间，分片下载时连接中断（如暂停期间超时）会从已写入的位置继续
 pub async fn download(
-    url: String,
+    request: DownloadRequest,
     (start, end): (u64, u64),
     is_partial: bool,
     file: Arc<Mutex<File>>,
     speed_limiter: Option<Arc<SpeedLimiter>>,
 ) -> AnyResult<()> {
-    let req = reqwest::Client::new().get(url);
-    let req = if is_partial {
-        req.header(RANGE, format!("bytes={}-{}", start, end))
-    } else {
-        req
-    };
-    let rep = req.send().await?;
+    let mut offset = start;
+    let mut attempt = 0;
+    loop {
+        let from = offset;
+        let result = download_range(
+            &request,
+            (&mut offset, end),
+            is_partial,
+            &file,
+            speed_limiter.as_ref(),
+        )
+        .await;
+        match result {
+            Ok(()) => return Ok(()),
+            Err(_) if is_partial && offset > end => return Ok(()),
+            Err(e) if !is_partial || attempt >= MAX_RESUME_RETRIES => return Err(e),
+            Err(_) => {
+                attempt = if offset > from { 0 } else { attempt + 1 };
+            }
+        }
+    }
+}
+
+async fn download_range(
+    request: &DownloadRequest,
+    (offset, end): (&mut u64, u64),
+    is_partial: bool,
+    file: &Mutex<File>,
+    speed_limiter: Option<&Arc<SpeedLimiter>>,
+) -> AnyResult<()> {
+    let (rep, _permit) = send_polite(request, || {
+        let req = request.get();
+        if is_partial {
+            req.header(RANGE, format!("bytes={}-{}", *offset, end))
+        } else {
+            req
+        }
+    })
+    .await?;
     if !rep.status().is_success() {
         return Err(Error::msg("请求失败"));
     }
-    let mut bytes = rep.bytes().await?;
 
-    if let Some(limiter) = speed_limiter {
-        if let Some(limiter) = Arc::into_inner(limiter) {
-            let mut limiter = limiter;
-            limiter.wait(bytes.len() as u64).await;
+    // 按数据块限速并写入，避免整个分片先读入内存
+    let mut stream = rep.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let mut chunk = chunk?;
+        if let Some(limiter) = speed_limiter {
+            limiter.wait(chunk.len() as u64).await;
         }
+        let len = chunk.len() as u64;
+        let mut file = file.lock().await;
+        file.seek(SeekFrom::Start(*offset)).await?;
+        file.write_all_buf(&mut chunk).await?;
+        *offset += len;
     }
 
-    let mut file = file.lock().await;
-    file.seek(SeekFrom::Start(start)).await?;
-    file.write_all_buf(&mut bytes).await?;
-
     Ok(())
 }
 
@@ -120,6 +260,27 @@ pub async fn rename_file(temp_path: &str, real_path: &str) -> AnyResult<()> {
     }
 }
 
+/// 流式计算文件 MD5，返回小写十六进制字符串
+pub async fn file_md5(path: &str) -> AnyResult<String> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || -> AnyResult<String> {
+        use std::io::Read;
+
+        let mut file = std::fs::File::open(path)?;
+        let mut context = md5::Context::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            context.consume(&buf[..n]);
+        }
+        Ok(format!("{:x}", context.finalize()))
+    })
+    .await?
+}
+
 pub async fn check_file_exist<P: AsRef<Path>>(path: P) -> bool {
     tokio::fs::metadata(path).await.is_ok()
 }
@@ -201,3 +362,31 @@ pub async fn load_download_progress(
         Ok(vec![])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirect_strips_credentials_for_other_hosts() {
+        let headers = HashMap::from([
+            (String::from("Cookie"), String::from("sid=1")),
+            (String::from("Referer"), String::from("https://a.com/")),
+            (String::from("User-Agent"), String::from("ua")),
+        ]);
+        let request = DownloadRequest::new(
+            "https://a.com/file",
+            Some(&headers),
+            Arc::new(HostLimiter::default()),
+        );
+
+        let same = request.with_url(String::from("https://a.com:443/cdn/file"));
+        assert!(same.headers.contains_key("cookie"));
+        assert!(same.headers.contains_key("referer"));
+
+        let other = request.with_url(String::from("https://cdn.b.com/file"));
+        assert!(!other.headers.contains_key("cookie"));
+        assert!(!other.headers.contains_key("referer"));
+        assert!(other.headers.contains_key("user-agent"));
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Error, Result as AnyResult};
+use chrono::DateTime;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::{
+    fs,
+    sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore},
+    time::{sleep_until, Instant},
+};
+
+const HOST_POLICY_FILE_NAME: &str = "download-host-policies.json";
+
+/// Retry-After 最长等待时间，避免服务器返回异常值时任务长时间挂起
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(600);
+
+/// 遇到 429/503 时的最大重试次数
+pub const MAX_RETRIES: u32 = 5;
+
+/// 单个域名的规则，同时匹配其子域名
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HostPolicy {
+    pub host: String,
+    pub max_connections: Option<usize>,
+    pub min_delay_ms: Option<u64>,
+    pub allow_ranges: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HostSettings {
+    pub default_max_connections: usize,
+    pub policies: Vec<HostPolicy>,
+}
+
+impl Default for HostSettings {
+    fn default() -> Self {
+        Self {
+            default_max_connections: 8,
+            policies: vec![],
+        }
+    }
+}
+
+struct HostGate {
+    semaphore: Arc<Semaphore>,
+    max_connections: AtomicUsize,
+    min_delay_ms: AtomicU64,
+    /// 下一次允许发起请求的时间（最小间隔与 Retry-After 共用）
+    next_allowed: Mutex<Instant>,
+}
+
+impl HostGate {
+    fn new(max_connections: usize, min_delay_ms: u64) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_connections)),
+            max_connections: AtomicUsize::new(max_connections),
+            min_delay_ms: AtomicU64::new(min_delay_ms),
+            next_allowed: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// 调整连接数，正在使用的名额在归还后才会被收回
+    fn resize(&self, max_connections: usize, min_delay_ms: u64) {
+        self.min_delay_ms.store(min_delay_ms, Ordering::Relaxed);
+        let old = self.max_connections.swap(max_connections, Ordering::SeqCst);
+        if max_connections > old {
+            self.semaphore.add_permits(max_connections - old);
+        } else if max_connections < old {
+            let excess = old - max_connections;
+            let remaining = excess - self.semaphore.forget_permits(excess);
+            if remaining > 0 {
+                let semaphore = self.semaphore.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(remaining as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// 按域名限制连接数与请求间隔，由所有下载共享
+#[derive(Default)]
+pub struct HostLimiter {
+    settings: RwLock<HostSettings>,
+    gates: Mutex<HashMap<String, Arc<HostGate>>>,
+}
+
+/// 持有期间占用该域名的一个连接名额
+pub struct HostPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+pub fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+}
+
+fn matches_host(policy_host: &str, host: &str) -> bool {
+    let policy_host = policy_host.trim().trim_start_matches("*.").to_lowercase();
+    host == policy_host || host.ends_with(&format!(".{}", policy_host))
+}
+
+fn find_policy<'a>(settings: &'a HostSettings, host: &str) -> Option<&'a HostPolicy> {
+    settings
+        .policies
+        .iter()
+        .find(|p| matches_host(&p.host, host))
+}
+
+/// 域名规则对应的连接数与请求间隔（毫秒）
+fn gate_limits(settings: &HostSettings, host: &str) -> (usize, u64) {
+    let policy = find_policy(settings, host);
+    let max_connections = policy
+        .and_then(|p| p.max_connections)
+        .unwrap_or(settings.default_max_connections)
+        .max(1);
+    let min_delay_ms = policy.and_then(|p| p.min_delay_ms).unwrap_or(0);
+    (max_connections, min_delay_ms)
+}
+
+impl HostLimiter {
+    async fn gate_for(&self, host: &str) -> Arc<HostGate> {
+        let mut gates = self.gates.lock().await;
+        if let Some(gate) = gates.get(host) {
+            return gate.clone();
+        }
+        let (max_connections, min_delay_ms) = gate_limits(&*self.settings.read().await, host);
+        let gate = Arc::new(HostGate::new(max_connections, min_delay_ms));
+        gates.insert(host.to_string(), gate.clone());
+        gate
+    }
+
+    /// 获取连接名额并等待到允许的请求时间，等待期间不占用锁
+    pub async fn acquire(&self, url: &str) -> AnyResult<HostPermit> {
+        let host = host_of(url).ok_or(Error::msg("无效的下载地址"))?;
+        let gate = self.gate_for(&host).await;
+        let permit = gate.semaphore.clone().acquire_owned().await?;
+
+        let start = {
+            let mut next_allowed = gate.next_allowed.lock().await;
+            let start = (*next_allowed).max(Instant::now());
+            let min_delay = Duration::from_millis(gate.min_delay_ms.load(Ordering::Relaxed));
+            *next_allowed = start + min_delay;
+            start
+        };
+        sleep_until(start).await;
+
+        Ok(HostPermit { _permit: permit })
+    }
+
+    /// 服务器要求退避时，推迟该域名所有后续请求
+    pub async fn delay_host(&self, url: &str, delay: Duration) {
+        if let Some(host) = host_of(url) {
+            let gate = self.gate_for(&host).await;
+            let mut next_allowed = gate.next_allowed.lock().await;
+            let until = Instant::now() + delay.min(MAX_RETRY_AFTER);
+            if until > *next_allowed {
+                *next_allowed = until;
+            }
+        }
+    }
+
+    pub async fn allows_ranges(&self, url: &str) -> bool {
+        let host = match host_of(url) {
+            Some(h) => h,
+            None => return true,
+        };
+        let settings = self.settings.read().await;
+        find_policy(&settings, &host)
+            .and_then(|p| p.allow_ranges)
+            .unwrap_or(true)
+    }
+
+    pub async fn get_settings(&self) -> HostSettings {
+        self.settings.read().await.clone()
+    }
+
+    /// 更新规则并按新规则调整已有域名的连接数，进行中的下载不受影响
+    pub async fn set_settings(&self, settings: HostSettings) {
+        // 与 `gate_for` 相同，先锁域名表再锁规则
+        let gates = self.gates.lock().await;
+        for (host, gate) in gates.iter() {
+            let (max_connections, min_delay_ms) = gate_limits(&settings, host);
+            gate.resize(max_connections, min_delay_ms);
+        }
+        *self.settings.write().await = settings;
+    }
+
+    pub async fn save<R: tauri::Runtime>(&self, app: &AppHandle<R>) -> AnyResult<()> {
+        let path = settings_file_path(app).ok_or(Error::msg("无法获取应用数据目录"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&self.get_settings().await)?;
+        fs::write(&path, json).await?;
+        Ok(())
+    }
+
+    pub async fn load<R: tauri::Runtime>(&self, app: &AppHandle<R>) {
+        let content = match settings_file_path(app) {
+            Some(path) => fs::read_to_string(path).await.ok(),
+            None => None,
+        };
+        if let Some(settings) = content.and_then(|c| serde_json::from_str(&c).ok()) {
+            self.set_settings(settings).await;
+        }
+    }
+}
+
+pub fn should_retry(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// 解析 Retry-After（秒数或 HTTP 日期），缺失时按重试次数递增退避
+pub fn retry_delay(headers: &HeaderMap, attempt: u32) -> Duration {
+    let fallback = Duration::from_secs(5 * u64::from(attempt + 1));
+    let value = match headers.get(RETRY_AFTER).and_then(|v| v.to_str().ok()) {
+        Some(v) => v.trim(),
+        None => return fallback,
+    };
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Duration::from_secs(seconds).min(MAX_RETRY_AFTER);
+    }
+    match DateTime::parse_from_rfc2822(value) {
+        Ok(date) => (date.timestamp() - chrono::Utc::now().timestamp())
+            .try_into()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::ZERO)
+            .min(MAX_RETRY_AFTER),
+        Err(_) => fallback,
+    }
+}
+
+fn settings_file_path<R: tauri::Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(HOST_POLICY_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_matches_host() {
+        assert!(matches_host("example.com", "example.com"));
+        assert!(matches_host("example.com", "cdn.example.com"));
+        assert!(matches_host("*.example.com", "cdn.example.com"));
+        assert!(!matches_host("example.com", "badexample.com"));
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(
+            host_of("https://CDN.Example.com:8443/a.zip").as_deref(),
+            Some("cdn.example.com")
+        );
+        assert_eq!(host_of("not a url"), None);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_delay(&headers, 0), Duration::from_secs(5));
+        assert_eq!(retry_delay(&headers, 2), Duration::from_secs(15));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(retry_delay(&headers, 0), Duration::from_secs(30));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("99999"));
+        assert_eq!(retry_delay(&headers, 0), MAX_RETRY_AFTER);
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_delay(&headers, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_gate_resize_keeps_semaphore() {
+        let gate = HostGate::new(2, 0);
+        let semaphore = gate.semaphore.clone();
+        gate.resize(5, 100);
+        assert_eq!(semaphore.available_permits(), 5);
+        gate.resize(1, 100);
+        assert_eq!(semaphore.available_permits(), 1);
+        assert_eq!(gate.min_delay_ms.load(Ordering::Relaxed), 100);
+    }
+}
@@ -1,35 +1,104 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-pub struct SpeedLimiter {
-    bytes_per_second: u64,
+use tokio::sync::{Mutex, Notify};
+
+struct LimiterState {
     last_time: Instant,
     remaining_bytes: u64,
-    max_buffer: u64,
+}
+
+/// 多个分片共享的限速器，速率可在下载过程中调整（0 表示不限速）
+///
+/// 设置了 `parent` 时还需通过上级限速器，用于让所有下载共享时间段限速
+pub struct SpeedLimiter {
+    bytes_per_second: AtomicU64,
+    state: Mutex<LimiterState>,
+    paused: AtomicBool,
+    resumed: Notify,
+    parent: Option<Arc<SpeedLimiter>>,
+}
+
+fn mbps_to_bytes(mbps: f64) -> u64 {
+    (mbps * 1024.0 * 1024.0) as u64
+}
+
+impl Default for SpeedLimiter {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
 }
 
 impl SpeedLimiter {
     pub fn new(mbps: f64) -> Self {
+        Self::with_parent(Some(mbps), None)
+    }
+
+    pub fn with_parent(mbps: Option<f64>, parent: Option<Arc<SpeedLimiter>>) -> Self {
         Self {
-            bytes_per_second: (mbps * 1024.0 * 1024.0) as u64,
-            last_time: Instant::now(),
-            remaining_bytes: 0,
-            max_buffer: (mbps * 1024.0 * 1024.0 * 2.0) as u64,
+            bytes_per_second: AtomicU64::new(mbps.map(mbps_to_bytes).unwrap_or(0)),
+            state: Mutex::new(LimiterState {
+                last_time: Instant::now(),
+                remaining_bytes: 0,
+            }),
+            paused: AtomicBool::new(false),
+            resumed: Notify::new(),
+            parent,
         }
     }
 
-    pub async fn wait(&mut self, bytes: u64) {
+    pub fn set_limit(&self, mbps: Option<f64>) {
+        let bytes = mbps.map(mbps_to_bytes).unwrap_or(0);
+        self.bytes_per_second.store(bytes, Ordering::Relaxed);
+    }
+
+    /// 暂停后所有分片停在下一次 `wait`，已写入的数据保留
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+        self.resumed.notify_waiters();
+    }
+
+    pub async fn wait(&self, bytes: u64) {
+        loop {
+            let resumed = self.resumed.notified();
+            if !self.paused.load(Ordering::Relaxed) {
+                break;
+            }
+            resumed.await;
+        }
+        self.throttle(bytes).await;
+        if let Some(parent) = &self.parent {
+            parent.throttle(bytes).await;
+        }
+    }
+
+    async fn throttle(&self, bytes: u64) {
+        let bytes_per_second = self.bytes_per_second.load(Ordering::Relaxed);
+        if bytes_per_second == 0 {
+            return;
+        }
+        let max_buffer = bytes_per_second * 2;
+
+        let mut state = self.state.lock().await;
         let now = Instant::now();
-        let elapsed = now.duration_since(self.last_time).as_secs_f64();
-        
-        self.remaining_bytes = self.remaining_bytes.saturating_sub((elapsed * self.bytes_per_second as f64) as u64);
-        self.last_time = now;
-        
-        if self.remaining_bytes + bytes > self.max_buffer {
-            let needed = (self.remaining_bytes + bytes - self.max_buffer) as f64;
-            let sleep_time = needed / self.bytes_per_second as f64;
+        let elapsed = now.duration_since(state.last_time).as_secs_f64();
+
+        state.remaining_bytes = state
+            .remaining_bytes
+            .saturating_sub((elapsed * bytes_per_second as f64) as u64);
+        state.last_time = now;
+
+        if state.remaining_bytes + bytes > max_buffer {
+            let needed = (state.remaining_bytes + bytes - max_buffer) as f64;
+            let sleep_time = needed / bytes_per_second as f64;
             tokio::time::sleep(Duration::from_secs_f64(sleep_time)).await;
         }
-        
-        self.remaining_bytes += bytes;
+
+        state.remaining_bytes += bytes;
     }
 }
@@ -1,14 +1,34 @@
+mod bridge;
 mod core;
 mod downloader;
+mod host_policy;
 mod limiter;
+mod notify;
+mod post_action;
 mod progress;
+mod scheduler;
 mod utils;
 
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Error, Result as AnyResult};
+use futures::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    Manager, State,
+};
 
 use crate::utils::{os, output::Message, output::MessageSender};
 
+use bridge::{BridgeConfig, BridgeState};
 pub use core::{run, run_download};
+use host_policy::{HostLimiter, HostSettings};
+use notify::{BatchSummary, NotifySettings, NotifyState};
+use post_action::{PostAction, PostActionResult, PostActionRule, PostActionState};
+use scheduler::{BandwidthRules, ScheduledTaskInfo, SchedulerState};
 
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -19,7 +39,7 @@ pub struct DownloadPayload {
     pub plugin_name: String,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadConfig {
     pub url: String,
@@ -29,6 +49,13 @@ pub struct DownloadConfig {
     pub file_name: Option<String>,
     pub event_type: Option<String>,
     pub speed_limit_mbps: Option<f64>,
+    pub post_actions: Option<Vec<PostAction>>,
+    /// 期望的 MD5 校验值，下载完成后比对
+    pub checksum: Option<String>,
+    /// 是否发送系统通知，未设置时使用全局配置
+    pub notify: Option<bool>,
+    /// 附加请求头，如浏览器转发的 Referer、Cookie
+    pub headers: Option<HashMap<String, String>>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -39,6 +66,8 @@ pub struct DownloadProgress {
     pub percentage: f64,
     pub speed_mbps: f64,
     pub status: DownloadStatus,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub post_actions: Vec<PostActionResult>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -51,16 +80,24 @@ pub enum DownloadStatus {
     Resumed,
     Completed,
     Failed(String),
+    #[serde(rename_all = "camelCase")]
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
 }
 
 #[tauri::command]
 pub async fn download_file(
     payload: DownloadPayload,
+    hosts: State<'_, Arc<HostLimiter>>,
     app_handle: tauri::AppHandle,
 ) -> Result<Message<String>, ()> {
     let sender = MessageSender::new(app_handle, &payload.plugin_name);
     let event_name = format!("{}:download-output", payload.plugin_name);
-    let file_name = match payload.url.split('/').last() {
+    let file_name = match utils::extract_filename_from_url(&payload.url)
+        .and_then(|name| utils::sanitize_file_name(&name))
+    {
         Some(v) => v,
         None => return Ok(Message::failure("url错误")),
     };
@@ -71,55 +108,125 @@ pub async fn download_file(
         "/"
     };
     let path = format!("{}{}{}", payload.dir_path, splitter, file_name);
-    match run(payload, &path, sender, event_name).await {
+    match run(payload, &path, sender, event_name, hosts.inner().clone()).await {
         Ok(_) => Ok(Message::success(Some(String::from("下载成功")))),
         Err(e) => Ok(Message::failure(&e.to_string())),
     }
 }
 
-#[tauri::command]
-pub async fn download_file_with_config(
-    config: DownloadConfig,
+/// 解析保存路径、合并后续操作规则后执行下载，返回最终文件路径与下载结果
+async fn execute_download(
+    mut config: DownloadConfig,
+    rules: &[PostActionRule],
+    speed_limiter: Arc<limiter::SpeedLimiter>,
     app_handle: tauri::AppHandle,
-) -> Result<Message<String>, ()> {
+) -> (String, AnyResult<()>) {
+    let hosts = app_handle.state::<Arc<HostLimiter>>().inner().clone();
     let sender = MessageSender::new(app_handle, &config.plugin_name);
     let (event_name, progress_event) =
         utils::generate_event_name(&config.plugin_name, config.event_type.as_deref());
 
     let file_name = match &config.file_name {
-        Some(name) => name.clone(),
-        None => {
-            utils::extract_filename_from_url(&config.url).unwrap_or_else(|| "download".to_string())
-        }
+        Some(name) => utils::sanitize_file_name(name),
+        None => Some(
+            utils::extract_filename_from_url(&config.url)
+                .and_then(|name| utils::sanitize_file_name(&name))
+                .unwrap_or_else(|| "download".to_string()),
+        ),
     };
-
-    let system_name = os::get_system_name();
-    let splitter = if system_name.to_lowercase() == "windows" {
-        "\\"
-    } else {
-        "/"
+    let file_name = match file_name {
+        Some(val) => val,
+        None => return (String::new(), Err(Error::msg("无效的文件名"))),
     };
 
-    let base_path = format!("{}{}{}", config.dir_path, splitter, file_name);
+    let dir = Path::new(&config.dir_path);
+    let base_path = dir.join(&file_name).to_string_lossy().to_string();
     let path = utils::handle_filename_conflict(&base_path, &config.dir_path);
+    if Path::new(&path).parent() != Some(dir) {
+        return (path, Err(Error::msg("保存路径超出下载目录")));
+    }
 
-    let speed_limiter = config.speed_limit_mbps.map(limiter::SpeedLimiter::new);
+    config.post_actions = Some(post_action::resolve_actions(
+        &path,
+        config.post_actions.as_deref(),
+        rules,
+    ));
 
-    match run_download(
+    let result = run_download(
         config,
         &path,
         sender,
         event_name,
         progress_event,
-        speed_limiter,
+        Some(speed_limiter),
+        hosts,
     )
-    .await
-    {
+    .await;
+    (path, result)
+}
+
+#[tauri::command]
+pub async fn download_file_with_config(
+    config: DownloadConfig,
+    post_action_state: State<'_, PostActionState>,
+    notify_state: State<'_, NotifyState>,
+    scheduler_state: State<'_, SchedulerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    let rules = post_action_state.rules.read().await.clone();
+    let task_notify = config.notify;
+    let limiter = scheduler_state.task_limiter(config.speed_limit_mbps, true);
+    let (path, result) = execute_download(config, &rules, limiter, app_handle.clone()).await;
+
+    let settings = notify_state.settings.read().await.clone();
+    if notify::is_enabled(&settings, task_notify) {
+        notify::notify_download_result(&app_handle, &settings, &path, &result);
+    }
+
+    match result {
         Ok(_) => Ok(Message::success(Some(String::from("下载成功")))),
         Err(e) => Ok(Message::failure(&e.to_string())),
     }
 }
 
+/// 批量下载，结束后只发送一条汇总通知
+#[tauri::command]
+pub async fn download_files_batch(
+    configs: Vec<DownloadConfig>,
+    max_concurrent: Option<usize>,
+    post_action_state: State<'_, PostActionState>,
+    notify_state: State<'_, NotifyState>,
+    scheduler_state: State<'_, SchedulerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<BatchSummary>, ()> {
+    let rules = post_action_state.rules.read().await.clone();
+    let task_notify: Vec<Option<bool>> = configs.iter().map(|c| c.notify).collect();
+
+    let results: Vec<(String, AnyResult<()>)> = stream::iter(configs)
+        .map(|config| {
+            let limiter = scheduler_state.task_limiter(config.speed_limit_mbps, true);
+            execute_download(config, &rules, limiter, app_handle.clone())
+        })
+        .buffer_unordered(max_concurrent.unwrap_or(3).max(1))
+        .collect()
+        .await;
+
+    let mut summary = BatchSummary::default();
+    for (_, result) in &results {
+        summary.record(result);
+    }
+
+    let settings = notify_state.settings.read().await.clone();
+    if task_notify
+        .iter()
+        .any(|n| notify::is_enabled(&settings, *n))
+    {
+        notify::notify_batch_summary(&app_handle, &settings, &summary);
+    }
+
+    Ok(Message::success(Some(summary)))
+}
+
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ResumeDownloadInfo {
@@ -225,3 +332,162 @@ pub async fn check_server_range_support(url: String) -> Result<Message<RangeSupp
         Err(e) => Ok(Message::failure(&format!("无法访问 URL: {}", e))),
     }
 }
+
+#[tauri::command]
+pub async fn set_post_action_rules(
+    rules: Vec<PostActionRule>,
+    state: State<'_, PostActionState>,
+) -> Result<Message<String>, ()> {
+    *state.rules.write().await = rules;
+    Ok(Message::success(Some(String::from("设置成功"))))
+}
+
+#[tauri::command]
+pub async fn get_download_notify_settings(
+    state: State<'_, NotifyState>,
+) -> Result<Message<NotifySettings>, ()> {
+    Ok(Message::success(Some(state.settings.read().await.clone())))
+}
+
+#[tauri::command]
+pub async fn set_download_notify_settings(
+    settings: NotifySettings,
+    state: State<'_, NotifyState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    *state.settings.write().await = settings;
+    match state.save(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("设置成功")))),
+        Err(e) => Ok(Message::failure(&format!("保存配置失败：{}", e))),
+    }
+}
+
+/// 加入计划下载队列，`start_at` 为毫秒时间戳，为空表示尽快开始
+#[tauri::command]
+pub async fn schedule_download(
+    config: DownloadConfig,
+    start_at: Option<i64>,
+    respect_windows: Option<bool>,
+    state: State<'_, SchedulerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    let id = state
+        .add(config, start_at, respect_windows.unwrap_or(true))
+        .await;
+    state.save(&app_handle).await;
+    Ok(Message::success(Some(id)))
+}
+
+#[tauri::command]
+pub async fn cancel_scheduled_download(
+    id: String,
+    state: State<'_, SchedulerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    if !state.cancel(&id).await {
+        return Ok(Message::failure("任务不存在"));
+    }
+    state.save(&app_handle).await;
+    Ok(Message::success(Some(String::from("取消成功"))))
+}
+
+#[tauri::command]
+pub async fn list_scheduled_downloads(
+    state: State<'_, SchedulerState>,
+) -> Result<Message<Vec<ScheduledTaskInfo>>, ()> {
+    Ok(Message::success(Some(state.list().await)))
+}
+
+#[tauri::command]
+pub async fn get_bandwidth_rules(
+    state: State<'_, SchedulerState>,
+) -> Result<Message<BandwidthRules>, ()> {
+    Ok(Message::success(Some(state.rules.lock().await.clone())))
+}
+
+#[tauri::command]
+pub async fn set_bandwidth_rules(
+    rules: BandwidthRules,
+    state: State<'_, SchedulerState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    if let Some(e) = rules.windows.iter().find_map(|w| w.validate().err()) {
+        return Ok(Message::failure(&e.to_string()));
+    }
+    *state.rules.lock().await = rules;
+    state.save(&app_handle).await;
+    Ok(Message::success(Some(String::from("设置成功"))))
+}
+
+#[tauri::command]
+pub async fn get_browser_bridge_config(
+    state: State<'_, BridgeState>,
+) -> Result<Message<BridgeConfig>, ()> {
+    Ok(Message::success(Some(state.config.lock().await.clone())))
+}
+
+/// 保存配置并按新配置重启本地接收服务
+#[tauri::command]
+pub async fn set_browser_bridge_config(
+    mut config: BridgeConfig,
+    state: State<'_, BridgeState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    if config.token.is_empty() {
+        config.token = bridge::generate_token();
+    }
+    *state.config.lock().await = config;
+    if let Err(e) = state.save(&app_handle).await {
+        return Ok(Message::failure(&format!("保存配置失败：{}", e)));
+    }
+    match state.restart(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("设置成功")))),
+        Err(e) => Ok(Message::failure(&format!("启动服务失败：{}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_host_policies(
+    hosts: State<'_, Arc<HostLimiter>>,
+) -> Result<Message<HostSettings>, ()> {
+    Ok(Message::success(Some(hosts.get_settings().await)))
+}
+
+/// 设置各域名的连接数、请求间隔及是否允许分片下载
+#[tauri::command]
+pub async fn set_host_policies(
+    settings: HostSettings,
+    hosts: State<'_, Arc<HostLimiter>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, ()> {
+    if settings.policies.iter().any(|p| p.host.trim().is_empty()) {
+        return Ok(Message::failure("域名不能为空"));
+    }
+    hosts.set_settings(settings).await;
+    match hosts.save(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("设置成功")))),
+        Err(e) => Ok(Message::failure(&format!("保存配置失败：{}", e))),
+    }
+}
+
+pub fn init() -> TauriPlugin<tauri::Wry> {
+    println!("download plugin init");
+    Builder::new("download")
+        .setup(|app, _| {
+            app.manage(PostActionState::default());
+            app.manage(NotifyState::default());
+            app.manage(SchedulerState::default());
+            app.manage(BridgeState::default());
+            app.manage(Arc::new(HostLimiter::default()));
+            scheduler::start(app.clone());
+            bridge::start(app.clone());
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let hosts = handle.state::<Arc<HostLimiter>>().inner().clone();
+                hosts.load(&handle).await;
+                handle.state::<NotifyState>().load(&handle).await;
+            });
+            Ok(())
+        })
+        .build()
+}
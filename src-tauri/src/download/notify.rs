@@ -0,0 +1,161 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Error, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+use tokio::{fs, sync::RwLock};
+
+const NOTIFY_FILE_NAME: &str = "download-notify.json";
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifySettings {
+    pub enabled: bool,
+    pub on_completed: bool,
+    pub on_failed: bool,
+    pub on_checksum_mismatch: bool,
+}
+
+impl Default for NotifySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            on_completed: true,
+            on_failed: true,
+            on_checksum_mismatch: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct NotifyState {
+    pub settings: RwLock<NotifySettings>,
+}
+
+fn settings_file_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(NOTIFY_FILE_NAME))
+}
+
+impl NotifyState {
+    pub async fn save<R: Runtime>(&self, app: &AppHandle<R>) -> AnyResult<()> {
+        let path = settings_file_path(app).ok_or(Error::msg("无法获取应用数据目录"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&*self.settings.read().await)?;
+        fs::write(&path, json).await?;
+        Ok(())
+    }
+
+    pub async fn load<R: Runtime>(&self, app: &AppHandle<R>) {
+        let content = match settings_file_path(app) {
+            Some(path) => fs::read_to_string(path).await.ok(),
+            None => None,
+        };
+        if let Some(settings) = content.and_then(|c| serde_json::from_str(&c).ok()) {
+            *self.settings.write().await = settings;
+        }
+    }
+}
+
+/// 下载完成但校验值不一致，单独区分以便发送对应的通知
+#[derive(Debug)]
+pub struct ChecksumMismatch {
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "文件校验失败（期望：{}，实际：{}）",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+#[derive(Default, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub completed: usize,
+    pub failed: usize,
+    pub checksum_mismatch: usize,
+}
+
+impl BatchSummary {
+    pub fn record(&mut self, result: &AnyResult<()>) {
+        match result {
+            Ok(_) => self.completed += 1,
+            Err(e) if e.downcast_ref::<ChecksumMismatch>().is_some() => self.checksum_mismatch += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
+}
+
+/// 任务级别的开关优先于全局开关
+pub fn is_enabled(settings: &NotifySettings, task_notify: Option<bool>) -> bool {
+    task_notify.unwrap_or(settings.enabled)
+}
+
+fn show<R: Runtime>(app: &AppHandle<R>, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        println!("[download] 通知发送失败：{}", e);
+    }
+}
+
+pub fn notify_download_result<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &NotifySettings,
+    path: &str,
+    result: &AnyResult<()>,
+) {
+    let file_name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+
+    match result {
+        Ok(_) if settings.on_completed => show(app, "下载完成", &file_name),
+        Err(e) => match e.downcast_ref::<ChecksumMismatch>() {
+            Some(mismatch) if settings.on_checksum_mismatch => {
+                show(app, "文件校验失败", &format!("{}\n{}", file_name, mismatch))
+            }
+            None if settings.on_failed => show(app, "下载失败", &format!("{}\n{}", file_name, e)),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+pub fn notify_batch_summary<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &NotifySettings,
+    summary: &BatchSummary,
+) {
+    let has_failure = summary.failed > 0 || summary.checksum_mismatch > 0;
+    let wanted = if has_failure {
+        settings.on_failed || settings.on_checksum_mismatch
+    } else {
+        settings.on_completed
+    };
+    if !wanted {
+        return;
+    }
+
+    let mut body = format!("成功 {} 个", summary.completed);
+    if summary.failed > 0 {
+        body.push_str(&format!("，失败 {} 个", summary.failed));
+    }
+    if summary.checksum_mismatch > 0 {
+        body.push_str(&format!("，校验失败 {} 个", summary.checksum_mismatch));
+    }
+    show(app, "批量下载结束", &body);
+}
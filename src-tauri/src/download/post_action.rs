@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Error, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+use tokio::{fs, process::Command, sync::RwLock, time};
+
+use crate::utils::archive;
+
+/// 自定义命令的最长执行时间
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PostAction {
+    /// 解压到同级目录（或指定目录），可选删除压缩包
+    #[serde(rename_all = "camelCase")]
+    Extract {
+        target_dir: Option<String>,
+        #[serde(default)]
+        delete_archive: bool,
+    },
+    /// 按扩展名或大小移动到配置的目录，命中第一条规则即停止
+    #[serde(rename_all = "camelCase")]
+    Move { rules: Vec<MoveRule> },
+    /// 执行自定义命令，支持 `{path}` `{dir}` `{name}` 占位符
+    #[serde(rename_all = "camelCase")]
+    RunCommand { command: String },
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveRule {
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub target_dir: String,
+}
+
+/// 全局规则：按扩展名为所有下载任务追加后续操作
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PostActionRule {
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    pub actions: Vec<PostAction>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PostActionResult {
+    pub action: String,
+    pub success: bool,
+    pub message: String,
+    pub output_path: Option<String>,
+}
+
+#[derive(Default)]
+pub struct PostActionState {
+    pub rules: RwLock<Vec<PostActionRule>>,
+}
+
+impl PostAction {
+    fn name(&self) -> &'static str {
+        match self {
+            PostAction::Extract { .. } => "extract",
+            PostAction::Move { .. } => "move",
+            PostAction::RunCommand { .. } => "runCommand",
+        }
+    }
+}
+
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(n) => n.to_lowercase(),
+        None => return false,
+    };
+    extensions.iter().any(|ext| {
+        let ext = ext.trim_start_matches('.').to_lowercase();
+        name.ends_with(&format!(".{}", ext))
+    })
+}
+
+/// 合并任务自身配置与命中的全局规则，任务配置优先执行
+pub fn resolve_actions(
+    file_path: &str,
+    task_actions: Option<&[PostAction]>,
+    rules: &[PostActionRule],
+) -> Vec<PostAction> {
+    let path = Path::new(file_path);
+    let mut actions: Vec<PostAction> = task_actions.map(|a| a.to_vec()).unwrap_or_default();
+    for rule in rules {
+        if matches_extension(path, &rule.extensions) {
+            actions.extend(rule.actions.iter().cloned());
+        }
+    }
+    actions
+}
+
+/// 依次执行后续操作；移动或解压后，后续操作作用于新路径
+pub async fn run_post_actions(file_path: &str, actions: &[PostAction]) -> Vec<PostActionResult> {
+    let mut current = PathBuf::from(file_path);
+    let mut results = vec![];
+
+    for action in actions {
+        let result = match action {
+            PostAction::Extract {
+                target_dir,
+                delete_archive,
+            } => extract(&current, target_dir.as_deref(), *delete_archive).await,
+            PostAction::Move { rules } => move_by_rules(&current, rules).await,
+            PostAction::RunCommand { command } => run_command(&current, command).await,
+        };
+
+        match result {
+            Ok((message, output_path)) => {
+                if let Some(next) = &output_path {
+                    current = PathBuf::from(next);
+                }
+                results.push(PostActionResult {
+                    action: action.name().to_string(),
+                    success: true,
+                    message,
+                    output_path,
+                });
+            }
+            Err(e) => results.push(PostActionResult {
+                action: action.name().to_string(),
+                success: false,
+                message: e.to_string(),
+                output_path: None,
+            }),
+        }
+    }
+
+    results
+}
+
+async fn extract(
+    archive_path: &Path,
+    target_dir: Option<&str>,
+    delete_archive: bool,
+) -> AnyResult<(String, Option<String>)> {
+    if archive::detect_archive_kind(archive_path).is_none() {
+        return Ok((String::from("非压缩文件，跳过解压"), None));
+    }
+
+    let archive_path = archive_path.to_path_buf();
+    let target = match target_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => archive::default_extract_dir(&archive_path),
+    };
+
+    let (source, dest) = (archive_path.clone(), target.clone());
+    let count =
+        tokio::task::spawn_blocking(move || archive::extract_archive(&source, &dest)).await??;
+
+    // 只有删除了压缩包，后续操作才改为作用于解压目录
+    let output_path = if delete_archive {
+        fs::remove_file(&archive_path).await?;
+        Some(target.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    Ok((
+        format!("已解压 {} 个条目到：{}", count, target.to_string_lossy()),
+        output_path,
+    ))
+}
+
+async fn move_by_rules(path: &Path, rules: &[MoveRule]) -> AnyResult<(String, Option<String>)> {
+    let size = fs::metadata(path).await?.len();
+    let rule = rules.iter().find(|rule| {
+        matches_extension(path, &rule.extensions)
+            && rule.min_size.is_none_or(|min| size >= min)
+            && rule.max_size.is_none_or(|max| size <= max)
+    });
+
+    let rule = match rule {
+        Some(r) => r,
+        None => return Ok((String::from("没有匹配的移动规则"), None)),
+    };
+
+    let file_name = path.file_name().ok_or(Error::msg("无效的文件路径"))?;
+    fs::create_dir_all(&rule.target_dir).await?;
+    let base_path = Path::new(&rule.target_dir).join(file_name);
+    let target =
+        super::utils::handle_filename_conflict(&base_path.to_string_lossy(), &rule.target_dir);
+
+    // 跨磁盘时 rename 会失败，退化为复制后删除
+    if fs::rename(path, &target).await.is_err() {
+        fs::copy(path, &target).await?;
+        fs::remove_file(path).await?;
+    }
+
+    Ok((format!("已移动到：{}", target), Some(target)))
+}
+
+/// 将路径包裹为 shell 安全的参数，防止文件名中的特殊字符被解释
+fn quote_arg(arg: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("\"{}\"", arg.replace('"', "\"\""))
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+fn render_command(template: &str, path: &Path) -> String {
+    let dir = path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    template
+        .replace("{path}", &quote_arg(&path.to_string_lossy()))
+        .replace("{dir}", &quote_arg(&dir))
+        .replace("{name}", &quote_arg(&name))
+}
+
+async fn run_command(path: &Path, template: &str) -> AnyResult<(String, Option<String>)> {
+    let command_line = render_command(template, path);
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.arg("/C").arg(&command_line);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c").arg(&command_line);
+        c
+    };
+    command.kill_on_drop(true);
+
+    let output = time::timeout(COMMAND_TIMEOUT, command.output())
+        .await
+        .map_err(|_| Error::msg("命令执行超时"))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+
+    if output.status.success() {
+        Ok((stdout, None))
+    } else {
+        Err(Error::msg(format!(
+            "命令退出码 {}：{}",
+            output.status.code().unwrap_or(-1),
+            if stderr.is_empty() { stdout } else { stderr }
+        )))
+    }
+}
@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Error, Result as AnyResult};
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime::JoinHandle, AppHandle, Manager};
+use tokio::{fs, sync::Mutex, time};
+
+use super::downloader::is_credential_header;
+use super::limiter::SpeedLimiter;
+use super::notify::{self, NotifyState};
+use super::post_action::PostActionState;
+use super::DownloadConfig;
+
+/// 调度循环的检查间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+const SCHEDULE_FILE_NAME: &str = "download-schedule.json";
+
+/// 每日时间段，`end` 不大于 `start` 时表示跨越零点
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeWindow {
+    pub start: String,
+    pub end: String,
+    pub speed_limit_mbps: Option<f64>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthRules {
+    /// 为 true 时只在时间段内下载，否则时间段只用于限速
+    pub restrict_to_windows: bool,
+    pub windows: Vec<TimeWindow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduledStatus {
+    Waiting,
+    Running,
+    /// 离开时间段后暂停，回到时间段内从已下载的位置继续
+    Paused,
+    Completed,
+    Failed(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskInfo {
+    pub id: String,
+    pub url: String,
+    /// 计划开始时间（毫秒时间戳），为空表示尽快开始
+    pub start_at: Option<i64>,
+    /// 是否受时间段规则约束
+    pub respect_windows: bool,
+    pub status: ScheduledStatus,
+    pub created_at: i64,
+}
+
+#[derive(Deserialize)]
+struct ScheduledTask {
+    info: ScheduledTaskInfo,
+    config: DownloadConfig,
+    #[serde(skip)]
+    handle: Option<JoinHandle<()>>,
+    #[serde(skip)]
+    limiter: Option<Arc<SpeedLimiter>>,
+}
+
+#[derive(Deserialize)]
+struct ScheduleFile {
+    #[serde(default)]
+    rules: BandwidthRules,
+    tasks: Vec<ScheduledTask>,
+}
+
+#[derive(Serialize)]
+struct PersistedTaskRef<'a> {
+    info: &'a ScheduledTaskInfo,
+    config: DownloadConfig,
+}
+
+#[derive(Serialize)]
+struct ScheduleFileRef<'a> {
+    rules: &'a BandwidthRules,
+    tasks: Vec<PersistedTaskRef<'a>>,
+}
+
+#[derive(Default)]
+pub struct SchedulerState {
+    tasks: Arc<Mutex<HashMap<String, ScheduledTask>>>,
+    pub rules: Mutex<BandwidthRules>,
+    /// 当前时间段的限速，由所有下载共享
+    window_limiter: Arc<SpeedLimiter>,
+}
+
+fn parse_time(value: &str) -> AnyResult<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .map_err(|_| Error::msg(format!("无效的时间格式：{}", value)))
+}
+
+impl TimeWindow {
+    pub fn validate(&self) -> AnyResult<()> {
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        Ok(())
+    }
+
+    fn contains(&self, time: NaiveTime) -> bool {
+        let (start, end) = match (parse_time(&self.start), parse_time(&self.end)) {
+            (Ok(s), Ok(e)) => (s, e),
+            _ => return false,
+        };
+        if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+impl BandwidthRules {
+    fn active_window(&self, time: NaiveTime) -> Option<&TimeWindow> {
+        self.windows.iter().find(|w| w.contains(time))
+    }
+}
+
+/// 去掉凭据类请求头后的配置，用于写入计划文件
+fn persisted_config(config: &DownloadConfig) -> DownloadConfig {
+    let mut config = config.clone();
+    if let Some(headers) = config.headers.as_mut() {
+        headers.retain(|name, _| !is_credential_header(name));
+    }
+    config
+}
+
+fn now_millis() -> i64 {
+    Local::now().timestamp_millis()
+}
+
+fn generate_task_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", now_millis(), seq)
+}
+
+fn schedule_file_path<R: tauri::Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(SCHEDULE_FILE_NAME))
+}
+
+impl SchedulerState {
+    /// 创建单个下载的限速器，`respect_windows` 为 true 时同时受时间段限速
+    pub fn task_limiter(
+        &self,
+        task_limit: Option<f64>,
+        respect_windows: bool,
+    ) -> Arc<SpeedLimiter> {
+        let parent = respect_windows.then(|| self.window_limiter.clone());
+        Arc::new(SpeedLimiter::with_parent(task_limit, parent))
+    }
+
+    pub async fn add(
+        &self,
+        config: DownloadConfig,
+        start_at: Option<i64>,
+        respect_windows: bool,
+    ) -> String {
+        let id = generate_task_id();
+        let task = ScheduledTask {
+            info: ScheduledTaskInfo {
+                id: id.clone(),
+                url: config.url.clone(),
+                start_at,
+                respect_windows,
+                status: ScheduledStatus::Waiting,
+                created_at: now_millis(),
+            },
+            config,
+            handle: None,
+            limiter: None,
+        };
+        self.tasks.lock().await.insert(id.clone(), task);
+        id
+    }
+
+    pub async fn cancel(&self, id: &str) -> bool {
+        match self.tasks.lock().await.remove(id) {
+            Some(task) => {
+                if let Some(handle) = task.handle {
+                    handle.abort();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn list(&self) -> Vec<ScheduledTaskInfo> {
+        let mut list: Vec<ScheduledTaskInfo> = self
+            .tasks
+            .lock()
+            .await
+            .values()
+            .map(|t| t.info.clone())
+            .collect();
+        list.sort_by_key(|info| info.created_at);
+        list
+    }
+
+    /// 持久化规则与尚未完成的任务，运行中的任务重启后重新排队
+    ///
+    /// Cookie 等凭据类请求头不会写入文件，重启后的任务不再携带这些请求头
+    pub async fn save<R: tauri::Runtime>(&self, app: &AppHandle<R>) {
+        let path = match schedule_file_path(app) {
+            Some(p) => p,
+            None => return,
+        };
+        let json = {
+            let rules = self.rules.lock().await;
+            let tasks = self.tasks.lock().await;
+            let file = ScheduleFileRef {
+                rules: &rules,
+                tasks: tasks
+                    .values()
+                    .filter(|t| {
+                        matches!(
+                            t.info.status,
+                            ScheduledStatus::Waiting
+                                | ScheduledStatus::Running
+                                | ScheduledStatus::Paused
+                        )
+                    })
+                    .map(|t| PersistedTaskRef {
+                        info: &t.info,
+                        config: persisted_config(&t.config),
+                    })
+                    .collect(),
+            };
+            match serde_json::to_string_pretty(&file) {
+                Ok(json) => json,
+                Err(e) => return println!("[download] 序列化计划任务失败：{}", e),
+            }
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir).await;
+        }
+        if let Err(e) = fs::write(&path, json).await {
+            println!("[download] 保存计划任务失败：{}", e);
+        }
+    }
+
+    async fn load<R: tauri::Runtime>(&self, app: &AppHandle<R>) {
+        let path = match schedule_file_path(app) {
+            Some(p) => p,
+            None => return,
+        };
+        let content = match fs::read_to_string(&path).await {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        let saved: ScheduleFile = match serde_json::from_str(&content) {
+            Ok(v) => v,
+            Err(e) => return println!("[download] 读取计划任务失败：{}", e),
+        };
+        *self.rules.lock().await = saved.rules;
+        let mut tasks = self.tasks.lock().await;
+        for mut task in saved.tasks {
+            task.info.status = ScheduledStatus::Waiting;
+            tasks.insert(task.info.id.clone(), task);
+        }
+    }
+
+    /// 检查一次所有任务：到点的任务开始下载，离开时间段的任务暂停，回到时间段内继续
+    async fn tick(&self, app: &AppHandle) -> bool {
+        let rules = self.rules.lock().await.clone();
+        let now = Local::now();
+        let active_window = rules.active_window(now.time());
+        let in_window = !rules.restrict_to_windows || active_window.is_some();
+        self.window_limiter
+            .set_limit(active_window.and_then(|w| w.speed_limit_mbps));
+
+        let mut changed = false;
+        let mut tasks = self.tasks.lock().await;
+        for task in tasks.values_mut() {
+            let allowed = in_window || !task.info.respect_windows;
+
+            match task.info.status {
+                ScheduledStatus::Running if !allowed => {
+                    if let Some(limiter) = &task.limiter {
+                        limiter.pause();
+                    }
+                    task.info.status = ScheduledStatus::Paused;
+                    changed = true;
+                }
+                ScheduledStatus::Paused if allowed => {
+                    if let Some(limiter) = &task.limiter {
+                        limiter.resume();
+                    }
+                    task.info.status = ScheduledStatus::Running;
+                    changed = true;
+                }
+                ScheduledStatus::Waiting => {
+                    let due = task
+                        .info
+                        .start_at
+                        .is_none_or(|at| at <= now.timestamp_millis());
+                    if due && allowed {
+                        let limiter = self
+                            .task_limiter(task.config.speed_limit_mbps, task.info.respect_windows);
+                        task.limiter = Some(limiter.clone());
+                        task.info.status = ScheduledStatus::Running;
+                        task.handle = Some(spawn_task(
+                            app.clone(),
+                            self.tasks.clone(),
+                            task.info.id.clone(),
+                            task.config.clone(),
+                            limiter,
+                        ));
+                        changed = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        changed
+    }
+}
+
+fn spawn_task(
+    app: AppHandle,
+    tasks: Arc<Mutex<HashMap<String, ScheduledTask>>>,
+    id: String,
+    config: DownloadConfig,
+    limiter: Arc<SpeedLimiter>,
+) -> JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        let rules = app.state::<PostActionState>().rules.read().await.clone();
+        let task_notify = config.notify;
+        let (path, result) = super::execute_download(config, &rules, limiter, app.clone()).await;
+
+        let settings = app.state::<NotifyState>().settings.read().await.clone();
+        if notify::is_enabled(&settings, task_notify) {
+            notify::notify_download_result(&app, &settings, &path, &result);
+        }
+
+        if let Some(task) = tasks.lock().await.get_mut(&id) {
+            task.handle = None;
+            task.limiter = None;
+            task.info.status = match result {
+                Ok(_) => ScheduledStatus::Completed,
+                Err(e) => ScheduledStatus::Failed(e.to_string()),
+            };
+        }
+        app.state::<SchedulerState>().save(&app).await;
+    })
+}
+
+/// 在 Rust 侧常驻运行，窗口隐藏（`--hidden` 启动）时同样生效
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<SchedulerState>();
+        state.load(&app).await;
+
+        let mut interval = time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if state.tick(&app).await {
+                state.save(&app).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str) -> TimeWindow {
+        TimeWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+            speed_limit_mbps: None,
+        }
+    }
+
+    fn time(value: &str) -> NaiveTime {
+        parse_time(value).unwrap()
+    }
+
+    #[test]
+    fn test_window_contains_same_day() {
+        let w = window("01:00", "07:00");
+        assert!(w.contains(time("01:00")));
+        assert!(w.contains(time("06:59")));
+        assert!(!w.contains(time("07:00")));
+        assert!(!w.contains(time("12:00")));
+    }
+
+    #[test]
+    fn test_window_contains_across_midnight() {
+        let w = window("22:00", "06:00");
+        assert!(w.contains(time("23:30")));
+        assert!(w.contains(time("00:00")));
+        assert!(w.contains(time("05:59")));
+        assert!(!w.contains(time("06:00")));
+        assert!(!w.contains(time("21:59")));
+    }
+
+    #[test]
+    fn test_window_validate() {
+        assert!(window("01:00", "07:30:00").validate().is_ok());
+        assert!(window("1am", "07:00").validate().is_err());
+    }
+
+    #[test]
+    fn test_persisted_config_strips_credentials() {
+        let headers = HashMap::from([
+            (String::from("Cookie"), String::from("session=1")),
+            (
+                String::from("Referer"),
+                String::from("https://example.com/?token=1"),
+            ),
+            (String::from("authorization"), String::from("Bearer x")),
+            (String::from("Accept"), String::from("*/*")),
+        ]);
+        let config: DownloadConfig = serde_json::from_value(serde_json::json!({
+            "url": "https://example.com/a.zip",
+            "dirPath": "/tmp",
+            "concurrent": 1,
+            "pluginName": "download",
+            "headers": headers,
+        }))
+        .unwrap();
+
+        let saved = persisted_config(&config);
+        let saved_headers = saved.headers.unwrap();
+        assert_eq!(saved_headers.len(), 1);
+        assert!(saved_headers.contains_key("Accept"));
+        assert_eq!(config.headers.unwrap().len(), 4);
+    }
+}
@@ -8,6 +8,15 @@ pub fn extract_filename_from_url(url: &str) -> Option<String> {
     url.split('/').last().map(|s| s.to_string())
 }
 
+/// 只保留文件名部分，拒绝空名称、`..`、路径分隔符与 NUL，避免写到下载目录之外
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let name = Path::new(name.trim()).file_name()?.to_str()?.trim();
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', '\0']) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
 pub fn handle_filename_conflict(base_path: &str, _dir_path: &str) -> String {
     if !Path::new(base_path).exists() {
         return base_path.to_string();
@@ -49,3 +58,19 @@ pub async fn report_progress(
 ) {
     sender.send(progress_event, progress, false);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("a.zip").as_deref(), Some("a.zip"));
+        assert_eq!(sanitize_file_name("../../.bashrc").as_deref(), Some(".bashrc"));
+        assert_eq!(sanitize_file_name("/etc/passwd").as_deref(), Some("passwd"));
+        assert_eq!(sanitize_file_name(".."), None);
+        assert_eq!(sanitize_file_name(""), None);
+        assert_eq!(sanitize_file_name("a\\..\\b.exe"), None);
+        assert_eq!(sanitize_file_name("a\0b"), None);
+    }
+}
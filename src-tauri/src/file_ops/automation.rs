@@ -0,0 +1,1034 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Error, Result as AnyResult};
+use chrono::{DateTime, Local};
+use globset::{GlobBuilder, GlobMatcher};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::time;
+
+use crate::utils::archive;
+use crate::utils::file_type::{self, FileCategory};
+use crate::utils::output::MessageSender;
+use crate::utils::trash::move_to_trash;
+
+use super::ops::{copy_recursive, move_path, unique_path};
+
+const RULES_FILE_NAME: &str = "folder-rules.json";
+const LOG_FILE_NAME: &str = "folder-rule-log.json";
+const HANDLED_FILE_NAME: &str = "folder-rule-handled.json";
+const DELETE_FILE_NAME: &str = "folder-rule-delete.json";
+
+static RULE_ACTION_EVENT: &str = "folder-rule-action";
+
+/// 最多保留的操作记录数量
+const MAX_LOG: usize = 500;
+/// 试运行最多列出的文件数量
+const MAX_TEST_ITEMS: usize = 500;
+/// 文件最后一次变化后等待的时间，避免处理仍在写入的文件
+const SETTLE_DELAY: Duration = Duration::from_secs(3);
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// 定期检查已有文件，使按时间计算的条件与操作生效
+const SWEEP_INTERVAL: Duration = Duration::from_secs(600);
+/// 下载中的临时文件，等改为正式名称后再处理
+const PARTIAL_EXTENSIONS: [&str; 4] = ["crdownload", "part", "download", "tmp"];
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+fn default_true() -> bool {
+    true
+}
+
+/// 规则条件，需全部满足
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RuleCondition {
+    /// 匹配文件名，如 `*.pdf` 或 `*.{jpg,png}`，不区分大小写
+    Glob {
+        pattern: String,
+    },
+    Size {
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    /// 按修改时间计算的天数
+    Age {
+        min_days: Option<u64>,
+        max_days: Option<u64>,
+    },
+    /// 按文件头识别的类型
+    Type {
+        categories: Vec<FileCategory>,
+    },
+}
+
+/// 规则操作，按顺序执行，移动或重命名后的操作作用于新路径
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RuleAction {
+    /// 同名时自动追加序号
+    Move { target_dir: String },
+    /// 目标已存在同名文件时跳过
+    Copy { target_dir: String },
+    /// 按模板重命名，支持 `{name}` `{ext}` `{date}`（修改日期）占位符
+    Rename { pattern: String },
+    /// 解压到同级目录（或指定目录），可选将压缩包移到回收站
+    Extract {
+        target_dir: Option<String>,
+        #[serde(default)]
+        delete_archive: bool,
+    },
+    /// 修改时间超过指定天数后移到回收站
+    DeleteAfter { days: u64 },
+}
+
+impl RuleAction {
+    fn name(&self) -> &'static str {
+        match self {
+            RuleAction::Move { .. } => "move",
+            RuleAction::Copy { .. } => "copy",
+            RuleAction::Rename { .. } => "rename",
+            RuleAction::Extract { .. } => "extract",
+            RuleAction::DeleteAfter { .. } => "deleteAfter",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderRule {
+    /// 新建时为空
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 监听的文件夹
+    pub folders: Vec<String>,
+    /// 包含子文件夹中的文件
+    #[serde(default)]
+    pub recursive: bool,
+    #[serde(default)]
+    pub conditions: Vec<RuleCondition>,
+    pub actions: Vec<RuleAction>,
+}
+
+impl FolderRule {
+    fn covers(&self, path: &Path) -> bool {
+        self.folders.iter().any(|folder| {
+            let folder = Path::new(folder);
+            if self.recursive {
+                path.starts_with(folder) && path != folder
+            } else {
+                path.parent() == Some(folder)
+            }
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionResult {
+    pub action: String,
+    pub success: bool,
+    pub message: String,
+    pub output_path: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleLogEntry {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub path: String,
+    /// 毫秒时间戳
+    pub executed_at: i64,
+    pub results: Vec<ActionResult>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestItem {
+    pub path: String,
+    /// 试运行时各操作的预期结果
+    pub results: Vec<ActionResult>,
+}
+
+fn to_millis(time: SystemTime) -> Option<i64> {
+    let duration = time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    Some(duration.as_millis() as i64)
+}
+
+fn is_partial(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| PARTIAL_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 预先编译规则中的匹配模式
+struct CompiledRule {
+    rule: FolderRule,
+    globs: Vec<GlobMatcher>,
+}
+
+impl CompiledRule {
+    fn new(rule: FolderRule) -> AnyResult<Self> {
+        let mut globs = vec![];
+        for condition in &rule.conditions {
+            if let RuleCondition::Glob { pattern } = condition {
+                let glob = GlobBuilder::new(pattern.trim())
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| Error::msg(format!("无效的匹配模式：{}", e)))?;
+                globs.push(glob.compile_matcher());
+            }
+        }
+        Ok(Self { rule, globs })
+    }
+
+    /// 先检查开销小的条件，最后才读取文件头
+    fn matches(&self, path: &Path, metadata: &fs::Metadata, now: i64) -> bool {
+        let name = match path.file_name() {
+            Some(val) => val,
+            None => return false,
+        };
+        if !self.globs.iter().all(|glob| glob.is_match(name)) {
+            return false;
+        }
+        let modified = metadata.modified().ok().and_then(to_millis);
+        let cheap = self
+            .rule
+            .conditions
+            .iter()
+            .all(|condition| match condition {
+                RuleCondition::Size { min, max } => {
+                    min.is_none_or(|min| metadata.len() >= min)
+                        && max.is_none_or(|max| metadata.len() <= max)
+                }
+                RuleCondition::Age { min_days, max_days } => match modified {
+                    Some(modified) => {
+                        let age = now - modified;
+                        min_days.is_none_or(|days| age >= days as i64 * DAY_MILLIS)
+                            && max_days.is_none_or(|days| age <= days as i64 * DAY_MILLIS)
+                    }
+                    None => false,
+                },
+                _ => true,
+            });
+        if !cheap {
+            return false;
+        }
+        let type_conditions: Vec<&Vec<FileCategory>> = self
+            .rule
+            .conditions
+            .iter()
+            .filter_map(|condition| match condition {
+                RuleCondition::Type { categories } => Some(categories),
+                _ => None,
+            })
+            .collect();
+        if type_conditions.is_empty() {
+            return true;
+        }
+        file_type::detect_file(path)
+            .is_ok_and(|t| type_conditions.iter().all(|c| c.contains(&t.category)))
+    }
+}
+
+/// 检查规则是否可以保存
+pub fn validate_rule(rule: &FolderRule) -> AnyResult<()> {
+    if rule.name.trim().is_empty() {
+        return Err(Error::msg("请输入规则名称"));
+    }
+    if rule.folders.is_empty() {
+        return Err(Error::msg("请选择需要监听的文件夹"));
+    }
+    for folder in &rule.folders {
+        if !Path::new(folder).is_dir() {
+            return Err(Error::msg(format!("监听的文件夹不存在：{}", folder)));
+        }
+    }
+    if rule.actions.is_empty() {
+        return Err(Error::msg("请至少添加一个操作"));
+    }
+    for action in &rule.actions {
+        let dir = match action {
+            RuleAction::Move { target_dir } | RuleAction::Copy { target_dir } => Some(target_dir),
+            RuleAction::Extract { target_dir, .. } => target_dir.as_ref(),
+            RuleAction::Rename { pattern } if pattern.trim().is_empty() => {
+                return Err(Error::msg("重命名模板不能为空"))
+            }
+            _ => None,
+        };
+        if dir.is_some_and(|dir| !Path::new(dir).is_absolute()) {
+            return Err(Error::msg("目标文件夹必须是完整路径"));
+        }
+    }
+    CompiledRule::new(rule.clone()).map(|_| ())
+}
+
+fn render_name(pattern: &str, path: &Path, modified: Option<i64>) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let date = modified
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|d| d.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let name = pattern
+        .replace("{name}", &stem)
+        .replace("{ext}", &ext)
+        .replace("{date}", &date);
+    // 没有扩展名时去掉模板中多余的点
+    name.trim().trim_end_matches('.').to_string()
+}
+
+/// 单个操作的结果，`output` 为操作后文件的新位置，`stop` 表示后续操作不再执行
+struct Step {
+    message: String,
+    output: Option<PathBuf>,
+    stop: bool,
+}
+
+impl Step {
+    fn done(message: String) -> Self {
+        Self {
+            message,
+            output: None,
+            stop: false,
+        }
+    }
+}
+
+fn run_action(
+    path: &Path,
+    action: &RuleAction,
+    modified: Option<i64>,
+    now: i64,
+    dry_run: bool,
+) -> AnyResult<Step> {
+    let name = path.file_name().ok_or(Error::msg("无效的文件路径"))?;
+    match action {
+        RuleAction::Move { target_dir } => {
+            let target_dir = Path::new(target_dir);
+            if path.parent() == Some(target_dir) {
+                return Ok(Step::done(String::from("已在目标文件夹中")));
+            }
+            let target = unique_path(&target_dir.join(name));
+            if !dry_run {
+                fs::create_dir_all(target_dir)?;
+                move_path(path, &target)?;
+            }
+            Ok(Step {
+                message: format!("移动到：{}", target.to_string_lossy()),
+                output: Some(target),
+                stop: false,
+            })
+        }
+        RuleAction::Copy { target_dir } => {
+            let target = Path::new(target_dir).join(name);
+            if fs::symlink_metadata(&target).is_ok() {
+                return Ok(Step::done(String::from("目标已存在同名文件，跳过")));
+            }
+            if !dry_run {
+                fs::create_dir_all(target_dir)?;
+                copy_recursive(path, &target)?;
+            }
+            Ok(Step::done(format!("复制到：{}", target.to_string_lossy())))
+        }
+        RuleAction::Rename { pattern } => {
+            let new_name = render_name(pattern, path, modified);
+            if new_name.is_empty() || new_name.contains(['/', '\\', '\0']) {
+                return Err(Error::msg(format!("无效的文件名：{}", new_name)));
+            }
+            if name.to_string_lossy() == new_name {
+                return Ok(Step::done(String::from("名称未变化")));
+            }
+            let target = unique_path(&path.with_file_name(&new_name));
+            if !dry_run {
+                fs::rename(path, &target)?;
+            }
+            Ok(Step {
+                message: format!("重命名为：{}", target.to_string_lossy()),
+                output: Some(target),
+                stop: false,
+            })
+        }
+        RuleAction::Extract {
+            target_dir,
+            delete_archive,
+        } => {
+            if archive::detect_archive_kind(path).is_none() {
+                return Ok(Step::done(String::from("非压缩文件，跳过解压")));
+            }
+            let target = match target_dir {
+                Some(dir) => PathBuf::from(dir),
+                None => archive::default_extract_dir(path),
+            };
+            let message = if dry_run {
+                format!("解压到：{}", target.to_string_lossy())
+            } else {
+                let count = archive::extract_archive(path, &target)?;
+                if *delete_archive {
+                    move_to_trash(path)?;
+                }
+                format!("已解压 {} 个条目到：{}", count, target.to_string_lossy())
+            };
+            Ok(Step {
+                message,
+                output: None,
+                stop: *delete_archive,
+            })
+        }
+        RuleAction::DeleteAfter { days } => {
+            let due = modified.is_some_and(|m| now - m >= *days as i64 * DAY_MILLIS);
+            if !due {
+                return Ok(Step::done(format!("未满 {} 天，暂不删除", days)));
+            }
+            if !dry_run {
+                move_to_trash(path)?;
+            }
+            Ok(Step {
+                message: String::from("移到回收站"),
+                output: None,
+                stop: true,
+            })
+        }
+    }
+}
+
+/// 依次执行操作，失败或文件已被删除时停止，返回各操作结果与文件的最终位置
+fn run_actions(
+    path: &Path,
+    actions: &[RuleAction],
+    modified: Option<i64>,
+    now: i64,
+    dry_run: bool,
+) -> (Vec<ActionResult>, Option<PathBuf>) {
+    let mut current = Some(path.to_path_buf());
+    let mut results = vec![];
+    for action in actions {
+        let path = match &current {
+            Some(val) => val.clone(),
+            None => break,
+        };
+        match run_action(&path, action, modified, now, dry_run) {
+            Ok(step) => {
+                if step.stop {
+                    current = None;
+                } else if let Some(output) = &step.output {
+                    current = Some(output.clone());
+                }
+                results.push(ActionResult {
+                    action: action.name().to_string(),
+                    success: true,
+                    message: step.message,
+                    output_path: step.output.map(|p| p.to_string_lossy().to_string()),
+                });
+            }
+            Err(e) => {
+                results.push(ActionResult {
+                    action: action.name().to_string(),
+                    success: false,
+                    message: e.to_string(),
+                    output_path: None,
+                });
+                break;
+            }
+        }
+    }
+    (results, current)
+}
+
+/// 列出规则监听的文件夹中的文件，不跟随符号链接
+fn list_files(rule: &FolderRule) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut stack: Vec<PathBuf> = rule.folders.iter().map(PathBuf::from).collect();
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(val) => val,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            match entry.file_type() {
+                Ok(t) if t.is_dir() && rule.recursive => stack.push(entry.path()),
+                Ok(t) if t.is_file() => files.push(entry.path()),
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// 对规则监听的已有文件试运行，不修改文件
+pub fn test_rule(rule: FolderRule) -> AnyResult<Vec<RuleTestItem>> {
+    let compiled = CompiledRule::new(rule)?;
+    let now = chrono::Local::now().timestamp_millis();
+    let mut items = vec![];
+    for path in list_files(&compiled.rule) {
+        if is_partial(&path) {
+            continue;
+        }
+        let metadata = match fs::metadata(&path) {
+            Ok(val) => val,
+            Err(_) => continue,
+        };
+        if !compiled.matches(&path, &metadata, now) {
+            continue;
+        }
+        let modified = metadata.modified().ok().and_then(to_millis);
+        let (results, _) = run_actions(&path, &compiled.rule.actions, modified, now, true);
+        items.push(RuleTestItem {
+            path: path.to_string_lossy().to_string(),
+            results,
+        });
+        if items.len() >= MAX_TEST_ITEMS {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+fn handled_key(rule_id: &str, path: &Path) -> String {
+    format!("{}\n{}", rule_id, path.to_string_lossy())
+}
+
+/// 文件夹监听规则，在后台运行，与窗口是否显示无关
+#[derive(Default)]
+pub struct FolderRules {
+    rules: Mutex<Vec<FolderRule>>,
+    log: Mutex<VecDeque<RuleLogEntry>>,
+    /// 规则处理后的文件及当时的修改时间，文件未变化时不再重复处理
+    handled: Mutex<HashMap<String, Option<i64>>>,
+    /// 尚未到期删除的文件，键与 `handled` 相同，文件可能已被规则移出监听的文件夹
+    awaiting_delete: Mutex<HashSet<String>>,
+    /// 监听到变化的文件及最后一次变化的时间
+    pending: Mutex<HashMap<PathBuf, Instant>>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    /// 规则变化后尽快检查已有文件
+    sweep_requested: AtomicBool,
+}
+
+impl FolderRules {
+    pub fn list(&self) -> Vec<FolderRule> {
+        self.rules.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    pub fn upsert(&self, rule: FolderRule) {
+        if let Ok(mut rules) = self.rules.lock() {
+            match rules.iter_mut().find(|r| r.id == rule.id) {
+                Some(val) => *val = rule,
+                None => rules.push(rule),
+            }
+        }
+        self.sweep_requested.store(true, Ordering::Relaxed);
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        let removed = match self.rules.lock() {
+            Ok(mut rules) => {
+                let len = rules.len();
+                rules.retain(|r| r.id != id);
+                rules.len() != len
+            }
+            Err(_) => false,
+        };
+        let prefix = format!("{}\n", id);
+        if let Ok(mut handled) = self.handled.lock() {
+            handled.retain(|key, _| !key.starts_with(&prefix));
+        }
+        if let Ok(mut awaiting) = self.awaiting_delete.lock() {
+            awaiting.retain(|key| !key.starts_with(&prefix));
+        }
+        removed
+    }
+
+    /// 最近的记录在前
+    pub fn log(&self) -> Vec<RuleLogEntry> {
+        self.log
+            .lock()
+            .map(|l| l.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear_log(&self) {
+        if let Ok(mut log) = self.log.lock() {
+            log.clear();
+        }
+    }
+
+    fn compiled_rules(&self) -> Vec<CompiledRule> {
+        self.list()
+            .into_iter()
+            .filter(|r| r.enabled)
+            .filter_map(|r| CompiledRule::new(r).ok())
+            .collect()
+    }
+
+    fn is_handled(&self, rule_id: &str, path: &Path, modified: Option<i64>) -> bool {
+        self.handled
+            .lock()
+            .is_ok_and(|h| h.get(&handled_key(rule_id, path)) == Some(&modified))
+    }
+
+    fn mark_handled(&self, rule_id: &str, path: &Path) {
+        let modified = fs::metadata(path)
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(to_millis);
+        if let Ok(mut handled) = self.handled.lock() {
+            handled.insert(handled_key(rule_id, path), modified);
+        }
+    }
+
+    /// 按顺序对文件应用命中的规则，文件被移走或删除后不再检查后续规则
+    fn process_file(&self, rules: &[CompiledRule], path: &Path, now: i64) -> Vec<RuleLogEntry> {
+        let mut entries = vec![];
+        if is_partial(path) {
+            return entries;
+        }
+        for compiled in rules {
+            let metadata = match fs::symlink_metadata(path) {
+                Ok(val) if val.is_file() => val,
+                _ => break,
+            };
+            let rule = &compiled.rule;
+            let modified = metadata.modified().ok().and_then(to_millis);
+            if !rule.covers(path)
+                || self.is_handled(&rule.id, path, modified)
+                || !compiled.matches(path, &metadata, now)
+            {
+                continue;
+            }
+
+            let (results, output) = run_actions(path, &rule.actions, modified, now, false);
+            if let Some(output) = output.filter(|p| p.is_file()) {
+                self.mark_handled(&rule.id, &output);
+                // 未到期的删除留到之后的检查中执行
+                let delete_pending = rule.actions.iter().zip(&results).any(|(action, result)| {
+                    matches!(action, RuleAction::DeleteAfter { .. }) && result.success
+                });
+                if delete_pending {
+                    if let Ok(mut awaiting) = self.awaiting_delete.lock() {
+                        awaiting.insert(handled_key(&rule.id, &output));
+                    }
+                }
+            }
+            entries.push(RuleLogEntry {
+                rule_id: rule.id.clone(),
+                rule_name: rule.name.clone(),
+                path: path.to_string_lossy().to_string(),
+                executed_at: now,
+                results,
+            });
+        }
+        entries
+    }
+
+    /// 取出已经稳定一段时间的变更
+    fn take_settled(&self) -> Vec<PathBuf> {
+        let mut pending = match self.pending.lock() {
+            Ok(val) => val,
+            Err(_) => return vec![],
+        };
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, at)| at.elapsed() >= SETTLE_DELAY)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &settled {
+            pending.remove(path);
+        }
+        settled
+    }
+
+    fn process_paths(&self, paths: &[PathBuf]) -> Vec<RuleLogEntry> {
+        let rules = self.compiled_rules();
+        let now = chrono::Local::now().timestamp_millis();
+        paths
+            .iter()
+            .flat_map(|path| self.process_file(&rules, path, now))
+            .collect()
+    }
+
+    /// 删除已到期的文件，规则已删除或不再包含到期删除操作时放弃
+    fn delete_due(&self, rules: &[CompiledRule], now: i64) -> Vec<RuleLogEntry> {
+        let keys: Vec<String> = self
+            .awaiting_delete
+            .lock()
+            .map(|a| a.iter().cloned().collect())
+            .unwrap_or_default();
+        let mut entries = vec![];
+        for key in keys {
+            let (rule_id, path) = match key.split_once('\n') {
+                Some(val) => val,
+                None => continue,
+            };
+            let rule = match rules.iter().find(|r| r.rule.id == rule_id) {
+                Some(val) => &val.rule,
+                // 规则暂时停用时保留记录
+                None if self.list().iter().any(|r| r.id == rule_id) => continue,
+                None => {
+                    self.forget_delete(&key);
+                    continue;
+                }
+            };
+            let action = rule
+                .actions
+                .iter()
+                .find(|a| matches!(a, RuleAction::DeleteAfter { .. }));
+            let path = Path::new(path);
+            let (action, metadata) = match (action, fs::symlink_metadata(path)) {
+                (Some(action), Ok(metadata)) if metadata.is_file() => (action, metadata),
+                _ => {
+                    self.forget_delete(&key);
+                    continue;
+                }
+            };
+            let modified = metadata.modified().ok().and_then(to_millis);
+            let (results, output) =
+                run_actions(path, std::slice::from_ref(action), modified, now, false);
+            if output.is_some() && results.iter().all(|r| r.success) {
+                continue;
+            }
+            self.forget_delete(&key);
+            entries.push(RuleLogEntry {
+                rule_id: rule.id.clone(),
+                rule_name: rule.name.clone(),
+                path: path.to_string_lossy().to_string(),
+                executed_at: now,
+                results,
+            });
+        }
+        entries
+    }
+
+    fn forget_delete(&self, key: &str) {
+        if let Ok(mut awaiting) = self.awaiting_delete.lock() {
+            awaiting.remove(key);
+        }
+    }
+
+    /// 检查所有规则监听的已有文件与到期的删除，并清理已不存在的处理记录
+    fn sweep(&self) -> Vec<RuleLogEntry> {
+        let rules = self.compiled_rules();
+        let now = chrono::Local::now().timestamp_millis();
+        let paths: BTreeSet<PathBuf> = rules.iter().flat_map(|r| list_files(&r.rule)).collect();
+        let mut entries: Vec<RuleLogEntry> = paths
+            .iter()
+            .flat_map(|path| self.process_file(&rules, path, now))
+            .collect();
+        entries.extend(self.delete_due(&rules, now));
+        let exists = |key: &str| {
+            key.split_once('\n')
+                .is_some_and(|(_, path)| Path::new(path).is_file())
+        };
+        if let Ok(mut handled) = self.handled.lock() {
+            handled.retain(|key, _| exists(key));
+        }
+        if let Ok(mut awaiting) = self.awaiting_delete.lock() {
+            awaiting.retain(|key| exists(key));
+        }
+        entries
+    }
+
+    fn handle_event(&self, event: Event) {
+        if matches!(event.kind, EventKind::Access(_) | EventKind::Remove(_)) {
+            return;
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            for path in event.paths {
+                pending.insert(path, Instant::now());
+            }
+        }
+    }
+
+    /// 按启用的规则重新监听文件夹
+    pub fn restart_watcher(self: &Arc<Self>) {
+        let mut folders: HashMap<String, bool> = HashMap::new();
+        for rule in self.list().into_iter().filter(|r| r.enabled) {
+            for folder in rule.folders {
+                *folders.entry(folder).or_default() |= rule.recursive;
+            }
+        }
+        let mut slot = match self.watcher.lock() {
+            Ok(val) => val,
+            Err(_) => return,
+        };
+        *slot = None;
+        if folders.is_empty() {
+            return;
+        }
+
+        let engine = Arc::downgrade(self);
+        let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let (Ok(event), Some(engine)) = (res, engine.upgrade()) {
+                engine.handle_event(event);
+            }
+        });
+        let mut watcher = match watcher {
+            Ok(val) => val,
+            Err(e) => {
+                println!("[file-ops] 创建文件监听失败：{}", e);
+                return;
+            }
+        };
+        for (folder, recursive) in folders {
+            let mode = if recursive {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            // 监听失败时仍依赖定期检查
+            if let Err(e) = watcher.watch(Path::new(&folder), mode) {
+                println!("[file-ops] 监听文件夹失败：{}，错误：{}", folder, e);
+            }
+        }
+        *slot = Some(watcher);
+    }
+
+    async fn record(&self, app: &AppHandle, entries: Vec<RuleLogEntry>) {
+        let sender = MessageSender::new(app.clone(), "file-ops");
+        if let Ok(mut log) = self.log.lock() {
+            for entry in &entries {
+                log.push_back(entry.clone());
+            }
+            let overflow = log.len().saturating_sub(MAX_LOG);
+            log.drain(..overflow);
+        }
+        for entry in entries {
+            sender.send(RULE_ACTION_EVENT, entry, false);
+        }
+        if let Err(e) = self.save_state(app).await {
+            println!("[file-ops] 保存规则记录失败：{}", e);
+        }
+    }
+
+    pub async fn save_rules(&self, app: &AppHandle) -> AnyResult<()> {
+        write_json(app, RULES_FILE_NAME, &self.list()).await
+    }
+
+    pub async fn save_state(&self, app: &AppHandle) -> AnyResult<()> {
+        let log: Vec<RuleLogEntry> = self
+            .log
+            .lock()
+            .map(|l| l.iter().cloned().collect())
+            .unwrap_or_default();
+        write_json(app, LOG_FILE_NAME, &log).await?;
+        let handled = self.handled.lock().map(|h| h.clone()).unwrap_or_default();
+        write_json(app, HANDLED_FILE_NAME, &handled).await?;
+        let awaiting = self
+            .awaiting_delete
+            .lock()
+            .map(|a| a.clone())
+            .unwrap_or_default();
+        write_json(app, DELETE_FILE_NAME, &awaiting).await
+    }
+
+    async fn load(&self, app: &AppHandle) {
+        if let Some(rules) = read_json(app, RULES_FILE_NAME).await {
+            if let Ok(mut slot) = self.rules.lock() {
+                *slot = rules;
+            }
+        }
+        if let Some(log) = read_json(app, LOG_FILE_NAME).await {
+            if let Ok(mut slot) = self.log.lock() {
+                *slot = log;
+            }
+        }
+        if let Some(handled) = read_json(app, HANDLED_FILE_NAME).await {
+            if let Ok(mut slot) = self.handled.lock() {
+                *slot = handled;
+            }
+        }
+        if let Some(awaiting) = read_json(app, DELETE_FILE_NAME).await {
+            if let Ok(mut slot) = self.awaiting_delete.lock() {
+                *slot = awaiting;
+            }
+        }
+    }
+}
+
+fn data_file_path(app: &AppHandle, name: &str) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(name))
+}
+
+async fn write_json<T: Serialize>(app: &AppHandle, name: &str, value: &T) -> AnyResult<()> {
+    let path = data_file_path(app, name).ok_or(Error::msg("无法获取应用数据目录"))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(&path, serde_json::to_string_pretty(value)?).await?;
+    Ok(())
+}
+
+async fn read_json<T: for<'de> Deserialize<'de>>(app: &AppHandle, name: &str) -> Option<T> {
+    let path = data_file_path(app, name)?;
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 加载规则并开始监听，等待文件稳定后处理变更，同时定期检查已有文件
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let engine = app.state::<Arc<FolderRules>>().inner().clone();
+        engine.load(&app).await;
+        engine.restart_watcher();
+
+        let mut interval = time::interval(TICK_INTERVAL);
+        let mut last_sweep: Option<Instant> = None;
+        loop {
+            interval.tick().await;
+            let sweep = engine.sweep_requested.swap(false, Ordering::Relaxed)
+                || last_sweep.is_none_or(|at| at.elapsed() >= SWEEP_INTERVAL);
+            let paths = engine.take_settled();
+            if paths.is_empty() && !sweep {
+                continue;
+            }
+            if sweep {
+                last_sweep = Some(Instant::now());
+            }
+
+            let worker = engine.clone();
+            let entries = tauri::async_runtime::spawn_blocking(move || {
+                let mut entries = worker.process_paths(&paths);
+                if sweep {
+                    entries.extend(worker.sweep());
+                }
+                entries
+            })
+            .await
+            .unwrap_or_default();
+            if !entries.is_empty() || sweep {
+                engine.record(&app, entries).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(json: &str) -> FolderRule {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_render_name() {
+        let path = Path::new("/x/report.pdf");
+        assert_eq!(
+            render_name("{name}-final.{ext}", path, None),
+            "report-final.pdf"
+        );
+        assert_eq!(
+            render_name("{date}_{name}.{ext}", Path::new("/x/README"), Some(0)).len(),
+            17
+        );
+        assert_eq!(
+            render_name("{name}.{ext}", Path::new("/x/README"), None),
+            "README"
+        );
+    }
+
+    #[test]
+    fn test_rule_matching_and_actions() {
+        let root = std::env::temp_dir().join(format!("tool-box-rules-{}", std::process::id()));
+        let inbox = root.join("inbox");
+        let archive_dir = root.join("docs");
+        fs::create_dir_all(inbox.join("sub")).unwrap();
+        fs::write(inbox.join("a.PDF"), b"%PDF-1.4 test").unwrap();
+        fs::write(inbox.join("b.txt"), b"text").unwrap();
+        fs::write(inbox.join("sub/c.pdf"), b"%PDF-1.4").unwrap();
+        fs::write(inbox.join("d.pdf.part"), b"%PDF").unwrap();
+
+        let json = format!(
+            r#"{{
+                "name": "pdf",
+                "folders": [{:?}],
+                "conditions": [
+                    {{"type": "glob", "pattern": "*.pdf"}},
+                    {{"type": "size", "max": 1024}},
+                    {{"type": "type", "categories": ["document"]}}
+                ],
+                "actions": [
+                    {{"type": "rename", "pattern": "doc-{{name}}.{{ext}}"}},
+                    {{"type": "move", "targetDir": {:?}}},
+                    {{"type": "deleteAfter", "days": 30}}
+                ]
+            }}"#,
+            inbox.to_string_lossy(),
+            archive_dir.to_string_lossy()
+        );
+        let mut folder_rule = rule(&json);
+        folder_rule.id = String::from("r1");
+        validate_rule(&folder_rule).unwrap();
+
+        // 试运行只列出当前文件夹中的 PDF，不修改文件
+        let items = test_rule(folder_rule.clone()).unwrap();
+        assert_eq!(items.len(), 1);
+        assert!(items[0].path.ends_with("a.PDF"));
+        assert_eq!(items[0].results.len(), 3);
+        assert!(items[0].results[2].message.contains("30"));
+        assert!(inbox.join("a.PDF").exists());
+
+        let engine = FolderRules::default();
+        engine.upsert(folder_rule);
+        let entries = engine.sweep();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].results.iter().all(|r| r.success));
+        let moved = archive_dir.join("doc-a.PDF");
+        assert!(moved.is_file());
+        assert!(!inbox.join("a.PDF").exists());
+        assert!(inbox.join("sub/c.pdf").exists());
+
+        // 移走的文件与不匹配的文件都不再处理
+        assert!(engine
+            .process_paths(&[moved.clone(), inbox.join("b.txt")])
+            .is_empty());
+
+        // 未满 30 天时保留，到期后的检查中移到回收站
+        assert!(engine.sweep().is_empty());
+        assert!(moved.is_file());
+        let backdated = SystemTime::now() - Duration::from_secs(31 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&moved)
+            .unwrap()
+            .set_modified(backdated)
+            .unwrap();
+        let entries = engine.sweep();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].results[0].action, "deleteAfter");
+        assert!(entries[0].results[0].success);
+        assert!(!moved.exists());
+        assert!(engine.sweep().is_empty());
+
+        let mut invalid = rule(&json);
+        invalid.actions = vec![RuleAction::Copy {
+            target_dir: String::from("relative"),
+        }];
+        assert!(validate_rule(&invalid).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
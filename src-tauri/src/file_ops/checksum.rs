@@ -0,0 +1,329 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+
+const BUFFER_SIZE: usize = 256 * 1024;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Crc32,
+}
+
+impl HashAlgorithm {
+    pub const ALL: [HashAlgorithm; 4] = [Self::Md5, Self::Sha1, Self::Sha256, Self::Crc32];
+
+    /// 十六进制摘要的长度
+    fn hex_len(self) -> usize {
+        match self {
+            Self::Md5 => 32,
+            Self::Sha1 => 40,
+            Self::Sha256 => 64,
+            Self::Crc32 => 8,
+        }
+    }
+
+    fn from_hex_len(len: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.hex_len() == len)
+    }
+
+    /// 根据校验文件的扩展名或 BSD 格式的标签判断算法
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "md5" | "md5sum" => Some(Self::Md5),
+            "sha1" | "sha1sum" => Some(Self::Sha1),
+            "sha256" | "sha256sum" => Some(Self::Sha256),
+            "crc32" => Some(Self::Crc32),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHashes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<String>,
+}
+
+impl FileHashes {
+    pub fn get(&self, algorithm: HashAlgorithm) -> Option<&str> {
+        match algorithm {
+            HashAlgorithm::Md5 => self.md5.as_deref(),
+            HashAlgorithm::Sha1 => self.sha1.as_deref(),
+            HashAlgorithm::Sha256 => self.sha256.as_deref(),
+            HashAlgorithm::Crc32 => self.crc32.as_deref(),
+        }
+    }
+}
+
+/// 读取一次数据同时更新选中的所有算法
+#[derive(Default)]
+struct MultiHasher {
+    md5: Option<md5::Context>,
+    sha1: Option<Sha1>,
+    sha256: Option<Sha256>,
+    crc32: Option<crc32fast::Hasher>,
+}
+
+impl MultiHasher {
+    fn new(algorithms: &[HashAlgorithm]) -> Self {
+        let mut hasher = Self::default();
+        for algorithm in algorithms {
+            match algorithm {
+                HashAlgorithm::Md5 => hasher.md5 = Some(md5::Context::new()),
+                HashAlgorithm::Sha1 => hasher.sha1 = Some(Sha1::new()),
+                HashAlgorithm::Sha256 => hasher.sha256 = Some(Sha256::new()),
+                HashAlgorithm::Crc32 => hasher.crc32 = Some(crc32fast::Hasher::new()),
+            }
+        }
+        hasher
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        if let Some(ctx) = self.md5.as_mut() {
+            ctx.consume(data);
+        }
+        if let Some(ctx) = self.sha1.as_mut() {
+            ctx.update(data);
+        }
+        if let Some(ctx) = self.sha256.as_mut() {
+            ctx.update(data);
+        }
+        if let Some(ctx) = self.crc32.as_mut() {
+            ctx.update(data);
+        }
+    }
+
+    fn finalize(self) -> FileHashes {
+        FileHashes {
+            md5: self.md5.map(|ctx| format!("{:x}", ctx.finalize())),
+            sha1: self.sha1.map(|ctx| format!("{:x}", ctx.finalize())),
+            sha256: self.sha256.map(|ctx| format!("{:x}", ctx.finalize())),
+            crc32: self.crc32.map(|ctx| format!("{:08x}", ctx.finalize())),
+        }
+    }
+}
+
+/// 流式计算文件哈希，每读取一块回调已处理的字节数，被取消时返回 `None`
+pub fn hash_file(
+    path: &Path,
+    algorithms: &[HashAlgorithm],
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(u64),
+) -> io::Result<Option<FileHashes>> {
+    let mut file = File::open(path)?;
+    let mut hasher = MultiHasher::new(algorithms);
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    let mut processed = 0u64;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let n = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buf[..n]);
+        processed += n as u64;
+        on_progress(processed);
+    }
+    Ok(Some(hasher.finalize()))
+}
+
+/// 展开文件夹中的所有文件，不跟随符号链接指向的文件夹
+pub fn collect_files(paths: &[String]) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for path in paths {
+        let path = PathBuf::from(path);
+        if path.is_dir() {
+            collect_dir(&path, &mut files);
+        } else {
+            // 不存在的路径也保留，由计算时报告错误
+            files.push(path);
+        }
+    }
+    files
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(val) => val.flatten().map(|e| e.path()).collect(),
+        Err(_) => return,
+    };
+    entries.sort();
+    for path in entries {
+        let is_symlink = fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink());
+        if path.is_dir() {
+            if !is_symlink {
+                collect_dir(&path, files);
+            }
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChecksumEntry {
+    pub algorithm: HashAlgorithm,
+    /// 小写的十六进制摘要
+    pub expected: String,
+    pub path: PathBuf,
+}
+
+fn is_hex(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 解析 `.sha256sum`、`.md5` 等校验文件
+///
+/// 支持 `hash  name`、`hash *name`、BSD 风格的 `SHA256 (name) = hash`，
+/// 以及只有一个哈希值的文件（对应去掉校验扩展名后的同名文件）。
+/// 相对路径以校验文件所在文件夹为基准。
+pub fn parse_checksum_file(checksum_path: &Path, content: &str) -> Vec<ChecksumEntry> {
+    let base = checksum_path.parent().unwrap_or(Path::new(""));
+    let default_algorithm = checksum_path
+        .extension()
+        .and_then(|e| HashAlgorithm::from_name(&e.to_string_lossy()));
+
+    let mut entries = vec![];
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r').trim_start_matches('\u{feff}');
+        if line.trim().is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let (tag, hash, name) = match parse_bsd_line(line) {
+            Some((tag, name, hash)) => (HashAlgorithm::from_name(tag), hash, Some(name)),
+            None => {
+                let line = line.trim();
+                let hash_end = line.find(char::is_whitespace).unwrap_or(line.len());
+                let (hash, rest) = line.split_at(hash_end);
+                let name = rest
+                    .strip_prefix(" *")
+                    .or_else(|| rest.strip_prefix("  "))
+                    .unwrap_or(rest)
+                    .trim_start();
+                (None, hash, (!name.is_empty()).then_some(name))
+            }
+        };
+        if !is_hex(hash) {
+            continue;
+        }
+        let algorithm = match tag
+            .or(default_algorithm)
+            .or_else(|| HashAlgorithm::from_hex_len(hash.len()))
+        {
+            Some(val) if val.hex_len() == hash.len() => val,
+            _ => continue,
+        };
+        let path = match name {
+            Some(name) => base.join(name),
+            None => checksum_path.with_extension(""),
+        };
+        entries.push(ChecksumEntry {
+            algorithm,
+            expected: hash.to_lowercase(),
+            path,
+        });
+    }
+    entries
+}
+
+/// 解析 `TAG (name) = hash`，返回标签、文件名与哈希
+fn parse_bsd_line(line: &str) -> Option<(&str, &str, &str)> {
+    let (tag, rest) = line.split_once(" (")?;
+    let (name, hash) = rest.rsplit_once(") = ")?;
+    Some((tag.trim(), name, hash.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_file_all_algorithms() {
+        let path = std::env::temp_dir().join(format!("tool-box-hash-{}", std::process::id()));
+        fs::write(&path, b"abc").unwrap();
+
+        let mut progress = vec![];
+        let hashes = hash_file(&path, &HashAlgorithm::ALL, &AtomicBool::new(false), |n| {
+            progress.push(n)
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            hashes.md5.as_deref(),
+            Some("900150983cd24fb0d6963f7d28e17f72")
+        );
+        assert_eq!(
+            hashes.sha1.as_deref(),
+            Some("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+        assert_eq!(
+            hashes.sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(hashes.crc32.as_deref(), Some("352441c2"));
+        assert_eq!(progress, vec![3]);
+
+        let hashes = hash_file(
+            &path,
+            &[HashAlgorithm::Crc32],
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .unwrap()
+        .unwrap();
+        assert!(hashes.md5.is_none());
+        assert!(
+            hash_file(&path, &[HashAlgorithm::Md5], &AtomicBool::new(true), |_| {})
+                .unwrap()
+                .is_none()
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_checksum_formats() {
+        let sha = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let content = format!(
+            "# comment\n{sha}  a.txt\n{sha} *sub/b.bin\nnot-a-hash  c.txt\n900150983cd24fb0d6963f7d28e17f72  short.txt\n"
+        );
+        let entries = parse_checksum_file(Path::new("/data/SHA256SUMS.sha256sum"), &content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, Path::new("/data/a.txt"));
+        assert_eq!(entries[1].path, Path::new("/data/sub/b.bin"));
+        assert!(entries
+            .iter()
+            .all(|e| e.algorithm == HashAlgorithm::Sha256 && e.expected == sha));
+
+        let content = "MD5 (my file.iso) = 900150983CD24FB0D6963F7D28E17F72\n";
+        let entries = parse_checksum_file(Path::new("/data/list.txt"), content);
+        assert_eq!(entries[0].algorithm, HashAlgorithm::Md5);
+        assert_eq!(entries[0].path, Path::new("/data/my file.iso"));
+        assert_eq!(entries[0].expected, "900150983cd24fb0d6963f7d28e17f72");
+
+        let content = "900150983cd24fb0d6963f7d28e17f72\n";
+        let entries = parse_checksum_file(Path::new("/data/disk.iso.md5"), content);
+        assert_eq!(entries[0].path, Path::new("/data/disk.iso"));
+    }
+}
@@ -0,0 +1,181 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+
+/// JPEG 的 EXIF 段一般在文件开头，只读取这部分
+const MAX_HEADER_LEN: u64 = 256 * 1024;
+
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..4)? {
+            [b'I', b'I', 42, 0] => true,
+            [b'M', b'M', 0, 42] => false,
+            _ => return None,
+        };
+        Some(Self {
+            data,
+            little_endian,
+        })
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// 在 IFD 中查找标签，返回值字段所在的偏移和数量
+    fn find(&self, ifd: usize, tag: u16) -> Option<(usize, usize)> {
+        let count = self.u16(ifd)? as usize;
+        (0..count).find_map(|i| {
+            let entry = ifd + 2 + i * 12;
+            (self.u16(entry)? == tag).then_some((entry + 8, self.u32(entry + 4)? as usize))
+        })
+    }
+
+    fn ascii(&self, ifd: usize, tag: u16) -> Option<String> {
+        let (value, count) = self.find(ifd, tag)?;
+        // 超过 4 字节的值存放在偏移处
+        let start = if count > 4 {
+            self.u32(value)? as usize
+        } else {
+            value
+        };
+        let bytes = self.data.get(start..start + count)?;
+        let text = String::from_utf8_lossy(bytes);
+        Some(text.trim_end_matches('\0').trim().to_string())
+    }
+
+    fn date_taken(&self) -> Option<NaiveDateTime> {
+        let ifd0 = self.u32(4)? as usize;
+        let from_exif = self.find(ifd0, TAG_EXIF_IFD).and_then(|(value, _)| {
+            let exif_ifd = self.u32(value)? as usize;
+            self.ascii(exif_ifd, TAG_DATE_TIME_ORIGINAL)
+        });
+        let text = from_exif.or_else(|| self.ascii(ifd0, TAG_DATE_TIME))?;
+        NaiveDateTime::parse_from_str(&text, "%Y:%m:%d %H:%M:%S").ok()
+    }
+}
+
+/// 在 JPEG 的各个段中查找 APP1 Exif 段
+fn jpeg_exif(data: &[u8]) -> Option<&[u8]> {
+    let mut offset = 2;
+    while offset + 4 <= data.len() {
+        if data[offset] != 0xFF {
+            return None;
+        }
+        let marker = data[offset + 1];
+        let len = u16::from_be_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let segment = data.get(offset + 4..offset + 2 + len)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return Some(&segment[6..]);
+        }
+        // 图像数据开始后不会再有 EXIF
+        if marker == 0xDA {
+            return None;
+        }
+        offset += 2 + len;
+    }
+    None
+}
+
+fn parse(data: &[u8]) -> Option<NaiveDateTime> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        return Tiff::new(jpeg_exif(data)?)?.date_taken();
+    }
+    // TIFF 及基于 TIFF 的 RAW 格式（如 CR2、NEF、DNG）
+    Tiff::new(data)?.date_taken()
+}
+
+/// 读取照片的拍摄时间，没有 EXIF 信息时返回 None
+pub fn read_date_taken(path: &Path) -> io::Result<Option<NaiveDateTime>> {
+    let mut data = vec![];
+    File::open(path)?
+        .take(MAX_HEADER_LEN)
+        .read_to_end(&mut data)?;
+    Ok(parse(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造只包含 Exif IFD 与 DateTimeOriginal 的最小 TIFF 数据
+    fn tiff(little_endian: bool) -> Vec<u8> {
+        let u16b = |v: u16| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let u32b = |v: u32| {
+            if little_endian {
+                v.to_le_bytes()
+            } else {
+                v.to_be_bytes()
+            }
+        };
+        let mut data = vec![];
+        data.extend(if little_endian { b"II" } else { b"MM" });
+        data.extend(u16b(42));
+        data.extend(u32b(8));
+        // IFD0：一个指向 Exif IFD 的条目，位于偏移 26
+        data.extend(u16b(1));
+        data.extend(u16b(TAG_EXIF_IFD));
+        data.extend(u16b(4));
+        data.extend(u32b(1));
+        data.extend(u32b(26));
+        data.extend(u32b(0));
+        // Exif IFD：DateTimeOriginal，字符串位于偏移 44
+        data.extend(u16b(1));
+        data.extend(u16b(TAG_DATE_TIME_ORIGINAL));
+        data.extend(u16b(2));
+        data.extend(u32b(20));
+        data.extend(u32b(44));
+        data.extend(u32b(0));
+        data.extend(b"2023:07:15 08:30:00\0");
+        data
+    }
+
+    #[test]
+    fn test_parse_exif_date() {
+        let expected =
+            NaiveDateTime::parse_from_str("2023-07-15 08:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(parse(&tiff(true)), Some(expected));
+        assert_eq!(parse(&tiff(false)), Some(expected));
+
+        let exif = tiff(true);
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend(((exif.len() + 8) as u16).to_be_bytes());
+        jpeg.extend(b"Exif\0\0");
+        jpeg.extend(&exif);
+        jpeg.extend([0xFF, 0xD9]);
+        assert_eq!(parse(&jpeg), Some(expected));
+
+        assert_eq!(parse(b"not an image"), None);
+    }
+}
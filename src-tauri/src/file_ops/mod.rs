@@ -0,0 +1,1003 @@
+mod automation;
+mod checksum;
+mod exif;
+mod ops;
+mod rename;
+mod sync;
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    plugin::{Builder, TauriPlugin},
+    Manager, State,
+};
+
+use crate::utils::output::{Message, MessageSender};
+use crate::utils::trash::restore_from_trash;
+
+use automation::{FolderRule, FolderRules, RuleLogEntry, RuleTestItem};
+use checksum::{FileHashes, HashAlgorithm};
+use ops::{ConflictPolicy, FileAction, ItemStatus, OperationItem};
+use rename::{BatchRenamePayload, RenameBatch, RenameHistory, RenamePreview};
+use sync::{CompareEntry, CompareMode, SyncPlanItem};
+
+static FILE_OPERATION_EVENT: &str = "file-operation-progress";
+static FILE_HASH_EVENT: &str = "file-hash-progress";
+static DIR_SYNC_EVENT: &str = "dir-sync-progress";
+
+/// 最多保留的可撤销操作数量
+const MAX_UNDO_RECORDS: usize = 20;
+/// 超过该大小的文件在计算过程中推送进度
+const HASH_PROGRESS_MIN_SIZE: u64 = 16 * 1024 * 1024;
+const HASH_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationPayload {
+    action: FileAction,
+    paths: Vec<String>,
+    /// 移动、复制的目标文件夹
+    target_dir: Option<String>,
+    #[serde(default)]
+    conflict: ConflictPolicy,
+    /// 可由前端指定，用于过滤进度事件
+    operation_id: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationReport {
+    operation_id: String,
+    action: FileAction,
+    items: Vec<OperationItem>,
+    /// 回收站与移动操作可撤销
+    undoable: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct OperationProgress {
+    operation_id: String,
+    /// 从 1 开始
+    index: usize,
+    total: usize,
+    item: OperationItem,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoRecord {
+    operation_id: String,
+    action: FileAction,
+    /// 毫秒时间戳
+    executed_at: i64,
+    /// 原位置与操作后的位置，移到回收站时没有后者
+    items: Vec<(String, Option<String>)>,
+}
+
+/// 可撤销的操作记录，只保存在内存中
+#[derive(Default)]
+pub struct UndoLog {
+    records: Mutex<VecDeque<UndoRecord>>,
+}
+
+impl UndoLog {
+    fn push(&self, record: UndoRecord) {
+        if let Ok(mut records) = self.records.lock() {
+            records.push_back(record);
+            while records.len() > MAX_UNDO_RECORDS {
+                records.pop_front();
+            }
+        }
+    }
+
+    /// 未指定 ID 时取出最近的一条
+    fn take(&self, operation_id: Option<&str>) -> Option<UndoRecord> {
+        let mut records = self.records.lock().ok()?;
+        let index = match operation_id {
+            Some(id) => records.iter().position(|r| r.operation_id == id)?,
+            None => records.len().checked_sub(1)?,
+        };
+        records.remove(index)
+    }
+
+    fn list(&self) -> Vec<UndoRecord> {
+        self.records
+            .lock()
+            .map(|r| r.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashFilesPayload {
+    /// 文件或文件夹，文件夹会递归计算其中所有文件
+    paths: Vec<String>,
+    /// 为空时计算全部算法
+    #[serde(default)]
+    algorithms: Vec<HashAlgorithm>,
+    /// 可由前端指定，用于过滤进度事件与取消
+    task_id: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HashItem {
+    path: String,
+    size: u64,
+    #[serde(flatten)]
+    hashes: FileHashes,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HashReport {
+    task_id: String,
+    items: Vec<HashItem>,
+    cancelled: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum VerifyStatus {
+    Ok,
+    Mismatch,
+    Missing,
+    Error,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyItem {
+    path: String,
+    algorithm: HashAlgorithm,
+    expected: String,
+    actual: Option<String>,
+    status: VerifyStatus,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    task_id: String,
+    checksum_file: String,
+    items: Vec<VerifyItem>,
+    cancelled: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct HashProgress {
+    task_id: String,
+    path: String,
+    /// 从 1 开始
+    index: usize,
+    total: usize,
+    /// 当前文件已处理的字节数
+    processed: u64,
+    size: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirSyncPayload {
+    /// 同步的源文件夹
+    left: String,
+    /// 同步的目标文件夹
+    right: String,
+    #[serde(default)]
+    mode: CompareMode,
+    /// 将目标中多余的项目移到回收站
+    #[serde(default)]
+    delete_extra: bool,
+    /// 可由前端指定，用于过滤进度事件与取消
+    task_id: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DirCompareReport {
+    task_id: String,
+    entries: Vec<CompareEntry>,
+    /// 按当前比较结果生成的同步计划，不会执行
+    plan: Vec<SyncPlanItem>,
+    cancelled: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncItem {
+    #[serde(flatten)]
+    item: SyncPlanItem,
+    status: ItemStatus,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DirSyncReport {
+    task_id: String,
+    items: Vec<SyncItem>,
+    cancelled: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct DirSyncProgress {
+    task_id: String,
+    /// 从 1 开始
+    index: usize,
+    total: usize,
+    item: SyncItem,
+}
+
+/// 正在运行的哈希与目录同步任务，用于取消
+#[derive(Default)]
+pub struct RunningTasks {
+    tasks: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl RunningTasks {
+    fn register(&self, task_id: &str) -> Option<Arc<AtomicBool>> {
+        let mut tasks = self.tasks.lock().ok()?;
+        if tasks.contains_key(task_id) {
+            return None;
+        }
+        let flag = Arc::new(AtomicBool::new(false));
+        tasks.insert(task_id.to_string(), flag.clone());
+        Some(flag)
+    }
+
+    fn remove(&self, task_id: &str) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.remove(task_id);
+        }
+    }
+
+    fn cancel(&self, task_id: &str) -> bool {
+        match self.tasks.lock().ok().and_then(|t| t.get(task_id).cloned()) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 单个任务中共享的进度推送
+struct HashJob {
+    task_id: String,
+    sender: MessageSender,
+    cancelled: Arc<AtomicBool>,
+    total: usize,
+}
+
+impl HashJob {
+    /// 计算单个文件，大文件按间隔推送进度，结束时推送一次
+    fn hash(
+        &self,
+        index: usize,
+        path: &Path,
+        algorithms: &[HashAlgorithm],
+    ) -> (u64, Result<Option<FileHashes>, String>) {
+        let path_str = path.to_string_lossy().to_string();
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let progress = |processed: u64| HashProgress {
+            task_id: self.task_id.clone(),
+            path: path_str.clone(),
+            index: index + 1,
+            total: self.total,
+            processed,
+            size,
+        };
+
+        let mut last_emit = Instant::now();
+        let result = checksum::hash_file(path, algorithms, &self.cancelled, |processed| {
+            if size >= HASH_PROGRESS_MIN_SIZE && last_emit.elapsed() >= HASH_PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                self.sender
+                    .send(FILE_HASH_EVENT, progress(processed), false);
+            }
+        })
+        .map_err(|e| e.to_string());
+        if let Ok(Some(_)) | Err(_) = result {
+            self.sender.send(FILE_HASH_EVENT, progress(size), false);
+        }
+        (size, result)
+    }
+}
+
+fn generate_operation_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", chrono::Local::now().timestamp_millis(), seq)
+}
+
+/// 逐个处理并推送进度，回收站与移动操作记录到撤销列表
+#[tauri::command]
+pub async fn run_file_operation(
+    payload: FileOperationPayload,
+    undo_log: State<'_, UndoLog>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<OperationReport>, String> {
+    if payload.paths.is_empty() {
+        return Ok(Message::failure("请选择需要处理的文件"));
+    }
+    let target_dir = match payload.target_dir.as_deref() {
+        Some(dir) => match std::fs::canonicalize(dir) {
+            Ok(val) if val.is_dir() => Some(val),
+            _ => return Ok(Message::failure("目标文件夹不存在")),
+        },
+        None => None,
+    };
+    if matches!(payload.action, FileAction::Move | FileAction::Copy) && target_dir.is_none() {
+        return Ok(Message::failure("请选择目标文件夹"));
+    }
+
+    let operation_id = payload
+        .operation_id
+        .clone()
+        .unwrap_or_else(generate_operation_id);
+    let executed_at = chrono::Local::now().timestamp_millis();
+    let sender = MessageSender::new(app_handle, "file-ops");
+    let action = payload.action;
+    let id = operation_id.clone();
+    let items = tauri::async_runtime::spawn_blocking(move || {
+        let total = payload.paths.len();
+        let mut items = vec![];
+        for (index, path) in payload.paths.iter().enumerate() {
+            let item = ops::run_item(action, path, target_dir.as_deref(), payload.conflict);
+            let progress = OperationProgress {
+                operation_id: id.clone(),
+                index: index + 1,
+                total,
+                item: item.clone(),
+            };
+            sender.send(FILE_OPERATION_EVENT, progress, false);
+            items.push(item);
+        }
+        items
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let undoable = matches!(action, FileAction::Trash | FileAction::Move);
+    let done: Vec<(String, Option<String>)> = items
+        .iter()
+        .filter(|item| item.status == ItemStatus::Done)
+        .map(|item| (item.path.clone(), item.target.clone()))
+        .collect();
+    if undoable && !done.is_empty() {
+        undo_log.push(UndoRecord {
+            operation_id: operation_id.clone(),
+            action,
+            executed_at,
+            items: done,
+        });
+    }
+
+    Ok(Message::success(Some(OperationReport {
+        operation_id,
+        action,
+        items,
+        undoable,
+    })))
+}
+
+/// 撤销回收站或移动操作，不传 ID 时撤销最近一次
+#[tauri::command]
+pub async fn undo_file_operation(
+    operation_id: Option<String>,
+    undo_log: State<'_, UndoLog>,
+) -> Result<Message<OperationReport>, String> {
+    let record = match undo_log.take(operation_id.as_deref()) {
+        Some(val) => val,
+        None => return Ok(Message::failure("没有可撤销的操作")),
+    };
+
+    let items = tauri::async_runtime::spawn_blocking({
+        let record = record.clone();
+        move || undo(&record)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(Message::success(Some(OperationReport {
+        operation_id: record.operation_id,
+        action: record.action,
+        items,
+        undoable: false,
+    })))
+}
+
+fn undo(record: &UndoRecord) -> Vec<OperationItem> {
+    match record.action {
+        FileAction::Trash => {
+            let paths: Vec<PathBuf> = record.items.iter().map(|(p, _)| p.into()).collect();
+            let result = restore_from_trash(&paths, record.executed_at / 1000);
+            record
+                .items
+                .iter()
+                .map(|(path, _)| {
+                    let restored = Path::new(path).exists();
+                    OperationItem {
+                        path: path.clone(),
+                        target: None,
+                        status: if restored {
+                            ItemStatus::Done
+                        } else {
+                            ItemStatus::Failed
+                        },
+                        error: match (&result, restored) {
+                            (_, true) => None,
+                            (Err(e), false) => Some(e.to_string()),
+                            (Ok(_), false) => Some(String::from("回收站中找不到该文件")),
+                        },
+                    }
+                })
+                .collect()
+        }
+        _ => record
+            .items
+            .iter()
+            .filter_map(|(path, target)| {
+                let target = target.as_ref()?;
+                let mut item = OperationItem {
+                    path: target.clone(),
+                    target: Some(path.clone()),
+                    status: ItemStatus::Done,
+                    error: None,
+                };
+                let result = if Path::new(path).exists() {
+                    Err(String::from("原位置已存在同名文件"))
+                } else {
+                    ops::move_path(Path::new(target), Path::new(path)).map_err(|e| e.to_string())
+                };
+                if let Err(e) = result {
+                    item.status = ItemStatus::Failed;
+                    item.error = Some(e);
+                }
+                Some(item)
+            })
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn list_file_operations(
+    undo_log: State<'_, UndoLog>,
+) -> Result<Message<Vec<UndoRecord>>, String> {
+    Ok(Message::success(Some(undo_log.list())))
+}
+
+/// 预览批量重命名的结果并检查冲突，不修改文件
+#[tauri::command]
+pub async fn preview_batch_rename(
+    payload: BatchRenamePayload,
+) -> Result<Message<RenamePreview>, String> {
+    let result = tauri::async_runtime::spawn_blocking(move || rename::preview(&payload))
+        .await
+        .map_err(|e| e.to_string())?;
+    match result {
+        Ok(preview) => Ok(Message::success(Some(preview))),
+        Err(e) => Ok(Message::failure(&e.to_string())),
+    }
+}
+
+/// 存在冲突时不执行；执行中任意文件失败时全部还原，成功后记录到历史用于撤销
+#[tauri::command]
+pub async fn apply_batch_rename(
+    payload: BatchRenamePayload,
+    history: State<'_, RenameHistory>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<RenameBatch>, String> {
+    let id = generate_operation_id();
+    let batch_id = id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let preview = rename::preview(&payload)?;
+        if preview.conflict_count > 0 {
+            return Err(anyhow::Error::msg(format!(
+                "有 {} 个文件存在冲突，请先预览并处理",
+                preview.conflict_count
+            )));
+        }
+        let pairs: Vec<(String, String)> = preview
+            .items
+            .into_iter()
+            .filter(|item| item.changed)
+            .map(|item| (item.path, item.new_path))
+            .collect();
+        rename::rename_all(&pairs, &batch_id)?;
+        Ok(pairs)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let pairs = match result {
+        Ok(val) => val,
+        Err(e) => return Ok(Message::failure(&e.to_string())),
+    };
+    let batch = RenameBatch {
+        id,
+        executed_at: chrono::Local::now().timestamp_millis(),
+        items: pairs,
+    };
+    if !batch.items.is_empty() {
+        history.push(batch.clone()).await;
+        if let Err(e) = history.save(&app_handle).await {
+            println!("[file-ops] 保存重命名记录失败：{}", e);
+        }
+    }
+    Ok(Message::success(Some(batch)))
+}
+
+/// 撤销批量重命名，不传 ID 时撤销最近一次；失败时保留该记录
+#[tauri::command]
+pub async fn undo_batch_rename(
+    batch_id: Option<String>,
+    history: State<'_, RenameHistory>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    let batch = match history.take(batch_id.as_deref()).await {
+        Some(val) => val,
+        None => return Ok(Message::failure("没有可撤销的重命名")),
+    };
+
+    let reversed: Vec<(String, String)> = batch
+        .items
+        .iter()
+        .map(|(from, to)| (to.clone(), from.clone()))
+        .collect();
+    let undo_id = generate_operation_id();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || rename::rename_all(&reversed, &undo_id))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    if let Err(e) = result {
+        history.push(batch).await;
+        return Ok(Message::failure(&format!("撤销失败：{}", e)));
+    }
+    if let Err(e) = history.save(&app_handle).await {
+        println!("[file-ops] 保存重命名记录失败：{}", e);
+    }
+    Ok(Message::success(Some(String::from("已撤销"))))
+}
+
+#[tauri::command]
+pub async fn list_rename_history(
+    history: State<'_, RenameHistory>,
+) -> Result<Message<Vec<RenameBatch>>, String> {
+    Ok(Message::success(Some(history.list().await)))
+}
+
+/// 在系统文件管理器中显示，Linux 下打开所在文件夹
+#[tauri::command]
+pub async fn reveal_in_folder(path: String) -> Result<Message<String>, String> {
+    let target = Path::new(&path);
+    if !target.exists() {
+        return Ok(Message::failure("文件不存在"));
+    }
+
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("explorer")
+        .arg(format!("/select,{}", path))
+        .spawn();
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open")
+        .arg("-R")
+        .arg(&path)
+        .spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = {
+        let dir = if target.is_dir() {
+            target
+        } else {
+            target.parent().unwrap_or(target)
+        };
+        std::process::Command::new("xdg-open").arg(dir).spawn()
+    };
+
+    match result {
+        Ok(_) => Ok(Message::success(Some(String::from("已打开")))),
+        Err(e) => Ok(Message::failure(&format!("打开文件夹失败：{}", e))),
+    }
+}
+
+/// 一次读取同时计算多种哈希，文件夹会递归展开，进度通过 `file-hash-progress` 事件推送
+#[tauri::command]
+pub async fn hash_files(
+    payload: HashFilesPayload,
+    running_tasks: State<'_, RunningTasks>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<HashReport>, String> {
+    if payload.paths.is_empty() {
+        return Ok(Message::failure("请选择需要计算的文件"));
+    }
+    let task_id = payload.task_id.unwrap_or_else(generate_operation_id);
+    let cancelled = match running_tasks.register(&task_id) {
+        Some(val) => val,
+        None => return Ok(Message::failure("该任务正在运行")),
+    };
+    let algorithms = if payload.algorithms.is_empty() {
+        HashAlgorithm::ALL.to_vec()
+    } else {
+        payload.algorithms
+    };
+
+    let paths = payload.paths;
+    let sender = MessageSender::new(app_handle, "file-ops");
+    let id = task_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let files = checksum::collect_files(&paths);
+        let job = HashJob {
+            task_id: id,
+            sender,
+            cancelled,
+            total: files.len(),
+        };
+        let mut items = vec![];
+        for (index, path) in files.iter().enumerate() {
+            let (size, result) = job.hash(index, path, &algorithms);
+            let (hashes, error) = match result {
+                Ok(Some(val)) => (val, None),
+                Ok(None) => break,
+                Err(e) => (FileHashes::default(), Some(e)),
+            };
+            items.push(HashItem {
+                path: path.to_string_lossy().to_string(),
+                size,
+                hashes,
+                error,
+            });
+        }
+        (items, job.cancelled.load(Ordering::Relaxed))
+    })
+    .await;
+    running_tasks.remove(&task_id);
+
+    let (items, cancelled) = result.map_err(|e| e.to_string())?;
+    Ok(Message::success(Some(HashReport {
+        task_id,
+        items,
+        cancelled,
+    })))
+}
+
+/// 按 `.sha256sum`、`.md5` 等校验文件逐个核对文件
+#[tauri::command]
+pub async fn verify_checksum_file(
+    path: String,
+    task_id: Option<String>,
+    running_tasks: State<'_, RunningTasks>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<VerifyReport>, String> {
+    let content = match tokio::fs::read(&path).await {
+        Ok(val) => String::from_utf8_lossy(&val).to_string(),
+        Err(_) => return Ok(Message::failure("无法读取校验文件")),
+    };
+    let entries = checksum::parse_checksum_file(Path::new(&path), &content);
+    if entries.is_empty() {
+        return Ok(Message::failure("校验文件中没有可识别的哈希值"));
+    }
+    let task_id = task_id.unwrap_or_else(generate_operation_id);
+    let cancelled = match running_tasks.register(&task_id) {
+        Some(val) => val,
+        None => return Ok(Message::failure("该任务正在运行")),
+    };
+
+    let sender = MessageSender::new(app_handle, "file-ops");
+    let id = task_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let job = HashJob {
+            task_id: id,
+            sender,
+            cancelled,
+            total: entries.len(),
+        };
+        let mut items = vec![];
+        for (index, entry) in entries.into_iter().enumerate() {
+            let mut item = VerifyItem {
+                path: entry.path.to_string_lossy().to_string(),
+                algorithm: entry.algorithm,
+                expected: entry.expected,
+                actual: None,
+                status: VerifyStatus::Missing,
+                error: None,
+            };
+            if entry.path.is_file() {
+                match job.hash(index, &entry.path, &[entry.algorithm]).1 {
+                    Ok(Some(hashes)) => {
+                        item.actual = hashes.get(entry.algorithm).map(String::from);
+                        item.status = if item.actual.as_deref() == Some(item.expected.as_str()) {
+                            VerifyStatus::Ok
+                        } else {
+                            VerifyStatus::Mismatch
+                        };
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        item.status = VerifyStatus::Error;
+                        item.error = Some(e);
+                    }
+                }
+            }
+            items.push(item);
+        }
+        (items, job.cancelled.load(Ordering::Relaxed))
+    })
+    .await;
+    running_tasks.remove(&task_id);
+
+    let (items, cancelled) = result.map_err(|e| e.to_string())?;
+    Ok(Message::success(Some(VerifyReport {
+        task_id,
+        checksum_file: path,
+        items,
+        cancelled,
+    })))
+}
+
+#[tauri::command]
+pub async fn cancel_hash_task(
+    task_id: String,
+    running_tasks: State<'_, RunningTasks>,
+) -> Result<Message<String>, String> {
+    if !running_tasks.cancel(&task_id) {
+        return Ok(Message::failure("任务不存在或已结束"));
+    }
+    Ok(Message::success(Some(String::from("停止成功"))))
+}
+
+/// 检查同步的两个文件夹，返回规范化后的路径
+fn resolve_sync_dirs(payload: &DirSyncPayload) -> Result<(PathBuf, PathBuf), &'static str> {
+    let left = match std::fs::canonicalize(&payload.left) {
+        Ok(val) if val.is_dir() => val,
+        _ => return Err("源文件夹不存在"),
+    };
+    let right = match std::fs::canonicalize(&payload.right) {
+        Ok(val) if val.is_dir() => val,
+        _ => return Err("目标文件夹不存在"),
+    };
+    if left.starts_with(&right) || right.starts_with(&left) {
+        return Err("两个文件夹不能相同或互相包含");
+    }
+    Ok((left, right))
+}
+
+/// 比较两个文件夹并生成从左到右的同步计划，不修改文件
+#[tauri::command]
+pub async fn compare_directories(
+    payload: DirSyncPayload,
+    running_tasks: State<'_, RunningTasks>,
+) -> Result<Message<DirCompareReport>, String> {
+    let (left, right) = match resolve_sync_dirs(&payload) {
+        Ok(val) => val,
+        Err(e) => return Ok(Message::failure(e)),
+    };
+    let task_id = payload.task_id.unwrap_or_else(generate_operation_id);
+    let cancelled = match running_tasks.register(&task_id) {
+        Some(val) => val,
+        None => return Ok(Message::failure("该任务正在运行")),
+    };
+
+    let (mode, delete_extra) = (payload.mode, payload.delete_extra);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        sync::compare_dirs(&left, &right, mode, &cancelled)
+    })
+    .await;
+    running_tasks.remove(&task_id);
+
+    let report = match result {
+        Ok(Ok(Some(entries))) => DirCompareReport {
+            task_id,
+            plan: sync::build_plan(&entries, delete_extra),
+            entries,
+            cancelled: false,
+        },
+        Ok(Ok(None)) => DirCompareReport {
+            task_id,
+            entries: vec![],
+            plan: vec![],
+            cancelled: true,
+        },
+        Ok(Err(e)) => return Ok(Message::failure(&format!("比较失败：{}", e))),
+        Err(e) => return Err(e.to_string()),
+    };
+    Ok(Message::success(Some(report)))
+}
+
+/// 重新比较后执行同步计划，进度通过 `dir-sync-progress` 事件推送
+#[tauri::command]
+pub async fn sync_directories(
+    payload: DirSyncPayload,
+    running_tasks: State<'_, RunningTasks>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<DirSyncReport>, String> {
+    let (left, right) = match resolve_sync_dirs(&payload) {
+        Ok(val) => val,
+        Err(e) => return Ok(Message::failure(e)),
+    };
+    let task_id = payload.task_id.unwrap_or_else(generate_operation_id);
+    let cancelled = match running_tasks.register(&task_id) {
+        Some(val) => val,
+        None => return Ok(Message::failure("该任务正在运行")),
+    };
+
+    let (mode, delete_extra) = (payload.mode, payload.delete_extra);
+    let sender = MessageSender::new(app_handle, "file-ops");
+    let id = task_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let entries = match sync::compare_dirs(&left, &right, mode, &cancelled)? {
+            Some(val) => val,
+            None => return Ok((vec![], true)),
+        };
+        let plan = sync::build_plan(&entries, delete_extra);
+        let total = plan.len();
+        let mut items = vec![];
+        for (index, item) in plan.into_iter().enumerate() {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let result = sync::execute_item(&item, &left, &right);
+            let item = SyncItem {
+                item,
+                status: if result.is_ok() {
+                    ItemStatus::Done
+                } else {
+                    ItemStatus::Failed
+                },
+                error: result.err(),
+            };
+            let progress = DirSyncProgress {
+                task_id: id.clone(),
+                index: index + 1,
+                total,
+                item: item.clone(),
+            };
+            sender.send(DIR_SYNC_EVENT, progress, false);
+            items.push(item);
+        }
+        Ok::<_, std::io::Error>((items, cancelled.load(Ordering::Relaxed)))
+    })
+    .await;
+    running_tasks.remove(&task_id);
+
+    match result {
+        Ok(Ok((items, cancelled))) => Ok(Message::success(Some(DirSyncReport {
+            task_id,
+            items,
+            cancelled,
+        }))),
+        Ok(Err(e)) => Ok(Message::failure(&format!("比较失败：{}", e))),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_dir_sync(
+    task_id: String,
+    running_tasks: State<'_, RunningTasks>,
+) -> Result<Message<String>, String> {
+    if !running_tasks.cancel(&task_id) {
+        return Ok(Message::failure("任务不存在或已结束"));
+    }
+    Ok(Message::success(Some(String::from("停止成功"))))
+}
+
+#[tauri::command]
+pub async fn list_folder_rules(
+    folder_rules: State<'_, Arc<FolderRules>>,
+) -> Result<Message<Vec<FolderRule>>, String> {
+    Ok(Message::success(Some(folder_rules.list())))
+}
+
+/// 保存文件夹监听规则，保存后会立即检查文件夹中已有的文件
+#[tauri::command]
+pub async fn save_folder_rule(
+    rule: FolderRule,
+    folder_rules: State<'_, Arc<FolderRules>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<FolderRule>, String> {
+    let mut rule = rule;
+    if let Err(e) = automation::validate_rule(&rule) {
+        return Ok(Message::failure(&e.to_string()));
+    }
+    if rule.id.is_empty() {
+        rule.id = generate_operation_id();
+    }
+    rule.name = rule.name.trim().to_string();
+    folder_rules.upsert(rule.clone());
+    folder_rules.restart_watcher();
+    match folder_rules.save_rules(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(rule))),
+        Err(e) => Ok(Message::failure(&format!("保存规则失败：{}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_folder_rule(
+    id: String,
+    folder_rules: State<'_, Arc<FolderRules>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    if !folder_rules.remove(&id) {
+        return Ok(Message::failure("规则不存在"));
+    }
+    folder_rules.restart_watcher();
+    match folder_rules.save_rules(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("删除成功")))),
+        Err(e) => Ok(Message::failure(&format!("保存规则失败：{}", e))),
+    }
+}
+
+/// 对规则监听的已有文件试运行，只返回预期结果，不修改文件
+#[tauri::command]
+pub async fn test_folder_rule(rule: FolderRule) -> Result<Message<Vec<RuleTestItem>>, String> {
+    if let Err(e) = automation::validate_rule(&rule) {
+        return Ok(Message::failure(&e.to_string()));
+    }
+    match tauri::async_runtime::spawn_blocking(move || automation::test_rule(rule)).await {
+        Ok(Ok(items)) => Ok(Message::success(Some(items))),
+        Ok(Err(e)) => Ok(Message::failure(&e.to_string())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 获取规则的操作记录，最近的在前
+#[tauri::command]
+pub async fn list_folder_rule_log(
+    folder_rules: State<'_, Arc<FolderRules>>,
+) -> Result<Message<Vec<RuleLogEntry>>, String> {
+    Ok(Message::success(Some(folder_rules.log())))
+}
+
+#[tauri::command]
+pub async fn clear_folder_rule_log(
+    folder_rules: State<'_, Arc<FolderRules>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    folder_rules.clear_log();
+    match folder_rules.save_state(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("清除成功")))),
+        Err(e) => Ok(Message::failure(&format!("保存记录失败：{}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn check_file_exists(file_path: String) -> bool {
+    tokio::fs::metadata(file_path).await.is_ok()
+}
+
+#[tauri::command]
+pub async fn delete_file(file_path: String) -> bool {
+    tokio::fs::remove_file(file_path).await.is_ok()
+}
+
+pub fn init() -> TauriPlugin<tauri::Wry> {
+    println!("file-ops plugin init");
+
+    Builder::new("file-ops")
+        .setup(|app, _| {
+            app.manage(UndoLog::default());
+            app.manage(RenameHistory::default());
+            app.manage(RunningTasks::default());
+            app.manage(Arc::new(FolderRules::default()));
+            automation::start(app.clone());
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                handle.state::<RenameHistory>().load(&handle).await;
+            });
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,353 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::trash::move_to_trash;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FileAction {
+    Delete,
+    Trash,
+    Move,
+    Copy,
+}
+
+/// 目标位置已存在同名文件时的处理方式
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    #[default]
+    Skip,
+    Overwrite,
+    /// 自动重命名为 `name (1).ext`
+    Rename,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemStatus {
+    Done,
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationItem {
+    pub path: String,
+    /// 移动、复制后的位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub status: ItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OperationItem {
+    fn failed(path: &str, error: String) -> Self {
+        Self {
+            path: path.to_string(),
+            target: None,
+            status: ItemStatus::Failed,
+            error: Some(error),
+        }
+    }
+}
+
+/// 在名称后追加序号，直到找到不存在的路径
+pub fn unique_path(path: &Path) -> PathBuf {
+    if fs::symlink_metadata(path).is_err() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut index = 1;
+    loop {
+        let candidate = path.with_file_name(format!("{} ({}){}", stem, index, ext));
+        if fs::symlink_metadata(&candidate).is_err() {
+            return candidate;
+        }
+        index += 1;
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// 复制符号链接本身，不复制其指向的内容
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_link(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+pub fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if metadata.is_symlink() {
+        copy_link(from, to)
+    } else if metadata.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// 优先重命名，跨分区时复制后删除源文件
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_recursive(from, to) {
+        let _ = remove_path(to);
+        return Err(e);
+    }
+    if let Err(e) = remove_path(from) {
+        // 源文件删除失败时撤回复制，避免出现两份
+        let _ = remove_path(to);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// 冲突处理后的目标位置，覆盖时已有文件先改名备份，操作失败后还原
+struct Target {
+    path: PathBuf,
+    backup: Option<PathBuf>,
+}
+
+/// 根据冲突策略计算目标路径，需要跳过时返回 None
+fn resolve_target(
+    source: &Path,
+    target_dir: &Path,
+    conflict: ConflictPolicy,
+) -> io::Result<Option<Target>> {
+    let name = source
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的文件路径"))?;
+    let target = target_dir.join(name);
+    if target == source {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "目标位置与源文件相同",
+        ));
+    }
+    let is_dir = fs::symlink_metadata(source).is_ok_and(|m| m.is_dir());
+    if is_dir && target.starts_with(source) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "不能移动或复制到自身的子目录",
+        ));
+    }
+    if fs::symlink_metadata(&target).is_err() {
+        return Ok(Some(Target {
+            path: target,
+            backup: None,
+        }));
+    }
+    match conflict {
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Rename => Ok(Some(Target {
+            path: unique_path(&target),
+            backup: None,
+        })),
+        ConflictPolicy::Overwrite => {
+            let backup = unique_path(
+                &target.with_file_name(format!(".{}.replaced", name.to_string_lossy())),
+            );
+            fs::rename(&target, &backup)?;
+            Ok(Some(Target {
+                path: target,
+                backup: Some(backup),
+            }))
+        }
+    }
+}
+
+/// 只规范化所在文件夹，选中的是符号链接时操作链接本身而不是其指向的文件
+fn normalize_path(path: &Path) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的文件路径"))?;
+    let parent = match path.parent() {
+        Some(val) if !val.as_os_str().is_empty() => fs::canonicalize(val)?,
+        _ => std::env::current_dir()?,
+    };
+    let normalized = parent.join(name);
+    fs::symlink_metadata(&normalized)?;
+    Ok(normalized)
+}
+
+/// 阻塞执行单个文件的操作
+pub fn run_item(
+    action: FileAction,
+    path: &str,
+    target_dir: Option<&Path>,
+    conflict: ConflictPolicy,
+) -> OperationItem {
+    let source = match normalize_path(Path::new(path)) {
+        Ok(val) => val,
+        Err(e) => return OperationItem::failed(path, e.to_string()),
+    };
+    let mut item = OperationItem {
+        path: source.to_string_lossy().to_string(),
+        target: None,
+        status: ItemStatus::Done,
+        error: None,
+    };
+
+    let result = match action {
+        FileAction::Delete => remove_path(&source).map_err(|e| e.to_string()),
+        FileAction::Trash => move_to_trash(&source).map_err(|e| e.to_string()),
+        FileAction::Move | FileAction::Copy => {
+            let target_dir = match target_dir {
+                Some(val) => val,
+                None => return OperationItem::failed(path, String::from("请选择目标文件夹")),
+            };
+            match resolve_target(&source, target_dir, conflict) {
+                Ok(Some(target)) => {
+                    let result = if action == FileAction::Move {
+                        move_path(&source, &target.path)
+                    } else {
+                        copy_recursive(&source, &target.path)
+                    };
+                    if let Some(backup) = &target.backup {
+                        if result.is_ok() {
+                            let _ = remove_path(backup);
+                        } else {
+                            let _ = remove_path(&target.path);
+                            let _ = fs::rename(backup, &target.path);
+                        }
+                    }
+                    item.target = Some(target.path.to_string_lossy().to_string());
+                    result.map_err(|e| e.to_string())
+                }
+                Ok(None) => {
+                    item.status = ItemStatus::Skipped;
+                    Ok(())
+                }
+                Err(e) => Err(e.to_string()),
+            }
+        }
+    };
+
+    if let Err(e) = result {
+        item.status = ItemStatus::Failed;
+        item.error = Some(e);
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_copy_with_conflicts() {
+        let root = std::env::temp_dir().join(format!("tool-box-ops-{}", std::process::id()));
+        let target = root.join("target");
+        fs::create_dir_all(root.join("src/sub")).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(root.join("src/a.txt"), b"new").unwrap();
+        fs::write(root.join("src/sub/b.txt"), b"b").unwrap();
+        fs::write(target.join("a.txt"), b"old").unwrap();
+
+        let a = root.join("src/a.txt").to_string_lossy().to_string();
+        let item = run_item(FileAction::Copy, &a, Some(&target), ConflictPolicy::Skip);
+        assert_eq!(item.status, ItemStatus::Skipped);
+
+        let item = run_item(FileAction::Copy, &a, Some(&target), ConflictPolicy::Rename);
+        assert_eq!(item.status, ItemStatus::Done);
+        assert!(item.target.unwrap().ends_with("a (1).txt"));
+
+        let item = run_item(
+            FileAction::Move,
+            &a,
+            Some(&target),
+            ConflictPolicy::Overwrite,
+        );
+        assert_eq!(item.status, ItemStatus::Done);
+        assert_eq!(fs::read(target.join("a.txt")).unwrap(), b"new");
+        assert!(!Path::new(&a).exists());
+
+        let sub = root.join("src/sub").to_string_lossy().to_string();
+        let item = run_item(FileAction::Move, &sub, Some(&target), ConflictPolicy::Skip);
+        assert_eq!(item.status, ItemStatus::Done);
+        assert!(target.join("sub/b.txt").exists());
+
+        // 选中符号链接时只操作链接本身
+        #[cfg(unix)]
+        {
+            let linked = root.join("linked");
+            fs::create_dir_all(&linked).unwrap();
+            fs::write(linked.join("keep.txt"), b"keep").unwrap();
+            let link = root.join("src/link");
+            std::os::unix::fs::symlink(&linked, &link).unwrap();
+            let link_str = link.to_string_lossy().to_string();
+
+            let item = run_item(
+                FileAction::Copy,
+                &link_str,
+                Some(&target),
+                ConflictPolicy::Skip,
+            );
+            assert_eq!(item.status, ItemStatus::Done);
+            assert!(fs::symlink_metadata(target.join("link"))
+                .unwrap()
+                .is_symlink());
+
+            let item = run_item(
+                FileAction::Move,
+                &link_str,
+                Some(&target),
+                ConflictPolicy::Rename,
+            );
+            assert_eq!(item.status, ItemStatus::Done);
+            let moved = PathBuf::from(item.target.unwrap());
+            assert!(fs::symlink_metadata(&moved).unwrap().is_symlink());
+            assert!(fs::symlink_metadata(&link).is_err());
+
+            let moved_str = moved.to_string_lossy().to_string();
+            let item = run_item(FileAction::Delete, &moved_str, None, ConflictPolicy::Skip);
+            assert_eq!(item.status, ItemStatus::Done);
+            assert!(fs::symlink_metadata(&moved).is_err());
+            assert!(linked.join("keep.txt").is_file());
+        }
+
+        let src = root.join("src");
+        let inner = src.join("inner");
+        fs::create_dir_all(&inner).unwrap();
+        let src_str = src.to_string_lossy().to_string();
+        let item = run_item(
+            FileAction::Copy,
+            &src_str,
+            Some(&inner),
+            ConflictPolicy::Skip,
+        );
+        assert_eq!(item.status, ItemStatus::Failed);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
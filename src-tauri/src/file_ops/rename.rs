@@ -0,0 +1,567 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Error, Result as AnyResult};
+use chrono::{DateTime, Local, NaiveDateTime};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+use super::exif;
+
+const RENAME_HISTORY_FILE_NAME: &str = "rename-history.json";
+
+/// 最多保留的重命名记录数量
+const MAX_HISTORY: usize = 50;
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Position {
+    #[default]
+    Prefix,
+    Suffix,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CaseMode {
+    Lower,
+    Upper,
+    /// 每个单词首字母大写
+    Title,
+    /// 只有第一个字母大写
+    Sentence,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DateSource {
+    #[default]
+    Modified,
+    /// 照片的拍摄时间，没有 EXIF 信息时使用修改时间
+    Exif,
+}
+
+fn default_one() -> i64 {
+    1
+}
+
+fn default_separator() -> String {
+    String::from("_")
+}
+
+fn default_date_format() -> String {
+    String::from("%Y%m%d")
+}
+
+/// 重命名规则，按顺序依次作用于文件名（默认不含扩展名）
+#[derive(Deserialize, Clone, Debug)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RenameRule {
+    Replace {
+        find: String,
+        #[serde(default)]
+        replace: String,
+        #[serde(default)]
+        regex: bool,
+        #[serde(default)]
+        ignore_case: bool,
+        #[serde(default)]
+        include_extension: bool,
+    },
+    Case {
+        mode: CaseMode,
+        #[serde(default)]
+        include_extension: bool,
+    },
+    Number {
+        #[serde(default = "default_one")]
+        start: i64,
+        #[serde(default = "default_one")]
+        step: i64,
+        /// 补零后的最小位数
+        #[serde(default)]
+        padding: usize,
+        #[serde(default)]
+        position: Position,
+        #[serde(default = "default_separator")]
+        separator: String,
+    },
+    Date {
+        #[serde(default)]
+        source: DateSource,
+        /// chrono 格式，如 `%Y-%m-%d`
+        #[serde(default = "default_date_format")]
+        format: String,
+        #[serde(default)]
+        position: Position,
+        #[serde(default = "default_separator")]
+        separator: String,
+    },
+    /// 修改扩展名，为空时移除
+    Extension { ext: String },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchRenamePayload {
+    pub paths: Vec<String>,
+    pub rules: Vec<RenameRule>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePreviewItem {
+    pub path: String,
+    pub new_name: String,
+    pub new_path: String,
+    pub changed: bool,
+    /// 名称无效或与其他文件冲突时的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePreview {
+    pub items: Vec<RenamePreviewItem>,
+    pub changed_count: usize,
+    pub conflict_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameBatch {
+    pub id: String,
+    /// 毫秒时间戳
+    pub executed_at: i64,
+    /// 原路径与新路径
+    pub items: Vec<(String, String)>,
+}
+
+fn split_name(name: &str) -> (String, String) {
+    match name.rfind('.') {
+        // 以点开头的隐藏文件没有扩展名
+        Some(index) if index > 0 => (name[..index].to_string(), name[index + 1..].to_string()),
+        _ => (name.to_string(), String::new()),
+    }
+}
+
+fn join_name(stem: &str, ext: &str) -> String {
+    if ext.is_empty() {
+        stem.to_string()
+    } else {
+        format!("{}.{}", stem, ext)
+    }
+}
+
+fn change_case(text: &str, mode: CaseMode) -> String {
+    match mode {
+        CaseMode::Lower => text.to_lowercase(),
+        CaseMode::Upper => text.to_uppercase(),
+        CaseMode::Title => {
+            let mut result = String::new();
+            let mut start = true;
+            for c in text.chars() {
+                if c.is_alphanumeric() {
+                    if start {
+                        result.extend(c.to_uppercase());
+                    } else {
+                        result.extend(c.to_lowercase());
+                    }
+                    start = false;
+                } else {
+                    result.push(c);
+                    start = true;
+                }
+            }
+            result
+        }
+        CaseMode::Sentence => {
+            let lower = text.to_lowercase();
+            let mut chars = lower.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => lower,
+            }
+        }
+    }
+}
+
+fn insert(stem: &str, value: &str, position: Position, separator: &str) -> String {
+    match position {
+        Position::Prefix => format!("{}{}{}", value, separator, stem),
+        Position::Suffix => format!("{}{}{}", stem, separator, value),
+    }
+}
+
+fn file_date(path: &Path, source: DateSource) -> Option<NaiveDateTime> {
+    if source == DateSource::Exif {
+        if let Ok(Some(date)) = exif::read_date_taken(path) {
+            return Some(date);
+        }
+    }
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Local>::from(modified).naive_local())
+}
+
+/// 依次应用全部规则，返回新的文件名
+fn apply_rules(path: &Path, index: usize, rules: &[RenameRule]) -> AnyResult<String> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| Error::msg("无效的文件路径"))?;
+    let is_dir = path.is_dir();
+    let (mut stem, mut ext) = if is_dir {
+        (name, String::new())
+    } else {
+        split_name(&name)
+    };
+
+    for rule in rules {
+        match rule {
+            RenameRule::Replace {
+                find,
+                replace,
+                regex,
+                ignore_case,
+                include_extension,
+            } => {
+                if find.is_empty() {
+                    continue;
+                }
+                let pattern = if *regex {
+                    find.clone()
+                } else {
+                    regex::escape(find)
+                };
+                let re = RegexBuilder::new(&pattern)
+                    .case_insensitive(*ignore_case)
+                    .build()
+                    .map_err(|e| Error::msg(format!("无效的正则表达式：{}", e)))?;
+                // 普通替换时不解析 `$1` 等引用
+                let replace = if *regex {
+                    replace.clone()
+                } else {
+                    replace.replace('$', "$$")
+                };
+                if *include_extension {
+                    let full = join_name(&stem, &ext);
+                    (stem, ext) = split_name(&re.replace_all(&full, replace.as_str()));
+                } else {
+                    stem = re.replace_all(&stem, replace.as_str()).to_string();
+                }
+            }
+            RenameRule::Case {
+                mode,
+                include_extension,
+            } => {
+                stem = change_case(&stem, *mode);
+                if *include_extension {
+                    ext = change_case(&ext, *mode);
+                }
+            }
+            RenameRule::Number {
+                start,
+                step,
+                padding,
+                position,
+                separator,
+            } => {
+                let number = start + step * index as i64;
+                let text = if number < 0 {
+                    format!("-{:0width$}", number.unsigned_abs(), width = padding)
+                } else {
+                    format!("{:0width$}", number, width = padding)
+                };
+                stem = insert(&stem, &text, *position, separator);
+            }
+            RenameRule::Date {
+                source,
+                format,
+                position,
+                separator,
+            } => {
+                if let Some(date) = file_date(path, *source) {
+                    let text = date.format(format).to_string();
+                    stem = insert(&stem, &text, *position, separator);
+                }
+            }
+            RenameRule::Extension { ext: new_ext } => {
+                if !is_dir {
+                    ext = new_ext.trim().trim_start_matches('.').to_string();
+                }
+            }
+        }
+    }
+    Ok(join_name(&stem, &ext))
+}
+
+fn invalid_name_reason(name: &str) -> Option<&'static str> {
+    if name.trim().is_empty() || name == "." || name == ".." {
+        return Some("文件名不能为空");
+    }
+    if name.contains(['/', '\\', '\0']) {
+        return Some("文件名不能包含路径分隔符");
+    }
+    #[cfg(windows)]
+    if name.contains(['<', '>', ':', '"', '|', '?', '*']) || name.ends_with(['.', ' ']) {
+        return Some("文件名包含 Windows 不支持的字符");
+    }
+    None
+}
+
+/// Windows 与 macOS 默认不区分大小写
+fn path_key(path: &Path) -> String {
+    let text = path.to_string_lossy().to_string();
+    if cfg!(any(windows, target_os = "macos")) {
+        text.to_lowercase()
+    } else {
+        text
+    }
+}
+
+/// 计算新名称并检查冲突，不修改文件
+pub fn preview(payload: &BatchRenamePayload) -> AnyResult<RenamePreview> {
+    let sources: Vec<PathBuf> = payload.paths.iter().map(PathBuf::from).collect();
+    let source_keys: HashSet<String> = sources.iter().map(|p| path_key(p)).collect();
+
+    let mut items = vec![];
+    for (index, path) in sources.iter().enumerate() {
+        let new_name = apply_rules(path, index, &payload.rules)?;
+        let new_path = path.with_file_name(&new_name);
+        items.push(RenamePreviewItem {
+            path: path.to_string_lossy().to_string(),
+            changed: new_path != *path,
+            new_path: new_path.to_string_lossy().to_string(),
+            new_name,
+            conflict: None,
+        });
+    }
+
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for item in &items {
+        *targets
+            .entry(path_key(Path::new(&item.new_path)))
+            .or_default() += 1;
+    }
+    for item in items.iter_mut() {
+        let new_path = Path::new(&item.new_path);
+        let key = path_key(new_path);
+        item.conflict = if !Path::new(&item.path).exists() {
+            Some(String::from("文件不存在"))
+        } else if let Some(reason) = invalid_name_reason(&item.new_name) {
+            Some(reason.to_string())
+        } else if targets.get(&key).copied().unwrap_or(0) > 1 {
+            Some(String::from("与其他文件的新名称相同"))
+        } else if item.changed
+            && fs::symlink_metadata(new_path).is_ok()
+            && !source_keys.contains(&key)
+        {
+            // 目标是本次会被改名的文件时不算冲突，如交换两个文件的名称
+            Some(String::from("已存在同名文件"))
+        } else {
+            None
+        };
+    }
+
+    Ok(RenamePreview {
+        changed_count: items.iter().filter(|i| i.changed).count(),
+        conflict_count: items.iter().filter(|i| i.conflict.is_some()).count(),
+        items,
+    })
+}
+
+/// 先全部改为临时名称再改为目标名称，任意一步失败时按相反顺序还原
+pub fn rename_all(pairs: &[(String, String)], batch_id: &str) -> AnyResult<()> {
+    let pairs: Vec<(&Path, &Path)> = pairs
+        .iter()
+        .filter(|(from, to)| from != to)
+        .map(|(from, to)| (Path::new(from.as_str()), Path::new(to.as_str())))
+        .collect();
+    // 已完成的步骤，用于回滚
+    let mut done: Vec<(PathBuf, PathBuf)> = vec![];
+
+    let rollback = |done: &[(PathBuf, PathBuf)]| {
+        for (from, to) in done.iter().rev() {
+            let _ = fs::rename(to, from);
+        }
+    };
+
+    let mut temps = vec![];
+    for (index, (from, _)) in pairs.iter().enumerate() {
+        let temp = from.with_file_name(format!(".rename-{}-{}.tmp", batch_id, index));
+        if let Err(e) = fs::rename(from, &temp) {
+            rollback(&done);
+            return Err(Error::msg(format!("重命名 {} 失败：{}", from.display(), e)));
+        }
+        done.push((from.to_path_buf(), temp.clone()));
+        temps.push(temp);
+    }
+    for (temp, (_, to)) in temps.iter().zip(pairs.iter()) {
+        // rename 在部分平台会直接覆盖已有文件，这里先检查
+        let result = if fs::symlink_metadata(to).is_ok() {
+            Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists))
+        } else {
+            fs::rename(temp, to)
+        };
+        if let Err(e) = result {
+            rollback(&done);
+            return Err(Error::msg(format!("重命名为 {} 失败：{}", to.display(), e)));
+        }
+        done.push((temp.clone(), to.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// 已执行的重命名记录，保存到应用数据目录用于撤销
+#[derive(Default)]
+pub struct RenameHistory {
+    batches: Mutex<Vec<RenameBatch>>,
+}
+
+fn history_file_path<R: tauri::Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(RENAME_HISTORY_FILE_NAME))
+}
+
+impl RenameHistory {
+    pub async fn push(&self, batch: RenameBatch) {
+        let mut batches = self.batches.lock().await;
+        batches.push(batch);
+        let overflow = batches.len().saturating_sub(MAX_HISTORY);
+        batches.drain(..overflow);
+    }
+
+    /// 未指定 ID 时取出最近的一条
+    pub async fn take(&self, id: Option<&str>) -> Option<RenameBatch> {
+        let mut batches = self.batches.lock().await;
+        let index = match id {
+            Some(id) => batches.iter().position(|b| b.id == id)?,
+            None => batches.len().checked_sub(1)?,
+        };
+        Some(batches.remove(index))
+    }
+
+    pub async fn list(&self) -> Vec<RenameBatch> {
+        self.batches.lock().await.iter().rev().cloned().collect()
+    }
+
+    pub async fn save<R: tauri::Runtime>(&self, app: &AppHandle<R>) -> AnyResult<()> {
+        let path = history_file_path(app).ok_or(Error::msg("无法获取应用数据目录"))?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&*self.batches.lock().await)?;
+        tokio::fs::write(&path, json).await?;
+        Ok(())
+    }
+
+    pub async fn load<R: tauri::Runtime>(&self, app: &AppHandle<R>) {
+        let content = match history_file_path(app) {
+            Some(path) => tokio::fs::read_to_string(path).await.ok(),
+            None => None,
+        };
+        if let Some(batches) = content.and_then(|c| serde_json::from_str(&c).ok()) {
+            *self.batches.lock().await = batches;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(json: &str) -> Vec<RenameRule> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_apply_rules() {
+        let path = Path::new("/not-exist/IMG_001 holiday.JPG");
+        let list = rules(
+            r#"[
+                {"type": "replace", "find": "IMG_(\\d+)", "replace": "photo-$1", "regex": true},
+                {"type": "case", "mode": "title"},
+                {"type": "number", "start": 1, "padding": 3, "position": "suffix", "separator": " #"},
+                {"type": "extension", "ext": ".jpg"}
+            ]"#,
+        );
+        assert_eq!(
+            apply_rules(path, 4, &list).unwrap(),
+            "Photo-001 Holiday #005.jpg"
+        );
+
+        let list = rules(r#"[{"type": "replace", "find": "$", "replace": "$1"}]"#);
+        assert_eq!(
+            apply_rules(Path::new("/x/a$b.txt"), 0, &list).unwrap(),
+            "a$1b.txt"
+        );
+        assert_eq!(
+            split_name(".gitignore"),
+            (".gitignore".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_preview_and_rename_swap() {
+        let root = std::env::temp_dir().join(format!("tool-box-rename-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let a = root.join("a.txt");
+        let b = root.join("b.txt");
+        fs::write(&a, b"a").unwrap();
+        fs::write(&b, b"b").unwrap();
+        fs::write(root.join("c.txt"), b"c").unwrap();
+
+        // 两个文件都改为同一个名称
+        let payload = BatchRenamePayload {
+            paths: vec![
+                a.to_string_lossy().to_string(),
+                b.to_string_lossy().to_string(),
+            ],
+            rules: rules(r#"[{"type": "replace", "find": "^.*$", "replace": "c", "regex": true}]"#),
+        };
+        let result = preview(&payload).unwrap();
+        assert_eq!(result.conflict_count, 2);
+
+        // 交换名称不算冲突
+        let pairs = vec![
+            (
+                a.to_string_lossy().to_string(),
+                b.to_string_lossy().to_string(),
+            ),
+            (
+                b.to_string_lossy().to_string(),
+                a.to_string_lossy().to_string(),
+            ),
+        ];
+        rename_all(&pairs, "t1").unwrap();
+        assert_eq!(fs::read(&a).unwrap(), b"b");
+        assert_eq!(fs::read(&b).unwrap(), b"a");
+
+        // 目标已存在时全部回滚
+        let pairs = vec![
+            (
+                a.to_string_lossy().to_string(),
+                root.join("d.txt").to_string_lossy().to_string(),
+            ),
+            (
+                b.to_string_lossy().to_string(),
+                root.join("c.txt").to_string_lossy().to_string(),
+            ),
+        ];
+        assert!(rename_all(&pairs, "t2").is_err());
+        assert_eq!(fs::read(&a).unwrap(), b"b");
+        assert!(!root.join("d.txt").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
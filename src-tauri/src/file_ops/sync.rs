@@ -0,0 +1,438 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::trash::move_to_trash;
+
+/// 修改时间允许的误差，FAT 文件系统只精确到 2 秒
+const MTIME_TOLERANCE_MS: i64 = 2000;
+const BUFFER_SIZE: usize = 256 * 1024;
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CompareMode {
+    /// 比较大小与修改时间
+    #[default]
+    Quick,
+    /// 大小相同时逐字节比较内容
+    Content,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CompareStatus {
+    OnlyLeft,
+    OnlyRight,
+    Same,
+    Differs,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareEntry {
+    /// 相对于比较根目录的路径，以 `/` 分隔
+    pub relative_path: String,
+    pub status: CompareStatus,
+    pub left: Option<EntryMeta>,
+    pub right: Option<EntryMeta>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryMeta {
+    pub is_dir: bool,
+    pub size: u64,
+    /// 毫秒时间戳
+    pub modified: Option<i64>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncAction {
+    /// 目标中不存在，复制文件或创建文件夹
+    Copy,
+    /// 覆盖目标中内容不同的文件
+    Update,
+    /// 将目标中多余的项目移到回收站
+    Delete,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPlanItem {
+    pub action: SyncAction,
+    pub relative_path: String,
+    pub is_dir: bool,
+    /// 需要复制的字节数
+    pub size: u64,
+}
+
+fn to_millis(time: SystemTime) -> Option<i64> {
+    let duration = time.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    Some(duration.as_millis() as i64)
+}
+
+/// 遍历整个目录树，跳过符号链接与无法读取的子文件夹，被取消时返回 `None`
+fn scan_tree(
+    root: &Path,
+    cancelled: &AtomicBool,
+) -> io::Result<Option<BTreeMap<String, EntryMeta>>> {
+    let mut entries = BTreeMap::new();
+    let mut stack = vec![(root.to_path_buf(), String::new())];
+    let mut is_root = true;
+    while let Some((dir, prefix)) = stack.pop() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let read_dir = match fs::read_dir(&dir) {
+            Ok(val) => val,
+            Err(e) if is_root => return Err(e),
+            Err(_) => continue,
+        };
+        is_root = false;
+        for entry in read_dir.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(val) if !val.file_type().is_symlink() => val,
+                _ => continue,
+            };
+            let relative_path = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+            if metadata.is_dir() {
+                stack.push((entry.path(), format!("{}/", relative_path)));
+            }
+            let meta = EntryMeta {
+                is_dir: metadata.is_dir(),
+                size: if metadata.is_dir() { 0 } else { metadata.len() },
+                modified: metadata.modified().ok().and_then(to_millis),
+            };
+            entries.insert(relative_path, meta);
+        }
+    }
+    Ok(Some(entries))
+}
+
+/// 逐块比较两个文件的内容，被取消时返回 `None`
+fn same_content(a: &Path, b: &Path, cancelled: &AtomicBool) -> io::Result<Option<bool>> {
+    let mut file_a = File::open(a)?;
+    let mut file_b = File::open(b)?;
+    let mut buf_a = vec![0u8; BUFFER_SIZE];
+    let mut buf_b = vec![0u8; BUFFER_SIZE];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let n = read_full(&mut file_a, &mut buf_a)?;
+        let m = read_full(&mut file_b, &mut buf_b)?;
+        if n != m || buf_a[..n] != buf_b[..m] {
+            return Ok(Some(false));
+        }
+        if n == 0 {
+            return Ok(Some(true));
+        }
+    }
+}
+
+fn read_full(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(total)
+}
+
+fn compare_entry(
+    relative_path: &str,
+    left: &EntryMeta,
+    right: &EntryMeta,
+    roots: (&Path, &Path),
+    mode: CompareMode,
+    cancelled: &AtomicBool,
+) -> io::Result<Option<CompareStatus>> {
+    if left.is_dir != right.is_dir || left.size != right.size {
+        return Ok(Some(CompareStatus::Differs));
+    }
+    if left.is_dir {
+        return Ok(Some(CompareStatus::Same));
+    }
+    let same = match mode {
+        CompareMode::Quick => match (left.modified, right.modified) {
+            (Some(a), Some(b)) => (a - b).abs() <= MTIME_TOLERANCE_MS,
+            _ => false,
+        },
+        CompareMode::Content => {
+            match same_content(
+                &roots.0.join(relative_path),
+                &roots.1.join(relative_path),
+                cancelled,
+            )? {
+                Some(val) => val,
+                None => return Ok(None),
+            }
+        }
+    };
+    Ok(Some(if same {
+        CompareStatus::Same
+    } else {
+        CompareStatus::Differs
+    }))
+}
+
+/// 比较左右两个目录树，结果按相对路径排序，被取消时返回 `None`
+pub fn compare_dirs(
+    left: &Path,
+    right: &Path,
+    mode: CompareMode,
+    cancelled: &AtomicBool,
+) -> io::Result<Option<Vec<CompareEntry>>> {
+    let (left_tree, right_tree) = match (scan_tree(left, cancelled)?, scan_tree(right, cancelled)?)
+    {
+        (Some(a), Some(b)) => (a, b),
+        _ => return Ok(None),
+    };
+    let paths: BTreeSet<&String> = left_tree.keys().chain(right_tree.keys()).collect();
+
+    let mut entries = vec![];
+    for relative_path in paths {
+        let left_meta = left_tree.get(relative_path);
+        let right_meta = right_tree.get(relative_path);
+        let status = match (left_meta, right_meta) {
+            (Some(a), Some(b)) => {
+                match compare_entry(relative_path, a, b, (left, right), mode, cancelled) {
+                    Ok(Some(val)) => val,
+                    Ok(None) => return Ok(None),
+                    // 单个文件无法读取时视为不同，由同步时再报告错误
+                    Err(_) => CompareStatus::Differs,
+                }
+            }
+            (Some(_), None) => CompareStatus::OnlyLeft,
+            _ => CompareStatus::OnlyRight,
+        };
+        entries.push(CompareEntry {
+            relative_path: relative_path.clone(),
+            status,
+            left: left_meta.cloned(),
+            right: right_meta.cloned(),
+        });
+    }
+    Ok(Some(entries))
+}
+
+fn is_under(path: &str, dirs: &[String]) -> bool {
+    dirs.iter().any(|dir| {
+        path.strip_prefix(dir.as_str())
+            .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// 根据比较结果生成从左到右的单向同步计划
+///
+/// 先删除再复制；类型不同（文件与文件夹）的项目需要先删除目标，
+/// 目标中多余的项目只有 `delete_extra` 时才删除，删除文件夹时不再单独列出其中的内容。
+pub fn build_plan(entries: &[CompareEntry], delete_extra: bool) -> Vec<SyncPlanItem> {
+    let mut deletes: Vec<SyncPlanItem> = vec![];
+    let mut deleted_dirs: Vec<String> = vec![];
+    let mut copies = vec![];
+    for entry in entries {
+        let (left, right) = (entry.left.as_ref(), entry.right.as_ref());
+        let replace = matches!((left, right), (Some(a), Some(b)) if a.is_dir != b.is_dir);
+        let remove = replace || (entry.status == CompareStatus::OnlyRight && delete_extra);
+        if let Some(right) = right.filter(|_| remove) {
+            if !is_under(&entry.relative_path, &deleted_dirs) {
+                deletes.push(SyncPlanItem {
+                    action: SyncAction::Delete,
+                    relative_path: entry.relative_path.clone(),
+                    is_dir: right.is_dir,
+                    size: 0,
+                });
+                if right.is_dir {
+                    deleted_dirs.push(entry.relative_path.clone());
+                }
+            }
+        }
+
+        let action = match entry.status {
+            CompareStatus::OnlyLeft => SyncAction::Copy,
+            CompareStatus::Differs if replace => SyncAction::Copy,
+            CompareStatus::Differs => SyncAction::Update,
+            _ => continue,
+        };
+        if let Some(left) = left {
+            copies.push(SyncPlanItem {
+                action,
+                relative_path: entry.relative_path.clone(),
+                is_dir: left.is_dir,
+                size: left.size,
+            });
+        }
+    }
+    deletes.extend(copies);
+    deletes
+}
+
+/// 先复制到临时文件并保留修改时间，再替换目标，避免中断后留下不完整的文件
+fn copy_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let name = to
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的文件路径"))?;
+    let temp = to.with_file_name(format!(".{}.syncing", name.to_string_lossy()));
+    let result = fs::copy(from, &temp).and_then(|_| {
+        let modified = fs::metadata(from)?.modified()?;
+        File::options()
+            .write(true)
+            .open(&temp)?
+            .set_modified(modified)?;
+        fs::rename(&temp, to)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// 执行计划中的单个项目
+pub fn execute_item(item: &SyncPlanItem, left: &Path, right: &Path) -> Result<(), String> {
+    let source = left.join(&item.relative_path);
+    let target = right.join(&item.relative_path);
+    match item.action {
+        SyncAction::Delete => move_to_trash(&target).map_err(|e| e.to_string()),
+        _ if item.is_dir => fs::create_dir_all(&target).map_err(|e| e.to_string()),
+        _ => copy_file(&source, &target).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(is_dir: bool, size: u64) -> Option<EntryMeta> {
+        Some(EntryMeta {
+            is_dir,
+            size,
+            modified: None,
+        })
+    }
+
+    fn entry(
+        path: &str,
+        status: CompareStatus,
+        left: Option<EntryMeta>,
+        right: Option<EntryMeta>,
+    ) -> CompareEntry {
+        CompareEntry {
+            relative_path: path.to_string(),
+            status,
+            left,
+            right,
+        }
+    }
+
+    #[test]
+    fn test_build_plan() {
+        let entries = vec![
+            entry(
+                "a.txt",
+                CompareStatus::Differs,
+                meta(false, 3),
+                meta(false, 2),
+            ),
+            entry(
+                "data",
+                CompareStatus::Differs,
+                meta(false, 5),
+                meta(true, 0),
+            ),
+            entry("data/x", CompareStatus::OnlyRight, None, meta(false, 1)),
+            entry("new", CompareStatus::OnlyLeft, meta(true, 0), None),
+            entry("new/b.txt", CompareStatus::OnlyLeft, meta(false, 4), None),
+            entry("old", CompareStatus::OnlyRight, None, meta(true, 0)),
+            entry("old/c.txt", CompareStatus::OnlyRight, None, meta(false, 1)),
+            entry(
+                "same.txt",
+                CompareStatus::Same,
+                meta(false, 1),
+                meta(false, 1),
+            ),
+        ];
+        let summary = |plan: Vec<SyncPlanItem>| -> Vec<(SyncAction, String)> {
+            plan.into_iter()
+                .map(|i| (i.action, i.relative_path))
+                .collect()
+        };
+
+        assert_eq!(
+            summary(build_plan(&entries, false)),
+            vec![
+                (SyncAction::Delete, "data".to_string()),
+                (SyncAction::Update, "a.txt".to_string()),
+                (SyncAction::Copy, "data".to_string()),
+                (SyncAction::Copy, "new".to_string()),
+                (SyncAction::Copy, "new/b.txt".to_string()),
+            ]
+        );
+        let plan = summary(build_plan(&entries, true));
+        assert_eq!(plan[1], (SyncAction::Delete, "old".to_string()));
+        assert_eq!(
+            plan.iter()
+                .filter(|(a, _)| *a == SyncAction::Delete)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_compare_and_sync() {
+        let root = std::env::temp_dir().join(format!("tool-box-sync-{}", std::process::id()));
+        let (left, right) = (root.join("left"), root.join("right"));
+        fs::create_dir_all(left.join("sub")).unwrap();
+        fs::create_dir_all(&right).unwrap();
+        fs::write(left.join("a.txt"), b"abc").unwrap();
+        fs::write(left.join("sub/b.txt"), b"b").unwrap();
+        fs::write(right.join("a.txt"), b"abd").unwrap();
+
+        let cancelled = AtomicBool::new(false);
+        let entries = compare_dirs(&left, &right, CompareMode::Content, &cancelled)
+            .unwrap()
+            .unwrap();
+        let statuses: Vec<_> = entries
+            .iter()
+            .map(|e| (e.relative_path.as_str(), e.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("a.txt", CompareStatus::Differs),
+                ("sub", CompareStatus::OnlyLeft),
+                ("sub/b.txt", CompareStatus::OnlyLeft),
+            ]
+        );
+
+        for item in build_plan(&entries, false) {
+            execute_item(&item, &left, &right).unwrap();
+        }
+        for mode in [CompareMode::Quick, CompareMode::Content] {
+            let entries = compare_dirs(&left, &right, mode, &cancelled)
+                .unwrap()
+                .unwrap();
+            assert!(entries.iter().all(|e| e.status == CompareStatus::Same));
+        }
+        assert_eq!(fs::read(right.join("a.txt")).unwrap(), b"abc");
+
+        cancelled.store(true, Ordering::Relaxed);
+        assert!(compare_dirs(&left, &right, CompareMode::Quick, &cancelled)
+            .unwrap()
+            .is_none());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
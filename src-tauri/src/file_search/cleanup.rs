@@ -0,0 +1,598 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::trash::move_to_trash;
+
+use super::exclude::ExcludeOptions;
+use super::SearchResultModel;
+
+const DEFAULT_TOP: usize = 50;
+const DEFAULT_TEMP_DAYS: u64 = 7;
+const DEFAULT_CACHE_DAYS: u64 = 30;
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+/// 未配对的下载临时文件超过该时长才视为遗留，避免误判刚开始的下载
+const ORPHAN_GRACE_MILLIS: i64 = 10 * 60 * 1000;
+
+/// 下载模块的临时文件与进度文件后缀，见 `download::progress`
+const DOWNLOAD_TEMP_SUFFIX: &str = ".download";
+const DOWNLOAD_PROGRESS_SUFFIX: &str = ".download.json";
+
+const TEMP_EXTENSIONS: &[&str] = &["tmp", "temp", "crdownload", "part", "partial"];
+/// Office 打开文档时生成的锁文件
+const TEMP_PREFIXES: &[&str] = &["~$"];
+/// 可整体清理的缓存目录名（不区分大小写），只收录确定由程序自动生成、删除后可重建的目录
+const CACHE_DIR_NAMES: &[&str] = &[
+    "cache",
+    "caches",
+    ".cache",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".parcel-cache",
+    ".sass-cache",
+    "_cacache",
+    "code cache",
+    "gpucache",
+    "shadercache",
+    "grshadercache",
+    "dawncache",
+    "cacheddata",
+];
+/// 有意保留的空文件，不作为零字节文件列出
+const PLACEHOLDER_FILES: &[&str] = &[".gitkeep", ".keep", "__init__.py", ".nomedia", "py.typed"];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupPayload {
+    pub root: String,
+    pub concurrent: usize,
+    pub search_id: Option<String>,
+    /// 列出的最大文件数量，默认 50
+    pub top: Option<usize>,
+    /// 临时文件超过多少天未修改视为过期，默认 7
+    pub temp_days: Option<u64>,
+    /// 缓存目录超过多少天未更新视为过期，默认 30
+    pub cache_days: Option<u64>,
+    /// 为空时使用默认排除规则
+    pub exclude: Option<ExcludeOptions>,
+}
+
+impl CleanupPayload {
+    pub fn exclude_options(&self) -> ExcludeOptions {
+        self.exclude.clone().unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum CleanupCategory {
+    LargeFile,
+    EmptyDir,
+    EmptyFile,
+    TempFile,
+    /// 本应用未完成或已放弃的下载留下的 `.download`、`.download.json`
+    DownloadLeftover,
+    Cache,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupItem {
+    pub path: String,
+    pub is_dir: bool,
+    /// 目录为累计大小
+    pub size: u64,
+    /// 毫秒时间戳，缓存目录为其中最近修改的时间
+    pub modified: Option<i64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupGroup {
+    pub category: CleanupCategory,
+    /// 按大小从大到小排列
+    pub items: Vec<CleanupItem>,
+    /// 可释放的空间
+    pub total_size: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupOutput {
+    pub search_id: String,
+    pub groups: Vec<CleanupGroup>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanTarget {
+    pub path: String,
+    pub category: CleanupCategory,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanItemResult {
+    pub path: String,
+    pub size: u64,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanReport {
+    pub items: Vec<CleanItemResult>,
+    /// 已移到回收站的总大小
+    pub reclaimed: u64,
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn is_cache_dir_name(name: &str) -> bool {
+    CACHE_DIR_NAMES.contains(&name.to_lowercase().as_str())
+}
+
+fn is_download_leftover_name(name: &str) -> bool {
+    name.ends_with(DOWNLOAD_TEMP_SUFFIX) || name.ends_with(DOWNLOAD_PROGRESS_SUFFIX)
+}
+
+fn is_temp_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    TEMP_PREFIXES.iter().any(|p| lower.starts_with(p))
+        || Path::new(&lower)
+            .extension()
+            .is_some_and(|e| TEMP_EXTENSIONS.contains(&e.to_string_lossy().as_ref()))
+}
+
+/// 目录中只有空目录（可多层）时视为空，符号链接与文件都不算空
+pub fn is_empty_tree(path: &Path) -> bool {
+    let read_dir = match fs::read_dir(path) {
+        Ok(val) => val,
+        Err(_) => return false,
+    };
+    for entry in read_dir {
+        let entry = match entry {
+            Ok(val) => val,
+            Err(_) => return false,
+        };
+        match entry.file_type() {
+            Ok(t) if t.is_dir() && is_empty_tree(&entry.path()) => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// 累计大小，不跟随符号链接
+fn path_size(path: &Path) -> u64 {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(val) => val,
+        Err(_) => return 0,
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|read_dir| read_dir.flatten().map(|e| path_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+#[derive(Default)]
+struct CacheUsage {
+    size: u64,
+    newest: Option<i64>,
+}
+
+#[derive(Default)]
+struct DownloadPair {
+    temp: Option<CleanupItem>,
+    progress: Option<CleanupItem>,
+}
+
+/// 收集遍历结果，遍历结束后按类别整理
+pub struct CleanupCollector {
+    root: PathBuf,
+    now: i64,
+    top: usize,
+    temp_millis: i64,
+    cache_millis: i64,
+    largest: BinaryHeap<Reverse<(u64, String, Option<i64>)>>,
+    empty_files: Vec<CleanupItem>,
+    temp_files: Vec<CleanupItem>,
+    downloads: HashMap<String, DownloadPair>,
+    caches: HashMap<PathBuf, CacheUsage>,
+    dirs: HashSet<PathBuf>,
+    /// 每个目录中遍历到的子项数量
+    child_counts: HashMap<PathBuf, u64>,
+}
+
+impl CleanupCollector {
+    pub fn new(payload: &CleanupPayload, now: i64) -> Self {
+        Self {
+            root: PathBuf::from(&payload.root),
+            now,
+            top: payload.top.unwrap_or(DEFAULT_TOP).max(1),
+            temp_millis: payload.temp_days.unwrap_or(DEFAULT_TEMP_DAYS) as i64 * DAY_MILLIS,
+            cache_millis: payload.cache_days.unwrap_or(DEFAULT_CACHE_DAYS) as i64 * DAY_MILLIS,
+            largest: BinaryHeap::new(),
+            empty_files: vec![],
+            temp_files: vec![],
+            downloads: HashMap::new(),
+            caches: HashMap::new(),
+            dirs: HashSet::new(),
+            child_counts: HashMap::new(),
+        }
+    }
+
+    fn is_older(&self, modified: Option<i64>, millis: i64) -> bool {
+        modified.is_some_and(|m| self.now - m >= millis)
+    }
+
+    /// 根目录以下最外层的缓存目录
+    fn cache_root(&self, path: &Path) -> Option<PathBuf> {
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| *dir != self.root && dir.starts_with(&self.root))
+            .filter(|dir| is_cache_dir_name(&file_name(dir)))
+            .last()
+            .map(Path::to_path_buf)
+    }
+
+    pub fn add(&mut self, result: &SearchResultModel) {
+        let path = PathBuf::from(&result.path);
+        if let Some(parent) = path.parent() {
+            *self.child_counts.entry(parent.to_path_buf()).or_default() += 1;
+        }
+        if result.is_dir {
+            self.dirs.insert(path);
+            return;
+        }
+
+        let item = CleanupItem {
+            path: result.path.clone(),
+            is_dir: false,
+            size: result.size,
+            modified: result.modified,
+        };
+        self.largest
+            .push(Reverse((item.size, item.path.clone(), item.modified)));
+        if self.largest.len() > self.top {
+            self.largest.pop();
+        }
+
+        // 缓存目录中的文件整体计入缓存，不再单独归类
+        if let Some(cache) = self.cache_root(&path) {
+            let usage = self.caches.entry(cache).or_default();
+            usage.size += item.size;
+            usage.newest = usage.newest.max(item.modified);
+            return;
+        }
+
+        let name = file_name(&path);
+        if let Some(base) = result.path.strip_suffix(DOWNLOAD_PROGRESS_SUFFIX) {
+            self.downloads.entry(base.to_string()).or_default().progress = Some(item);
+        } else if let Some(base) = result.path.strip_suffix(DOWNLOAD_TEMP_SUFFIX) {
+            self.downloads.entry(base.to_string()).or_default().temp = Some(item);
+        } else if item.size == 0 {
+            if !PLACEHOLDER_FILES.contains(&name.as_str()) {
+                self.empty_files.push(item);
+            }
+        } else if is_temp_name(&name) && self.is_older(item.modified, self.temp_millis) {
+            self.temp_files.push(item);
+        }
+    }
+
+    /// 成对存在时按过期时间判断，只剩一半时视为遗留
+    fn download_leftovers(&mut self) -> Vec<CleanupItem> {
+        let mut items = vec![];
+        for (_, pair) in self.downloads.drain() {
+            let newest = pair
+                .temp
+                .iter()
+                .chain(pair.progress.iter())
+                .filter_map(|i| i.modified)
+                .max();
+            let threshold = if pair.temp.is_some() && pair.progress.is_some() {
+                self.temp_millis
+            } else {
+                ORPHAN_GRACE_MILLIS
+            };
+            if newest.is_some_and(|m| self.now - m >= threshold) {
+                items.extend(pair.temp);
+                items.extend(pair.progress);
+            }
+        }
+        items
+    }
+
+    /// 自下而上合并只包含空目录的目录，只返回最外层，并在磁盘上再次确认
+    fn empty_dirs(&self) -> Vec<CleanupItem> {
+        let mut dirs: Vec<&PathBuf> = self.dirs.iter().collect();
+        dirs.sort_by_key(|d| Reverse(d.components().count()));
+        let mut empty_children: HashMap<&Path, u64> = HashMap::new();
+        let mut empty: HashSet<&Path> = HashSet::new();
+        for dir in dirs {
+            let children = self.child_counts.get(dir).copied().unwrap_or(0);
+            if children != empty_children.get(dir.as_path()).copied().unwrap_or(0) {
+                continue;
+            }
+            empty.insert(dir);
+            if let Some(parent) = dir.parent() {
+                *empty_children.entry(parent).or_default() += 1;
+            }
+        }
+        empty
+            .iter()
+            .filter(|dir| dir.parent().is_none_or(|p| !empty.contains(p)))
+            .filter(|dir| is_empty_tree(dir))
+            .map(|dir| CleanupItem {
+                path: dir.to_string_lossy().to_string(),
+                is_dir: true,
+                size: 0,
+                modified: None,
+            })
+            .collect()
+    }
+
+    fn stale_caches(&self) -> Vec<CleanupItem> {
+        self.caches
+            .iter()
+            .filter(|(_, usage)| usage.size > 0 && self.is_older(usage.newest, self.cache_millis))
+            .map(|(path, usage)| CleanupItem {
+                path: path.to_string_lossy().to_string(),
+                is_dir: true,
+                size: usage.size,
+                modified: usage.newest,
+            })
+            .collect()
+    }
+
+    /// 整理为分组结果，空目录需要访问磁盘确认（阻塞操作）
+    pub fn finish(mut self) -> Vec<CleanupGroup> {
+        let largest = self
+            .largest
+            .drain()
+            .map(|Reverse((size, path, modified))| CleanupItem {
+                path,
+                is_dir: false,
+                size,
+                modified,
+            })
+            .collect();
+        let downloads = self.download_leftovers();
+        let groups = [
+            (CleanupCategory::LargeFile, largest),
+            (CleanupCategory::EmptyDir, self.empty_dirs()),
+            (
+                CleanupCategory::EmptyFile,
+                std::mem::take(&mut self.empty_files),
+            ),
+            (
+                CleanupCategory::TempFile,
+                std::mem::take(&mut self.temp_files),
+            ),
+            (CleanupCategory::DownloadLeftover, downloads),
+            (CleanupCategory::Cache, self.stale_caches()),
+        ];
+        groups
+            .into_iter()
+            .map(|(category, mut items)| {
+                items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+                CleanupGroup {
+                    category,
+                    total_size: items.iter().map(|i| i.size).sum(),
+                    items,
+                }
+            })
+            .collect()
+    }
+}
+
+/// 清理前按类别重新确认条件仍然成立，不信任前端传入的类别，
+/// 例如空目录在扫描后可能被写入了文件，任意目录也不能作为缓存目录清理
+fn verify(target: &CleanTarget) -> Result<u64, String> {
+    let path = Path::new(&target.path);
+    let metadata = fs::symlink_metadata(path).map_err(|e| e.to_string())?;
+    let name = file_name(path);
+    match target.category {
+        CleanupCategory::EmptyDir if !metadata.is_dir() || !is_empty_tree(path) => {
+            Err(String::from("目录已不为空"))
+        }
+        CleanupCategory::EmptyFile if !metadata.is_file() || metadata.len() != 0 => {
+            Err(String::from("文件已不为空"))
+        }
+        CleanupCategory::LargeFile if !metadata.is_file() => Err(String::from("不是文件")),
+        CleanupCategory::TempFile if !metadata.is_file() || !is_temp_name(&name) => {
+            Err(String::from("不是临时文件"))
+        }
+        CleanupCategory::DownloadLeftover
+            if !metadata.is_file() || !is_download_leftover_name(&name) =>
+        {
+            Err(String::from("不是下载临时文件"))
+        }
+        CleanupCategory::Cache if !metadata.is_dir() || !is_cache_dir_name(&name) => {
+            Err(String::from("不是缓存目录"))
+        }
+        _ => Ok(path_size(path)),
+    }
+}
+
+/// 逐个移到回收站（阻塞操作）
+pub fn clean(targets: &[CleanTarget]) -> CleanReport {
+    let mut items = vec![];
+    let mut reclaimed = 0;
+    for target in targets {
+        let mut item = CleanItemResult {
+            path: target.path.clone(),
+            size: 0,
+            done: false,
+            error: None,
+        };
+        match verify(target) {
+            Ok(size) => item.size = size,
+            Err(e) => {
+                item.error = Some(e);
+                items.push(item);
+                continue;
+            }
+        }
+        match move_to_trash(Path::new(&target.path)) {
+            Ok(_) => {
+                item.done = true;
+                reclaimed += item.size;
+            }
+            Err(e) => item.error = Some(e.to_string()),
+        }
+        items.push(item);
+    }
+    CleanReport { items, reclaimed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(root: &str) -> CleanupPayload {
+        CleanupPayload {
+            root: root.to_string(),
+            concurrent: 1,
+            search_id: None,
+            top: Some(2),
+            temp_days: Some(1),
+            cache_days: Some(1),
+            exclude: None,
+        }
+    }
+
+    fn result(path: &str, is_dir: bool, size: u64, modified: i64) -> SearchResultModel {
+        SearchResultModel::new(path.to_string(), is_dir, size, Some(modified), None)
+    }
+
+    fn paths(groups: &[CleanupGroup], category: CleanupCategory) -> Vec<String> {
+        groups
+            .iter()
+            .find(|g| g.category == category)
+            .map(|g| g.items.iter().map(|i| i.path.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_collect_categories() {
+        let now = 100 * DAY_MILLIS;
+        let old = now - 2 * DAY_MILLIS;
+        let mut collector = CleanupCollector::new(&payload("/r"), now);
+        for r in [
+            result("/r/big.iso", false, 900, now),
+            result("/r/mid.bin", false, 500, now),
+            result("/r/small.txt", false, 10, now),
+            result("/r/empty.txt", false, 0, now),
+            result("/r/pkg/__init__.py", false, 0, now),
+            result("/r/old.tmp", false, 5, old),
+            result("/r/new.tmp", false, 5, now),
+            result("/r/a.zip.download", false, 50, old),
+            result("/r/a.zip.download.json", false, 1, old),
+            result("/r/b.zip.download", false, 50, now),
+            result("/r/b.zip.download.json", false, 1, now),
+            result("/r/c.zip.download.json", false, 1, old),
+            result("/r/app/Cache", true, 0, old),
+            result("/r/app/Cache/x/data", false, 40, old),
+            result("/r/app/Cache/y", false, 0, old),
+        ] {
+            collector.add(&r);
+        }
+        let groups = collector.finish();
+
+        assert_eq!(
+            paths(&groups, CleanupCategory::LargeFile),
+            vec!["/r/big.iso", "/r/mid.bin"]
+        );
+        assert_eq!(
+            paths(&groups, CleanupCategory::EmptyFile),
+            vec!["/r/empty.txt"]
+        );
+        assert_eq!(
+            paths(&groups, CleanupCategory::TempFile),
+            vec!["/r/old.tmp"]
+        );
+        assert_eq!(
+            paths(&groups, CleanupCategory::DownloadLeftover),
+            vec![
+                "/r/a.zip.download",
+                "/r/a.zip.download.json",
+                "/r/c.zip.download.json"
+            ]
+        );
+        assert_eq!(paths(&groups, CleanupCategory::Cache), vec!["/r/app/Cache"]);
+        let cache = groups
+            .iter()
+            .find(|g| g.category == CleanupCategory::Cache)
+            .unwrap();
+        assert_eq!(cache.total_size, 40);
+    }
+
+    #[test]
+    fn test_empty_dirs_verified_on_disk() {
+        let root = std::env::temp_dir().join(format!("tool-box-cleanup-{}", std::process::id()));
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+        fs::create_dir_all(root.join("d")).unwrap();
+        fs::create_dir_all(root.join("e")).unwrap();
+        // 被排除规则隐藏的文件不会出现在遍历结果中
+        fs::write(root.join("e/hidden"), b"x").unwrap();
+
+        let root_str = root.to_string_lossy().to_string();
+        let mut collector = CleanupCollector::new(&payload(&root_str), 0);
+        for dir in ["a", "a/b", "a/b/c", "d", "e"] {
+            collector.add(&result(&root.join(dir).to_string_lossy(), true, 0, 0));
+        }
+        let mut found = paths(&collector.finish(), CleanupCategory::EmptyDir);
+        found.sort();
+        let expected: Vec<String> = ["a", "d"]
+            .iter()
+            .map(|d| root.join(d).to_string_lossy().to_string())
+            .collect();
+        assert_eq!(found, expected);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_cache_dir_allowlist() {
+        assert!(is_cache_dir_name("Cache"));
+        assert!(is_cache_dir_name("__pycache__"));
+        assert!(is_cache_dir_name("Code Cache"));
+        assert!(!is_cache_dir_name("BrowserCache-backup"));
+        assert!(!is_cache_dir_name("ImportantNoCache"));
+    }
+
+    #[test]
+    fn test_clean_revalidates_category() {
+        let root = std::env::temp_dir().join(format!("tool-box-clean-{}", std::process::id()));
+        fs::create_dir_all(root.join("photos")).unwrap();
+        fs::write(root.join("photos/a.jpg"), b"x").unwrap();
+
+        let target = |path: &str, category| CleanTarget {
+            path: root.join(path).to_string_lossy().to_string(),
+            category,
+        };
+        let report = clean(&[
+            target("photos", CleanupCategory::Cache),
+            target("photos", CleanupCategory::LargeFile),
+            target("photos/a.jpg", CleanupCategory::TempFile),
+            target("photos/a.jpg", CleanupCategory::DownloadLeftover),
+        ]);
+        assert!(report.items.iter().all(|i| !i.done && i.error.is_some()));
+        assert!(root.join("photos/a.jpg").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
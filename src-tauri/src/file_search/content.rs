@@ -0,0 +1,222 @@
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Error, Result as AnyResult};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// 用于判断二进制文件的头部长度
+const SNIFF_LEN: usize = 8 * 1024;
+
+const DEFAULT_MAX_FILE_SIZE: u64 = 20 * 1024 * 1024;
+const DEFAULT_CONTEXT_CHARS: usize = 60;
+const DEFAULT_MAX_HITS_PER_FILE: usize = 100;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentOptions {
+    pub query: String,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub ignore_case: bool,
+    /// 跳过的二进制扩展名，由前端传入（不含点）
+    #[serde(default)]
+    pub binary_extensions: Vec<String>,
+    /// 超过该大小（字节）的文件不搜索内容
+    pub max_file_size: Option<u64>,
+    /// 片段中命中位置前后保留的字符数
+    pub context_chars: Option<usize>,
+    pub max_hits_per_file: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentHit {
+    /// 从 1 开始
+    pub line: usize,
+    pub snippet: String,
+}
+
+pub struct ContentMatcher {
+    regex: Regex,
+    binary_extensions: Vec<String>,
+    max_file_size: u64,
+    context_chars: usize,
+    max_hits: usize,
+}
+
+impl ContentMatcher {
+    pub fn new(options: &ContentOptions) -> AnyResult<Self> {
+        if options.query.is_empty() {
+            return Err(Error::msg("搜索内容不能为空"));
+        }
+        let pattern = if options.regex {
+            options.query.clone()
+        } else {
+            regex::escape(&options.query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(options.ignore_case)
+            .build()
+            .map_err(|e| Error::msg(format!("无效的正则表达式：{}", e)))?;
+
+        Ok(Self {
+            regex,
+            binary_extensions: options
+                .binary_extensions
+                .iter()
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .collect(),
+            max_file_size: options.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE),
+            context_chars: options.context_chars.unwrap_or(DEFAULT_CONTEXT_CHARS),
+            max_hits: options
+                .max_hits_per_file
+                .unwrap_or(DEFAULT_MAX_HITS_PER_FILE),
+        })
+    }
+
+    /// 根据扩展名和大小判断是否需要读取内容
+    pub fn is_candidate(&self, name: &str, size: u64) -> bool {
+        if size > self.max_file_size {
+            return false;
+        }
+        let ext = Path::new(name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        !self.binary_extensions.contains(&ext)
+    }
+
+    /// 阻塞读取文件并返回所有命中的行
+    pub fn search_file(&self, path: &Path) -> AnyResult<Vec<ContentHit>> {
+        let mut bytes = vec![];
+        std::fs::File::open(path)?
+            .take(self.max_file_size)
+            .read_to_end(&mut bytes)?;
+
+        let text = match decode(&bytes) {
+            Some(text) => text,
+            None => return Ok(vec![]),
+        };
+        Ok(self.search_text(&text))
+    }
+
+    fn search_text(&self, text: &str) -> Vec<ContentHit> {
+        let mut hits = vec![];
+        for (index, line) in text.lines().enumerate() {
+            if let Some(m) = self.regex.find(line) {
+                hits.push(ContentHit {
+                    line: index + 1,
+                    snippet: snippet(line, m.start(), m.end(), self.context_chars),
+                });
+                if hits.len() >= self.max_hits {
+                    break;
+                }
+            }
+        }
+        hits
+    }
+}
+
+/// 识别编码并解码，二进制内容返回 None
+fn decode(bytes: &[u8]) -> Option<Cow<'_, str>> {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Some(text);
+    }
+
+    let head = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if head.contains(&0) {
+        return None;
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Some(Cow::Borrowed(text));
+    }
+
+    // 非 UTF-8 时猜测编码，如 GBK/GB18030
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, false);
+    if encoding == UTF_8 {
+        return Some(String::from_utf8_lossy(bytes));
+    }
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    Some(text)
+}
+
+/// 截取命中位置前后的内容，过长时用省略号标记
+fn snippet(line: &str, start: usize, end: usize, context: usize) -> String {
+    let before: Vec<char> = line[..start].chars().collect();
+    let after: Vec<char> = line[end..].chars().collect();
+
+    let mut result = String::new();
+    if before.len() > context {
+        result.push('…');
+        result.extend(&before[before.len() - context..]);
+    } else {
+        result.extend(&before);
+    }
+    result.push_str(&line[start..end]);
+    if after.len() > context {
+        result.extend(&after[..context]);
+        result.push('…');
+    } else {
+        result.extend(&after);
+    }
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(query: &str, regex: bool, ignore_case: bool) -> ContentMatcher {
+        ContentMatcher::new(&ContentOptions {
+            query: query.to_string(),
+            regex,
+            ignore_case,
+            binary_extensions: vec!["png".to_string(), ".EXE".to_string()],
+            max_file_size: Some(1024),
+            context_chars: Some(5),
+            max_hits_per_file: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_search_text() {
+        let m = matcher("TODO", false, true);
+        let hits = m.search_text("fn main() {}\n// todo: fix this later please\nend");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line, 2);
+        assert_eq!(hits[0].snippet, "// todo: fix…");
+
+        let m = matcher(r"v\d+\.\d+", true, false);
+        assert_eq!(m.search_text("version v1.2\nnone\nv3.4").len(), 2);
+    }
+
+    #[test]
+    fn test_is_candidate() {
+        let m = matcher("a", false, false);
+        assert!(m.is_candidate("a.txt", 100));
+        assert!(!m.is_candidate("a.PNG", 100));
+        assert!(!m.is_candidate("setup.exe", 100));
+        assert!(!m.is_candidate("a.txt", 2048));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert!(decode(b"abc\0def").is_none());
+        assert_eq!(decode("你好".as_bytes()).unwrap(), "你好");
+
+        let (gbk, _, _) = encoding_rs::GBK.encode("中文内容搜索测试，这是一段用于检测编码的文本。");
+        assert_eq!(
+            decode(&gbk).unwrap(),
+            "中文内容搜索测试，这是一段用于检测编码的文本。"
+        );
+    }
+}
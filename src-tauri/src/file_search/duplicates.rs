@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::fs::{File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::session::SearchControl;
+use crate::utils::trash::move_to_trash;
+
+/// 快速哈希读取文件头尾各自的长度
+const PARTIAL_LEN: u64 = 16 * 1024;
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// 完整内容的 MD5
+    pub hash: String,
+    /// 单个文件的大小（字节）
+    pub size: u64,
+    pub files: Vec<String>,
+    /// 只保留一份时可释放的空间
+    pub reclaimable: u64,
+}
+
+/// 重复文件事件，`group` 为空表示查找已结束
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateOutput {
+    pub search_id: String,
+    pub group: Option<DuplicateGroup>,
+    pub group_count: usize,
+    pub total_reclaimable: u64,
+}
+
+/// 按大小分组，只保留可能重复的组，空文件不参与比较
+pub fn group_by_size(files: Vec<(String, u64)>) -> Vec<(u64, Vec<String>)> {
+    let mut groups: HashMap<u64, Vec<String>> = HashMap::new();
+    for (path, size) in files {
+        if size > 0 {
+            groups.entry(size).or_default().push(path);
+        }
+    }
+    let mut groups: Vec<(u64, Vec<String>)> = groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .collect();
+    // 大文件优先，尽早给出可释放空间最多的结果
+    groups.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+    groups
+}
+
+/// 只读取文件头尾计算的哈希，用于快速排除内容不同的文件
+fn partial_hash(path: &str, size: u64) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buf = vec![0u8; PARTIAL_LEN as usize];
+
+    let n = read_full(&mut file, &mut buf)?;
+    context.consume(&buf[..n]);
+    if size > PARTIAL_LEN * 2 {
+        file.seek(SeekFrom::End(-(PARTIAL_LEN as i64)))?;
+        let n = read_full(&mut file, &mut buf)?;
+        context.consume(&buf[..n]);
+    }
+    Ok(format!("{:x}", context.finalize()))
+}
+
+fn read_full(file: &mut File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        let n = file.read(&mut buf[total..])?;
+        if n == 0 {
+            break;
+        }
+        total += n;
+    }
+    Ok(total)
+}
+
+/// 计算完整内容的哈希，取消时返回 Interrupted
+pub fn full_hash(path: &str, control: &SearchControl) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    loop {
+        if control.wait_if_paused() {
+            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        }
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.consume(&buf[..n]);
+    }
+    Ok(format!("{:x}", context.finalize()))
+}
+
+/// 将文件按哈希分组，只返回包含多个文件的组
+fn split_by_hash<F>(
+    files: Vec<String>,
+    control: &SearchControl,
+    hash: F,
+) -> Vec<(String, Vec<String>)>
+where
+    F: Fn(&str) -> io::Result<String>,
+{
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for path in files {
+        if control.is_cancelled() {
+            return vec![];
+        }
+        match hash(&path) {
+            Ok(hash) => groups.entry(hash).or_default().push(path),
+            Err(e) => control.stats().record_error(Path::new(&path), &e),
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .collect()
+}
+
+/// 依次按大小、快速哈希、完整哈希筛选，每确认一组调用一次 `on_group`，返回 false 时停止
+pub fn find_duplicates<F>(files: Vec<(String, u64)>, control: &SearchControl, mut on_group: F)
+where
+    F: FnMut(DuplicateGroup) -> bool,
+{
+    for (size, candidates) in group_by_size(files) {
+        let partial = split_by_hash(candidates, control, |path| partial_hash(path, size));
+        for (quick_hash, candidates) in partial {
+            // 不超过快速哈希读取长度的文件，快速哈希即为完整内容
+            let groups = if size <= PARTIAL_LEN {
+                vec![(quick_hash, candidates)]
+            } else {
+                split_by_hash(candidates, control, |path| full_hash(path, control))
+            };
+            for (hash, mut files) in groups {
+                files.sort();
+                let group = DuplicateGroup {
+                    hash,
+                    size,
+                    reclaimable: size * (files.len() as u64 - 1),
+                    files,
+                };
+                if !on_group(group) {
+                    return;
+                }
+            }
+        }
+        if control.is_cancelled() {
+            return;
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolveAction {
+    Delete,
+    Trash,
+    HardLink,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveGroup {
+    /// 保留的文件
+    pub keep: String,
+    /// 需要处理的其他副本
+    pub remove: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveDuplicatesPayload {
+    pub groups: Vec<ResolveGroup>,
+    pub action: ResolveAction,
+    /// 只生成预览，不修改文件
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveItem {
+    pub path: String,
+    pub keep: String,
+    pub size: u64,
+    pub done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveReport {
+    pub dry_run: bool,
+    pub items: Vec<ResolveItem>,
+    /// 已释放（预览时为可释放）的空间
+    pub reclaimed: u64,
+}
+
+/// 逐字节比较两个文件的内容，取消时返回 Interrupted
+fn same_content(a: &str, b: &str, control: &SearchControl) -> io::Result<bool> {
+    let mut file_a = File::open(a)?;
+    let mut file_b = File::open(b)?;
+    let mut buf_a = vec![0u8; BUFFER_SIZE];
+    let mut buf_b = vec![0u8; BUFFER_SIZE];
+    loop {
+        if control.wait_if_paused() {
+            return Err(io::Error::from(io::ErrorKind::Interrupted));
+        }
+        let n = read_full(&mut file_a, &mut buf_a)?;
+        let m = read_full(&mut file_b, &mut buf_b)?;
+        if buf_a[..n] != buf_b[..m] {
+            return Ok(false);
+        }
+        if n < BUFFER_SIZE {
+            return Ok(true);
+        }
+    }
+}
+
+/// 两个路径是否指向同一个文件，包括同一路径的不同写法、符号链接与硬链接
+fn is_same_file(keep: &str, path: &str, keep_meta: &Metadata, meta: &Metadata) -> bool {
+    if let (Ok(a), Ok(b)) = (std::fs::canonicalize(keep), std::fs::canonicalize(path)) {
+        if a == b {
+            return true;
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if keep_meta.dev() == meta.dev() && keep_meta.ino() == meta.ino() {
+            return true;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (keep_meta, meta);
+    false
+}
+
+/// 处理前重新逐字节确认两个文件内容一致，避免扫描后文件被修改或误删唯一的副本
+fn verify_same(keep: &str, path: &str, control: &SearchControl) -> Result<u64, String> {
+    if keep == path {
+        return Err(String::from("不能处理保留的文件本身"));
+    }
+    let keep_meta = std::fs::metadata(keep).map_err(|e| format!("保留的文件无法访问：{}", e))?;
+    let meta = std::fs::symlink_metadata(path).map_err(|e| e.to_string())?;
+    if !keep_meta.is_file() || !meta.is_file() {
+        return Err(String::from("只能处理文件"));
+    }
+    if is_same_file(keep, path, &keep_meta, &meta) {
+        return Err(String::from("与保留的文件是同一个文件"));
+    }
+    if keep_meta.len() != meta.len() {
+        return Err(String::from("文件大小已变化"));
+    }
+    if !same_content(keep, path, control).map_err(|e| e.to_string())? {
+        return Err(String::from("文件内容已变化"));
+    }
+    Ok(meta.len())
+}
+
+/// 先在同一目录创建硬链接，再替换原文件，失败时原文件保持不变
+fn replace_with_hard_link(keep: &str, path: &str) -> io::Result<()> {
+    let target = Path::new(path);
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = target.with_file_name(format!(".{}.link-tmp", file_name));
+    std::fs::hard_link(keep, &temp)?;
+    if let Err(e) = std::fs::rename(&temp, target) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn apply(action: ResolveAction, keep: &str, path: &str) -> Result<(), String> {
+    let result = match action {
+        ResolveAction::Delete => std::fs::remove_file(path).map_err(|e| e.to_string()),
+        ResolveAction::Trash => move_to_trash(Path::new(path)).map_err(|e| e.to_string()),
+        ResolveAction::HardLink => replace_with_hard_link(keep, path).map_err(|e| e.to_string()),
+    };
+    result.map_err(|e| format!("处理失败：{}", e))
+}
+
+/// 阻塞执行，预览时同样校验文件内容
+pub fn resolve(payload: &ResolveDuplicatesPayload) -> ResolveReport {
+    let control = SearchControl::default();
+    let mut items = vec![];
+    let mut reclaimed = 0;
+    for group in &payload.groups {
+        for path in &group.remove {
+            let mut item = ResolveItem {
+                path: path.clone(),
+                keep: group.keep.clone(),
+                size: 0,
+                done: false,
+                error: None,
+            };
+            match verify_same(&group.keep, path, &control) {
+                Ok(size) => item.size = size,
+                Err(e) => {
+                    item.error = Some(e);
+                    items.push(item);
+                    continue;
+                }
+            }
+            if !payload.dry_run {
+                if let Err(e) = apply(payload.action, &group.keep, path) {
+                    item.error = Some(e);
+                    items.push(item);
+                    continue;
+                }
+                item.done = true;
+            }
+            reclaimed += item.size;
+            items.push(item);
+        }
+    }
+    ResolveReport {
+        dry_run: payload.dry_run,
+        items,
+        reclaimed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_duplicates() {
+        let root = std::env::temp_dir().join(format!("tool-box-dup-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let big_a = vec![7u8; 40 * 1024];
+        let mut big_b = big_a.clone();
+        // 头尾相同、中间不同，只有完整哈希能区分
+        big_b[20 * 1024] = 8;
+        let files = [
+            ("a.txt", b"same".to_vec()),
+            ("b.txt", b"same".to_vec()),
+            ("c.txt", b"diff".to_vec()),
+            ("empty1", vec![]),
+            ("empty2", vec![]),
+            ("big_a", big_a.clone()),
+            ("big_b", big_b),
+            ("big_c", big_a),
+        ];
+        let mut list = vec![];
+        for (name, content) in &files {
+            let path = root.join(name);
+            std::fs::write(&path, content).unwrap();
+            list.push((path.to_string_lossy().to_string(), content.len() as u64));
+        }
+
+        let mut groups = vec![];
+        find_duplicates(list, &SearchControl::default(), |group| {
+            groups.push(group);
+            true
+        });
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].size, 40 * 1024);
+        assert_eq!(groups[0].files.len(), 2);
+        assert!(groups[0].files[0].ends_with("big_a"));
+        assert!(groups[0].files[1].ends_with("big_c"));
+        assert_eq!(groups[1].reclaimable, 4);
+
+        let keep = groups[1].files[0].clone();
+        let dup = groups[1].files[1].clone();
+        let payload = ResolveDuplicatesPayload {
+            groups: vec![ResolveGroup {
+                keep: keep.clone(),
+                remove: vec![
+                    dup.clone(),
+                    root.join("c.txt").to_string_lossy().to_string(),
+                ],
+            }],
+            action: ResolveAction::HardLink,
+            dry_run: true,
+        };
+        let report = resolve(&payload);
+        assert_eq!(report.reclaimed, 4);
+        assert!(report.items[1].error.is_some());
+        assert!(!report.items[0].done);
+
+        let report = resolve(&ResolveDuplicatesPayload {
+            dry_run: false,
+            ..payload
+        });
+        assert!(report.items[0].done);
+        assert_eq!(std::fs::read(&dup).unwrap(), b"same");
+
+        // 已是硬链接、换一种写法或符号链接指向保留的文件时，不能删除唯一的副本
+        let respelled = root.join(".").join("a.txt").to_string_lossy().to_string();
+        let mut remove = vec![dup.clone(), respelled];
+        #[cfg(unix)]
+        {
+            let link = root.join("link.txt");
+            std::os::unix::fs::symlink(&keep, &link).unwrap();
+            remove.push(link.to_string_lossy().to_string());
+        }
+        let report = resolve(&ResolveDuplicatesPayload {
+            groups: vec![ResolveGroup {
+                keep: keep.clone(),
+                remove,
+            }],
+            action: ResolveAction::Delete,
+            dry_run: false,
+        });
+        assert!(report
+            .items
+            .iter()
+            .all(|item| !item.done && item.error.is_some()));
+        assert_eq!(std::fs::read(&keep).unwrap(), b"same");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
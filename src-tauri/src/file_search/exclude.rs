@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Error, Result as AnyResult};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::Gitignore;
+use ignore::Match;
+use serde::{Deserialize, Serialize};
+
+/// 未指定排除规则时使用的默认值
+pub const DEFAULT_EXCLUDE_GLOBS: &[&str] = &[
+    "/proc",
+    "/sys",
+    "/dev",
+    "/run",
+    "/snap",
+    "**/node_modules",
+    "**/.git",
+    "**/.svn",
+    "**/.hg",
+    "**/$RECYCLE.BIN",
+    "**/System Volume Information",
+    "**/.Trash-*",
+];
+
+const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore"];
+
+/// 网络文件系统类型，开启 `skip_network_mounts` 时不会进入这些挂载点
+#[cfg(target_os = "linux")]
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smbfs",
+    "smb3",
+    "9p",
+    "afs",
+    "ncpfs",
+    "davfs",
+    "fuse.sshfs",
+    "fuse.rclone",
+];
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExcludeOptions {
+    /// 为空时使用默认规则，传入空数组可关闭
+    pub exclude_globs: Option<Vec<String>>,
+    /// 遵循目录中的 `.gitignore`、`.ignore`
+    #[serde(default)]
+    pub respect_ignore_files: bool,
+    #[serde(default)]
+    pub skip_hidden: bool,
+    /// 不跨越所选目录所在的文件系统
+    #[serde(default)]
+    pub one_file_system: bool,
+    #[serde(default = "default_true")]
+    pub skip_network_mounts: bool,
+    #[serde(default)]
+    pub follow_symlinks: bool,
+}
+
+impl Default for ExcludeOptions {
+    fn default() -> Self {
+        Self {
+            exclude_globs: None,
+            respect_ignore_files: false,
+            skip_hidden: false,
+            one_file_system: false,
+            skip_network_mounts: true,
+            follow_symlinks: false,
+        }
+    }
+}
+
+#[cfg(unix)]
+type DirKey = (u64, u64);
+#[cfg(not(unix))]
+type DirKey = PathBuf;
+
+#[cfg(unix)]
+fn dir_key(_path: &Path, metadata: &Metadata) -> Option<DirKey> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_key(path: &Path, _metadata: &Metadata) -> Option<DirKey> {
+    std::fs::canonicalize(path).ok()
+}
+
+#[cfg(unix)]
+fn device_of(metadata: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_of(_metadata: &Metadata) -> Option<u64> {
+    None
+}
+
+/// 以 `.` 开头的文件，Windows 下还包括带隐藏或系统属性的文件
+pub fn is_hidden(name: &str, metadata: &Metadata) -> bool {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+        if metadata.file_attributes() & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0 {
+            return true;
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = metadata;
+    name.starts_with('.')
+}
+
+/// 解析 /proc/mounts 中的转义字符（如空格为 `\040`）
+#[cfg(target_os = "linux")]
+fn unescape_mount_path(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() {
+            if let Ok(v) = u8::from_str_radix(&raw[i + 1..i + 4], 8) {
+                out.push(v);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(target_os = "linux")]
+fn network_mounts() -> HashSet<PathBuf> {
+    let content = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?;
+            let fs_type = fields.next()?;
+            NETWORK_FS_TYPES
+                .contains(&fs_type)
+                .then(|| PathBuf::from(unescape_mount_path(mount_point)))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn network_mounts() -> HashSet<PathBuf> {
+    HashSet::new()
+}
+
+/// 单个遍历分支继承的状态：起始文件系统及沿途的忽略文件
+#[derive(Clone, Default)]
+pub struct DirScope {
+    device: Option<u64>,
+    ignores: Vec<Arc<Gitignore>>,
+}
+
+pub struct ExcludeRules {
+    options: ExcludeOptions,
+    globs: GlobSet,
+    network_mounts: HashSet<PathBuf>,
+    visited: Mutex<HashSet<DirKey>>,
+}
+
+impl ExcludeRules {
+    pub fn new(options: &ExcludeOptions) -> AnyResult<Self> {
+        let patterns: Vec<String> = match &options.exclude_globs {
+            Some(list) => list.clone(),
+            None => DEFAULT_EXCLUDE_GLOBS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        };
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns.iter().filter(|p| !p.trim().is_empty()) {
+            let glob = GlobBuilder::new(pattern.trim())
+                .case_insensitive(cfg!(windows))
+                .build()
+                .map_err(|e| Error::msg(format!("无效的排除规则：{}", e)))?;
+            builder.add(glob);
+        }
+
+        Ok(Self {
+            options: options.clone(),
+            globs: builder.build()?,
+            network_mounts: if options.skip_network_mounts {
+                network_mounts()
+            } else {
+                HashSet::new()
+            },
+            visited: Mutex::new(HashSet::new()),
+        })
+    }
+
+    pub fn follow_symlinks(&self) -> bool {
+        self.options.follow_symlinks
+    }
+
+    /// 进入目录前检查文件系统边界与是否已访问，返回该目录的遍历状态
+    ///
+    /// 需要跳过时返回 `Ok(None)`，无法读取目录信息时返回错误，由调用方记录
+    pub fn enter(&self, path: &Path, parent: &DirScope) -> io::Result<Option<DirScope>> {
+        self.scope_for(path, parent, true)
+    }
+
+    /// 与 `enter` 相同但不记录访问，用于逐级检查监听事件所在的目录
+    pub fn descend(&self, path: &Path, parent: &DirScope) -> Option<DirScope> {
+        self.scope_for(path, parent, false).ok().flatten()
+    }
+
+    fn scope_for(
+        &self,
+        path: &Path,
+        parent: &DirScope,
+        mark_visited: bool,
+    ) -> io::Result<Option<DirScope>> {
+        let metadata = std::fs::metadata(path)?;
+        if self.network_mounts.contains(path) {
+            return Ok(None);
+        }
+
+        let device = device_of(&metadata);
+        if self.options.one_file_system
+            && parent.device.is_some()
+            && device.is_some()
+            && parent.device != device
+        {
+            return Ok(None);
+        }
+
+        if let Some(key) = dir_key(path, &metadata).filter(|_| mark_visited) {
+            if !self.visited.lock().is_ok_and(|mut v| v.insert(key)) {
+                return Ok(None);
+            }
+        }
+
+        let mut scope = DirScope {
+            device: parent.device.or(device),
+            ignores: parent.ignores.clone(),
+        };
+        if self.options.respect_ignore_files {
+            for name in IGNORE_FILE_NAMES {
+                let file = path.join(name);
+                if file.is_file() {
+                    let (gitignore, _) = Gitignore::new(file);
+                    if !gitignore.is_empty() {
+                        scope.ignores.push(Arc::new(gitignore));
+                    }
+                }
+            }
+        }
+        Ok(Some(scope))
+    }
+
+    pub fn is_excluded(
+        &self,
+        name: &str,
+        path: &Path,
+        metadata: &Metadata,
+        scope: &DirScope,
+    ) -> bool {
+        if self.options.skip_hidden && is_hidden(name, metadata) {
+            return true;
+        }
+        if self.globs.is_match(name) || self.globs.is_match(path) {
+            return true;
+        }
+
+        // 越深的忽略文件优先级越高
+        for gitignore in scope.ignores.iter().rev() {
+            match gitignore.matched(path, metadata.is_dir()) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => continue,
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_globs() {
+        let rules = ExcludeRules::new(&ExcludeOptions::default()).unwrap();
+        let dir = std::env::temp_dir();
+        let metadata = std::fs::metadata(&dir).unwrap();
+        let scope = DirScope::default();
+
+        let excluded = |path: &str| {
+            let path = Path::new(path);
+            let name = path.file_name().unwrap().to_str().unwrap();
+            rules.is_excluded(name, path, &metadata, &scope)
+        };
+        assert!(excluded("/proc"));
+        assert!(excluded("/home/user/project/node_modules"));
+        assert!(excluded("/home/user/project/.git"));
+        assert!(!excluded("/home/user/project/src"));
+        assert!(!excluded("/home/user/procedures"));
+    }
+
+    #[test]
+    fn test_visited_once() {
+        let rules = ExcludeRules::new(&ExcludeOptions::default()).unwrap();
+        let dir = std::env::temp_dir();
+        let scope = rules.enter(&dir, &DirScope::default()).unwrap();
+        assert!(scope.is_some());
+        assert!(rules.enter(&dir, &scope.unwrap()).unwrap().is_none());
+        // 无法读取的目录返回错误，由遍历记录下来
+        let missing = dir.join(format!("tool-box-missing-{}", std::process::id()));
+        assert!(rules.enter(&missing, &DirScope::default()).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_unescape_mount_path() {
+        assert_eq!(unescape_mount_path("/mnt/my\\040share"), "/mnt/my share");
+        assert_eq!(unescape_mount_path("/mnt/plain"), "/mnt/plain");
+    }
+}
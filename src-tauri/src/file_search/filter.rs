@@ -0,0 +1,199 @@
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::file_type::{self, FileCategory};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum EntryKind {
+    #[default]
+    All,
+    File,
+    Dir,
+}
+
+/// 遍历过程中对匹配项的过滤条件，时间均为毫秒时间戳
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilter {
+    #[serde(default)]
+    pub kind: EntryKind,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub modified_after: Option<i64>,
+    pub modified_before: Option<i64>,
+    pub created_after: Option<i64>,
+    pub created_before: Option<i64>,
+    /// 扩展名不含点，不区分大小写
+    #[serde(default)]
+    pub include_extensions: Vec<String>,
+    #[serde(default)]
+    pub exclude_extensions: Vec<String>,
+    /// 1 表示只搜索所选目录的直接子项
+    pub max_depth: Option<usize>,
+    /// 按文件头识别的真实类型，设置后只命中文件，压缩包内的条目无法识别
+    #[serde(default)]
+    pub categories: Vec<FileCategory>,
+}
+
+pub fn to_millis(time: std::io::Result<SystemTime>) -> Option<i64> {
+    time.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+}
+
+fn in_range(value: Option<i64>, after: Option<i64>, before: Option<i64>) -> bool {
+    if after.is_none() && before.is_none() {
+        return true;
+    }
+    match value {
+        Some(v) => after.is_none_or(|a| v >= a) && before.is_none_or(|b| v <= b),
+        None => false,
+    }
+}
+
+fn normalize_extensions(list: &mut [String]) {
+    for ext in list.iter_mut() {
+        *ext = ext.trim().trim_start_matches('.').to_lowercase();
+    }
+}
+
+impl SearchFilter {
+    pub fn normalize(mut self) -> Self {
+        normalize_extensions(&mut self.include_extensions);
+        normalize_extensions(&mut self.exclude_extensions);
+        self
+    }
+
+    /// 是否继续进入下一层目录，`depth` 为当前目录中条目所在的层级
+    pub fn can_descend(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max| depth < max)
+    }
+
+    fn has_file_conditions(&self) -> bool {
+        self.min_size.is_some()
+            || self.max_size.is_some()
+            || !self.include_extensions.is_empty()
+            || !self.categories.is_empty()
+    }
+
+    /// 读取文件头判断类型，未设置类型条件时不读取文件
+    pub fn accepts_type(&self, path: &Path) -> bool {
+        if self.categories.is_empty() {
+            return true;
+        }
+        file_type::detect_file(path).is_ok_and(|t| self.categories.contains(&t.category))
+    }
+
+    pub fn accepts(&self, name: &str, metadata: &Metadata) -> bool {
+        self.accepts_entry(
+            name,
+            metadata.is_dir(),
+            metadata.len(),
+            to_millis(metadata.modified()),
+            to_millis(metadata.created()),
+        )
+    }
+
+    /// 按已知属性过滤，用于索引等无需再次读取元数据的场景
+    pub fn accepts_entry(
+        &self,
+        name: &str,
+        is_dir: bool,
+        size: u64,
+        modified: Option<i64>,
+        created: Option<i64>,
+    ) -> bool {
+        match self.kind {
+            EntryKind::File if is_dir => return false,
+            EntryKind::Dir if !is_dir => return false,
+            _ => {}
+        }
+
+        // 大小与扩展名只对文件有意义，设置后目录不再命中
+        if is_dir {
+            if self.has_file_conditions() {
+                return false;
+            }
+        } else {
+            if self.min_size.is_some_and(|min| size < min)
+                || self.max_size.is_some_and(|max| size > max)
+            {
+                return false;
+            }
+
+            let ext = Path::new(name)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if !self.include_extensions.is_empty() && !self.include_extensions.contains(&ext) {
+                return false;
+            }
+            if self.exclude_extensions.contains(&ext) {
+                return false;
+            }
+        }
+
+        in_range(modified, self.modified_after, self.modified_before)
+            && in_range(created, self.created_after, self.created_before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        assert!(in_range(None, None, None));
+        assert!(!in_range(None, Some(1), None));
+        assert!(in_range(Some(5), Some(1), Some(10)));
+        assert!(!in_range(Some(11), Some(1), Some(10)));
+    }
+
+    #[test]
+    fn test_can_descend() {
+        let filter = SearchFilter {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert!(filter.can_descend(1));
+        assert!(!filter.can_descend(2));
+        assert!(SearchFilter::default().can_descend(100));
+    }
+
+    #[test]
+    fn test_accepts_file() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("tool-box-filter-test.PDF");
+        std::fs::write(&path, vec![0u8; 100]).unwrap();
+        let metadata = std::fs::metadata(&path).unwrap();
+
+        let filter = SearchFilter {
+            include_extensions: vec![".pdf".to_string()],
+            min_size: Some(50),
+            ..Default::default()
+        }
+        .normalize();
+        assert!(filter.accepts("a.PDF", &metadata));
+        assert!(!filter.accepts("a.txt", &metadata));
+
+        let filter = SearchFilter {
+            max_size: Some(10),
+            ..Default::default()
+        };
+        assert!(!filter.accepts("a.pdf", &metadata));
+
+        let filter = SearchFilter {
+            kind: EntryKind::Dir,
+            ..Default::default()
+        };
+        assert!(!filter.accepts("a.pdf", &metadata));
+        assert!(filter.accepts("tmp", &std::fs::metadata(&dir).unwrap()));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,737 @@
+use std::collections::BTreeMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use anyhow::{Error, Result as AnyResult};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::utils::output::MessageSender;
+
+use super::exclude::{DirScope, ExcludeOptions, ExcludeRules};
+use super::filter::{to_millis, SearchFilter};
+use super::matcher::{MatchOptions, Matcher};
+use super::SearchResultModel;
+
+static INDEX_STATUS_EVENT: &str = "file-index-status";
+
+const INDEX_CONFIG_FILE_NAME: &str = "file-index-config.json";
+const INDEX_DATA_FILE_NAME: &str = "file-index.json";
+
+/// 有未保存的变更时，间隔多久写入一次磁盘
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// inotify 的递归监听会为每个子目录单独添加监听，因此只逐个监听未被排除的目录；
+/// 其他平台的递归监听只占用一个句柄，由事件过滤排除的路径
+const WATCH_PER_DIR: bool = cfg!(target_os = "linux");
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexConfig {
+    pub enabled: bool,
+    pub roots: Vec<String>,
+    #[serde(default)]
+    pub exclude: ExcludeOptions,
+    /// 定期全量校对的间隔（分钟），弥补监听遗漏的变更
+    pub reconcile_interval_minutes: u64,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            roots: vec![],
+            exclude: ExcludeOptions::default(),
+            reconcile_interval_minutes: 60,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexPhase {
+    Disabled,
+    Scanning,
+    Ready,
+    Failed(String),
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatus {
+    pub phase: IndexPhase,
+    pub entries: usize,
+    pub last_scan_at: Option<i64>,
+    /// 文件系统监听是否生效，未生效时仅依靠定期校对更新
+    pub watching: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct IndexEntry {
+    is_dir: bool,
+    size: u64,
+    modified: Option<i64>,
+    created: Option<i64>,
+}
+
+impl IndexEntry {
+    fn from_metadata(metadata: &Metadata) -> Self {
+        let is_dir = metadata.is_dir();
+        Self {
+            is_dir,
+            size: if is_dir { 0 } else { metadata.len() },
+            modified: to_millis(metadata.modified()),
+            created: to_millis(metadata.created()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexFile {
+    roots: Vec<String>,
+    last_scan_at: Option<i64>,
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexQueryPayload {
+    name: String,
+    #[serde(flatten)]
+    match_options: MatchOptions,
+    #[serde(default)]
+    filter: SearchFilter,
+    /// 只返回这些目录下的结果，为空时不限制
+    #[serde(default)]
+    disks: Vec<String>,
+    limit: Option<usize>,
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// 删除路径本身及其下所有条目
+fn remove_tree(entries: &mut BTreeMap<String, IndexEntry>, key: &str) {
+    entries.remove(key);
+    let prefix = format!("{}{}", key.trim_end_matches(MAIN_SEPARATOR), MAIN_SEPARATOR);
+    let children: Vec<String> = entries
+        .range(prefix.clone()..)
+        .take_while(|(k, _)| k.starts_with(&prefix))
+        .map(|(k, _)| k.clone())
+        .collect();
+    for child in children {
+        entries.remove(&child);
+    }
+}
+
+/// 阻塞遍历目录树，将条目写入 `entries`（不包含根目录本身），`scope` 为根目录上级的遍历状态
+fn scan_tree(
+    root: &Path,
+    scope: DirScope,
+    rules: &ExcludeRules,
+    entries: &mut BTreeMap<String, IndexEntry>,
+    is_cancelled: &dyn Fn() -> bool,
+) {
+    let mut stack = vec![(root.to_path_buf(), scope)];
+    while let Some((dir, parent)) = stack.pop() {
+        if is_cancelled() {
+            return;
+        }
+        let scope = match rules.enter(&dir, &parent) {
+            Ok(Some(scope)) => scope,
+            _ => continue,
+        };
+        let read_dir = match std::fs::read_dir(&dir) {
+            Ok(val) => val,
+            Err(_) => continue,
+        };
+        for entry in read_dir.flatten() {
+            let name = match entry.file_name().into_string() {
+                Ok(str) => str,
+                Err(_) => continue,
+            };
+            let metadata = match entry.metadata() {
+                Ok(val) => val,
+                Err(_) => continue,
+            };
+            let path = entry.path();
+            if rules.is_excluded(&name, &path, &metadata, &scope) {
+                continue;
+            }
+            let is_dir = metadata.is_dir()
+                || (metadata.is_symlink() && rules.follow_symlinks() && path.is_dir());
+            entries.insert(path_key(&path), IndexEntry::from_metadata(&metadata));
+            if is_dir {
+                stack.push((path, scope.clone()));
+            }
+        }
+    }
+}
+
+/// 从所属根目录开始逐级检查路径的上级目录，任一级被排除时返回 `None`，
+/// 否则返回上级目录的遍历状态
+fn parent_scope(rules: &ExcludeRules, roots: &[String], path: &Path) -> Option<DirScope> {
+    let root = roots.iter().map(Path::new).find(|r| path.starts_with(r))?;
+    if path == root {
+        return Some(DirScope::default());
+    }
+    let mut scope = rules.descend(root, &DirScope::default())?;
+    let mut current = root.to_path_buf();
+    for component in path.parent()?.strip_prefix(root).ok()?.components() {
+        current.push(component);
+        let metadata = std::fs::symlink_metadata(&current).ok()?;
+        let name = component.as_os_str().to_string_lossy();
+        if rules.is_excluded(&name, &current, &metadata, &scope) {
+            return None;
+        }
+        scope = rules.descend(&current, &scope)?;
+    }
+    Some(scope)
+}
+
+pub struct FileIndex {
+    sender: MessageSender,
+    entries: RwLock<BTreeMap<String, IndexEntry>>,
+    config: Mutex<IndexConfig>,
+    status: Mutex<IndexStatus>,
+    /// 用于过滤监听事件的排除规则，随配置更新
+    event_rules: Mutex<Option<Arc<ExcludeRules>>>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    /// 全量扫描期间收到的变更，扫描结束后重新应用
+    pending: Mutex<Option<Vec<PathBuf>>>,
+    /// 配置变更时递增，旧的扫描和校对任务据此退出
+    generation: AtomicU64,
+    dirty: AtomicBool,
+}
+
+impl FileIndex {
+    pub fn new(sender: MessageSender) -> Self {
+        Self {
+            sender,
+            entries: RwLock::new(BTreeMap::new()),
+            config: Mutex::new(IndexConfig::default()),
+            status: Mutex::new(IndexStatus {
+                phase: IndexPhase::Disabled,
+                entries: 0,
+                last_scan_at: None,
+                watching: false,
+            }),
+            event_rules: Mutex::new(None),
+            watcher: Mutex::new(None),
+            pending: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn config(&self) -> IndexConfig {
+        self.config.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    pub fn status(&self) -> IndexStatus {
+        let mut status = self
+            .status
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_else(|e| e.into_inner().clone());
+        status.entries = self.entries.read().map(|e| e.len()).unwrap_or(0);
+        status
+    }
+
+    fn update_status(&self, update: impl FnOnce(&mut IndexStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            update(&mut status);
+        }
+        self.sender.send(INDEX_STATUS_EVENT, self.status(), false);
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// 根据路径当前状态更新索引：存在则写入（新目录会遍历其子项），不存在则删除
+    fn refresh_path(&self, path: &Path, walk_dir: bool) {
+        let rules = match self.event_rules.lock().ok().and_then(|r| r.clone()) {
+            Some(rules) => rules,
+            None => return,
+        };
+        let key = path_key(path);
+
+        let metadata = match std::fs::symlink_metadata(path) {
+            Ok(val) => val,
+            Err(_) => {
+                if let Ok(mut entries) = self.entries.write() {
+                    remove_tree(&mut entries, &key);
+                }
+                self.dirty.store(true, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        let config = self.config();
+        let scope = match parent_scope(&rules, &config.roots, path) {
+            Some(val) => val,
+            None => return,
+        };
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if rules.is_excluded(&name, path, &metadata, &scope) {
+            return;
+        }
+
+        let mut subtree = BTreeMap::new();
+        if walk_dir && metadata.is_dir() {
+            if let Ok(walk_rules) = ExcludeRules::new(&config.exclude) {
+                scan_tree(path, scope, &walk_rules, &mut subtree, &|| false);
+            }
+            if WATCH_PER_DIR {
+                let dirs = subtree.iter().filter(|(_, e)| e.is_dir).map(|(k, _)| k);
+                self.watch_dirs(std::iter::once(&key).chain(dirs));
+            }
+        }
+
+        if let Ok(mut entries) = self.entries.write() {
+            if walk_dir {
+                remove_tree(&mut entries, &key);
+            }
+            entries.insert(key, IndexEntry::from_metadata(&metadata));
+            entries.extend(subtree);
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    fn handle_event(self: &Arc<Self>, event: Event) {
+        if event.need_rescan() {
+            let index = self.clone();
+            tauri::async_runtime::spawn(async move { index.rebuild().await });
+            return;
+        }
+
+        let walk_dir = match event.kind {
+            EventKind::Access(_) => return,
+            EventKind::Create(_) => true,
+            EventKind::Modify(notify::event::ModifyKind::Name(_)) => true,
+            _ => false,
+        };
+
+        if let Ok(mut pending) = self.pending.lock() {
+            if let Some(list) = pending.as_mut() {
+                list.extend(event.paths);
+                return;
+            }
+        }
+        for path in &event.paths {
+            self.refresh_path(path, walk_dir);
+        }
+    }
+
+    fn start_watcher(self: &Arc<Self>, config: &IndexConfig) -> bool {
+        let index = Arc::downgrade(self);
+        let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let (Ok(event), Some(index)) = (res, index.upgrade()) {
+                index.handle_event(event);
+            }
+        });
+        let mut watcher = match watcher {
+            Ok(val) => val,
+            Err(e) => {
+                println!("[file-search] 创建文件监听失败：{}", e);
+                return false;
+            }
+        };
+
+        let mode = if WATCH_PER_DIR {
+            RecursiveMode::NonRecursive
+        } else {
+            RecursiveMode::Recursive
+        };
+        let mut watching = true;
+        for root in &config.roots {
+            if let Err(e) = watcher.watch(Path::new(root), mode) {
+                // 如 inotify 监听数量达到上限，改为依赖定期校对
+                println!("[file-search] 监听目录失败：{}，错误：{}", root, e);
+                watching = false;
+            }
+        }
+        if let Ok(mut slot) = self.watcher.lock() {
+            *slot = Some(watcher);
+        }
+        watching
+    }
+
+    /// 逐个监听目录（不递归），监听失败时标记为未生效
+    fn watch_dirs<'a>(&self, dirs: impl Iterator<Item = &'a String>) {
+        let mut failed = false;
+        if let Ok(mut slot) = self.watcher.lock() {
+            let watcher = match slot.as_mut() {
+                Some(val) => val,
+                None => return,
+            };
+            for dir in dirs {
+                if let Err(e) = watcher.watch(Path::new(dir), RecursiveMode::NonRecursive) {
+                    println!("[file-search] 监听目录失败：{}，错误：{}", dir, e);
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        if failed && self.status().watching {
+            self.update_status(|s| s.watching = false);
+        }
+    }
+
+    /// 监听索引中的所有目录，排除的目录不在索引中因此不会被监听
+    fn watch_indexed_dirs(&self) {
+        if !WATCH_PER_DIR {
+            return;
+        }
+        let dirs: Vec<String> = match self.entries.read() {
+            Ok(entries) => entries
+                .iter()
+                .filter(|(_, e)| e.is_dir)
+                .map(|(k, _)| k.clone())
+                .collect(),
+            Err(_) => return,
+        };
+        self.watch_dirs(dirs.iter());
+    }
+
+    /// 全量扫描所有根目录并替换索引
+    pub async fn rebuild(self: &Arc<Self>) {
+        let config = self.config();
+        if !config.enabled {
+            return;
+        }
+        let generation = self.generation.load(Ordering::SeqCst);
+        let rules = match ExcludeRules::new(&config.exclude) {
+            Ok(val) => val,
+            Err(e) => {
+                self.update_status(|s| s.phase = IndexPhase::Failed(e.to_string()));
+                return;
+            }
+        };
+
+        if let Ok(mut pending) = self.pending.lock() {
+            if pending.is_some() {
+                // 已有扫描在进行
+                return;
+            }
+            *pending = Some(vec![]);
+        }
+        self.update_status(|s| s.phase = IndexPhase::Scanning);
+
+        let index = self.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            let mut entries = BTreeMap::new();
+            for root in &config.roots {
+                scan_tree(
+                    Path::new(root),
+                    DirScope::default(),
+                    &rules,
+                    &mut entries,
+                    &|| !index.is_current(generation),
+                );
+            }
+            entries
+        })
+        .await;
+
+        // 配置已变更时，待应用的变更属于新的扫描
+        if !self.is_current(generation) {
+            return;
+        }
+        let pending = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut p| p.take())
+            .unwrap_or_default();
+
+        match result {
+            Ok(entries) => {
+                if let Ok(mut current) = self.entries.write() {
+                    *current = entries;
+                }
+                self.watch_indexed_dirs();
+                for path in pending {
+                    self.refresh_path(&path, true);
+                }
+                self.dirty.store(true, Ordering::Relaxed);
+                let now = chrono::Utc::now().timestamp_millis();
+                self.update_status(|s| {
+                    s.phase = IndexPhase::Ready;
+                    s.last_scan_at = Some(now);
+                });
+            }
+            Err(e) => self.update_status(|s| s.phase = IndexPhase::Failed(e.to_string())),
+        }
+    }
+
+    /// 按配置重新启动监听与定期校对，关闭时清空索引
+    pub async fn apply_config<R: tauri::Runtime>(
+        self: &Arc<Self>,
+        app: &AppHandle<R>,
+        config: IndexConfig,
+        rebuild: bool,
+    ) -> AnyResult<()> {
+        let event_rules = Arc::new(ExcludeRules::new(&config.exclude)?);
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Ok(mut slot) = self.config.lock() {
+            *slot = config.clone();
+        }
+        if let Ok(mut slot) = self.watcher.lock() {
+            *slot = None;
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = None;
+        }
+
+        if !config.enabled {
+            if let Ok(mut entries) = self.entries.write() {
+                entries.clear();
+            }
+            if let Ok(mut slot) = self.event_rules.lock() {
+                *slot = None;
+            }
+            self.update_status(|s| {
+                s.phase = IndexPhase::Disabled;
+                s.watching = false;
+            });
+            return Ok(());
+        }
+
+        if let Ok(mut slot) = self.event_rules.lock() {
+            *slot = Some(event_rules);
+        }
+        let watching = self.start_watcher(&config);
+        self.update_status(|s| s.watching = watching);
+        if !rebuild {
+            self.watch_indexed_dirs();
+        }
+
+        let index = self.clone();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if rebuild {
+                index.rebuild().await;
+            }
+            index.maintain(&app, generation).await;
+        });
+        Ok(())
+    }
+
+    /// 定期保存变更并按间隔全量校对，配置变更后退出
+    async fn maintain<R: tauri::Runtime>(self: &Arc<Self>, app: &AppHandle<R>, generation: u64) {
+        let mut last_save = tokio::time::Instant::now();
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            if !self.is_current(generation) {
+                return;
+            }
+
+            let interval = self.config().reconcile_interval_minutes.max(1) as i64 * 60_000;
+            let last_scan_at = self.status().last_scan_at.unwrap_or(0);
+            if chrono::Utc::now().timestamp_millis() - last_scan_at >= interval {
+                self.rebuild().await;
+            }
+
+            if self.dirty.load(Ordering::Relaxed) && last_save.elapsed() >= SAVE_INTERVAL {
+                if let Err(e) = self.save_entries(app).await {
+                    println!("[file-search] 保存索引失败：{}", e);
+                }
+                last_save = tokio::time::Instant::now();
+            }
+        }
+    }
+
+    pub fn query(&self, payload: &IndexQueryPayload) -> AnyResult<Vec<SearchResultModel>> {
+        let matcher = Matcher::new(&payload.match_options, &payload.name)?;
+        let filter = payload.filter.clone().normalize();
+        let limit = payload.limit.unwrap_or(1000);
+        let entries = self
+            .entries
+            .read()
+            .map_err(|_| Error::msg("索引数据不可用"))?;
+
+        let mut results: Vec<SearchResultModel> = entries
+            .iter()
+            .filter(|(path, _)| {
+                payload.disks.is_empty() || payload.disks.iter().any(|d| path.starts_with(d))
+            })
+            .filter_map(|(path, entry)| {
+                let name = Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())?;
+                let score = matcher.matches(&name, path)?;
+                (filter.accepts_entry(
+                    &name,
+                    entry.is_dir,
+                    entry.size,
+                    entry.modified,
+                    entry.created,
+                ) && (entry.is_dir || filter.accepts_type(Path::new(path))))
+                .then(|| SearchResultModel {
+                    score: matcher.is_fuzzy().then_some(score),
+                    ..SearchResultModel::new(
+                        path.clone(),
+                        entry.is_dir,
+                        entry.size,
+                        entry.modified,
+                        entry.created,
+                    )
+                })
+            })
+            .take(if matcher.is_fuzzy() {
+                usize::MAX
+            } else {
+                limit
+            })
+            .collect();
+
+        if matcher.is_fuzzy() {
+            results.sort_by_key(|r| std::cmp::Reverse(r.score));
+            results.truncate(limit);
+        }
+        Ok(results)
+    }
+
+    pub async fn save_entries<R: tauri::Runtime>(&self, app: &AppHandle<R>) -> AnyResult<()> {
+        let path = data_file_path(app, INDEX_DATA_FILE_NAME)?;
+        let json = {
+            let entries = self
+                .entries
+                .read()
+                .map_err(|_| Error::msg("索引数据不可用"))?;
+            serde_json::to_string(&IndexFile {
+                roots: self.config().roots,
+                last_scan_at: self.status().last_scan_at,
+                entries: entries.clone(),
+            })?
+        };
+        self.dirty.store(false, Ordering::Relaxed);
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+
+    /// 读取上次保存的索引，根目录与当前配置一致时才使用
+    async fn load_entries<R: tauri::Runtime>(&self, app: &AppHandle<R>) -> bool {
+        let content = match data_file_path(app, INDEX_DATA_FILE_NAME) {
+            Ok(path) => tokio::fs::read_to_string(path).await.ok(),
+            Err(_) => None,
+        };
+        let file: IndexFile = match content.and_then(|c| serde_json::from_str(&c).ok()) {
+            Some(val) => val,
+            None => return false,
+        };
+        if file.roots != self.config().roots {
+            return false;
+        }
+        if let Ok(mut entries) = self.entries.write() {
+            *entries = file.entries;
+        }
+        self.update_status(|s| {
+            s.phase = IndexPhase::Ready;
+            s.last_scan_at = file.last_scan_at;
+        });
+        true
+    }
+
+    pub async fn save_config<R: tauri::Runtime>(&self, app: &AppHandle<R>) -> AnyResult<()> {
+        let path = data_file_path(app, INDEX_CONFIG_FILE_NAME)?;
+        let json = serde_json::to_string_pretty(&self.config())?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+}
+
+fn data_file_path<R: tauri::Runtime>(app: &AppHandle<R>, name: &str) -> AnyResult<PathBuf> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| Error::msg("无法获取应用数据目录"))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(name))
+}
+
+/// 启动时读取配置和已保存的索引，之后按校对间隔在后台更新
+pub fn start<R: tauri::Runtime>(app: AppHandle<R>, index: Arc<FileIndex>) {
+    tauri::async_runtime::spawn(async move {
+        let config: IndexConfig = match data_file_path(&app, INDEX_CONFIG_FILE_NAME) {
+            Ok(path) => tokio::fs::read_to_string(path)
+                .await
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .unwrap_or_default(),
+            Err(_) => IndexConfig::default(),
+        };
+        if let Ok(mut slot) = index.config.lock() {
+            *slot = config.clone();
+        }
+        if !config.enabled {
+            return;
+        }
+
+        let loaded = index.load_entries(&app).await;
+        if let Err(e) = index.apply_config(&app, config, !loaded).await {
+            index.update_status(|s| s.phase = IndexPhase::Failed(e.to_string()));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> IndexEntry {
+        IndexEntry {
+            is_dir: false,
+            size: 0,
+            modified: None,
+            created: None,
+        }
+    }
+
+    #[test]
+    fn test_remove_tree() {
+        let sep = MAIN_SEPARATOR;
+        let mut entries = BTreeMap::new();
+        for key in ["a", "a{s}b", "a{s}b{s}c", "a{s}b-x", "a{s}bc"] {
+            entries.insert(key.replace("{s}", &sep.to_string()), entry());
+        }
+        remove_tree(&mut entries, &format!("a{}b", sep));
+        let keys: Vec<String> = entries.keys().cloned().collect();
+        assert_eq!(
+            keys,
+            vec![
+                "a".to_string(),
+                format!("a{}b-x", sep),
+                format!("a{}bc", sep)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parent_scope_checks_ancestors() {
+        let root = std::env::temp_dir().join(format!("tool-box-index-{}", std::process::id()));
+        let nested = root.join("node_modules").join("pkg");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+
+        let rules = ExcludeRules::new(&ExcludeOptions::default()).unwrap();
+        let roots = vec![root.to_string_lossy().to_string()];
+        assert!(parent_scope(&rules, &roots, &nested.join("index.js")).is_none());
+        assert!(parent_scope(&rules, &roots, &root.join("src").join("main.rs")).is_some());
+        assert!(parent_scope(&rules, &roots, Path::new("/elsewhere/a.txt")).is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
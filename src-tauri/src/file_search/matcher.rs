@@ -0,0 +1,283 @@
+use anyhow::{Error, Result as AnyResult};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchMode {
+    /// 区分大小写的包含匹配
+    #[default]
+    Substring,
+    /// 不区分大小写的包含匹配
+    IgnoreCase,
+    /// 通配符，如 `*.log`、`report-??.xlsx`
+    Glob,
+    Regex,
+    /// 按顺序包含全部字符即可匹配，结果附带评分
+    Fuzzy,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TermLogic {
+    #[default]
+    And,
+    Or,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTerm {
+    pub pattern: String,
+    /// 为 true 时表示排除匹配该条件的结果（NOT）
+    #[serde(default)]
+    pub negate: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchOptions {
+    #[serde(default)]
+    pub match_mode: MatchMode,
+    /// 为空时使用 `name` 作为唯一的匹配条件
+    #[serde(default)]
+    pub terms: Vec<SearchTerm>,
+    #[serde(default)]
+    pub term_logic: TermLogic,
+    /// 匹配完整路径而非仅文件名
+    #[serde(default)]
+    pub match_full_path: bool,
+}
+
+enum Pattern {
+    Substring(String),
+    IgnoreCase(String),
+    Glob(GlobMatcher),
+    Regex(Regex),
+    Fuzzy(Vec<char>),
+}
+
+impl Pattern {
+    fn new(mode: MatchMode, pattern: &str) -> AnyResult<Self> {
+        Ok(match mode {
+            MatchMode::Substring => Pattern::Substring(pattern.to_string()),
+            MatchMode::IgnoreCase => Pattern::IgnoreCase(pattern.to_lowercase()),
+            MatchMode::Glob => {
+                let glob = GlobBuilder::new(pattern)
+                    .case_insensitive(cfg!(windows))
+                    .build()
+                    .map_err(|e| Error::msg(format!("无效的通配符：{}", e)))?;
+                Pattern::Glob(glob.compile_matcher())
+            }
+            MatchMode::Regex => Pattern::Regex(
+                Regex::new(pattern).map_err(|e| Error::msg(format!("无效的正则表达式：{}", e)))?,
+            ),
+            MatchMode::Fuzzy => Pattern::Fuzzy(pattern.to_lowercase().chars().collect()),
+        })
+    }
+
+    /// 匹配成功时返回评分，仅模糊匹配的评分有意义
+    fn score(&self, text: &str) -> Option<i64> {
+        match self {
+            Pattern::Substring(p) => text.contains(p.as_str()).then_some(0),
+            Pattern::IgnoreCase(p) => text.to_lowercase().contains(p.as_str()).then_some(0),
+            Pattern::Glob(g) => g.is_match(text).then_some(0),
+            Pattern::Regex(r) => r.is_match(text).then_some(0),
+            Pattern::Fuzzy(p) => fuzzy_score(p, text),
+        }
+    }
+}
+
+/// 子序列匹配评分：连续命中和位于单词开头的字符加分，间隔越大扣分越多
+fn fuzzy_score(pattern: &[char], text: &str) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut matched = 0;
+    let mut prev: Option<usize> = None;
+
+    for (i, c) in chars.iter().enumerate() {
+        if matched == pattern.len() {
+            break;
+        }
+        if *c != pattern[matched] {
+            continue;
+        }
+        score += 1;
+        score += match prev {
+            Some(p) if p + 1 == i => 5,
+            Some(p) => -((i - p - 1).min(5) as i64),
+            None => -(i.min(10) as i64),
+        };
+        if i == 0 || matches!(chars[i - 1], '/' | '\\' | '_' | '-' | '.' | ' ') {
+            score += 8;
+        }
+        prev = Some(i);
+        matched += 1;
+    }
+
+    (matched == pattern.len()).then_some(score)
+}
+
+pub struct Matcher {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    logic: TermLogic,
+    full_path: bool,
+    fuzzy: bool,
+}
+
+impl Matcher {
+    pub fn new(options: &MatchOptions, name: &str) -> AnyResult<Self> {
+        let fallback = [SearchTerm {
+            pattern: name.to_string(),
+            negate: false,
+        }];
+        let terms = if options.terms.is_empty() {
+            &fallback[..]
+        } else {
+            &options.terms[..]
+        };
+
+        let mut include = vec![];
+        let mut exclude = vec![];
+        for term in terms {
+            let pattern = Pattern::new(options.match_mode, &term.pattern)?;
+            if term.negate {
+                exclude.push(pattern);
+            } else {
+                include.push(pattern);
+            }
+        }
+
+        Ok(Self {
+            include,
+            exclude,
+            logic: options.term_logic,
+            full_path: options.match_full_path,
+            fuzzy: options.match_mode == MatchMode::Fuzzy,
+        })
+    }
+
+    pub fn is_fuzzy(&self) -> bool {
+        self.fuzzy
+    }
+
+    /// 匹配成功时返回评分，不匹配返回 None
+    pub fn matches(&self, name: &str, path: &str) -> Option<i64> {
+        let text = if self.full_path { path } else { name };
+
+        if self.exclude.iter().any(|p| p.score(text).is_some()) {
+            return None;
+        }
+
+        let mut scores = self.include.iter().map(|p| p.score(text));
+        let score = match self.logic {
+            TermLogic::And => scores.try_fold(0, |acc, s| s.map(|s| acc + s)),
+            TermLogic::Or => scores.flatten().max(),
+        };
+        // 只有排除条件时，未被排除即视为匹配
+        if self.include.is_empty() {
+            Some(0)
+        } else {
+            score
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(mode: MatchMode, terms: &[(&str, bool)], logic: TermLogic) -> Matcher {
+        let options = MatchOptions {
+            match_mode: mode,
+            terms: terms
+                .iter()
+                .map(|(p, n)| SearchTerm {
+                    pattern: p.to_string(),
+                    negate: *n,
+                })
+                .collect(),
+            term_logic: logic,
+            match_full_path: false,
+        };
+        Matcher::new(&options, "").unwrap()
+    }
+
+    #[test]
+    fn test_modes() {
+        let m = matcher(MatchMode::Substring, &[("Report", false)], TermLogic::And);
+        assert!(m.matches("Report.xlsx", "").is_some());
+        assert!(m.matches("report.xlsx", "").is_none());
+
+        let m = matcher(MatchMode::IgnoreCase, &[("Report", false)], TermLogic::And);
+        assert!(m.matches("report.xlsx", "").is_some());
+
+        let m = matcher(
+            MatchMode::Glob,
+            &[("report-??.xlsx", false)],
+            TermLogic::And,
+        );
+        assert!(m.matches("report-01.xlsx", "").is_some());
+        assert!(m.matches("report-001.xlsx", "").is_none());
+
+        let m = matcher(
+            MatchMode::Regex,
+            &[(r"^\d{4}-\d{2}\.log$", false)],
+            TermLogic::And,
+        );
+        assert!(m.matches("2024-05.log", "").is_some());
+        assert!(m.matches("x2024-05.log", "").is_none());
+    }
+
+    #[test]
+    fn test_term_logic() {
+        let m = matcher(
+            MatchMode::Glob,
+            &[("*.log", false), ("*.txt", false), ("debug*", true)],
+            TermLogic::Or,
+        );
+        assert!(m.matches("app.log", "").is_some());
+        assert!(m.matches("readme.txt", "").is_some());
+        assert!(m.matches("debug.log", "").is_none());
+
+        let m = matcher(
+            MatchMode::IgnoreCase,
+            &[("foo", false), ("bar", false)],
+            TermLogic::And,
+        );
+        assert!(m.matches("foo-bar.rs", "").is_some());
+        assert!(m.matches("foo.rs", "").is_none());
+
+        let m = matcher(MatchMode::Substring, &[("tmp", true)], TermLogic::And);
+        assert!(m.matches("main.rs", "").is_some());
+        assert!(m.matches("a.tmp", "").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        let m = matcher(MatchMode::Fuzzy, &[("fsr", false)], TermLogic::And);
+        let exact = m.matches("file_search.rs", "").unwrap();
+        let loose = m.matches("final_summary_report.txt", "").unwrap();
+        assert!(m.matches("main.rs", "").is_none());
+        assert!(exact > loose);
+        assert_eq!(fuzzy_score(&[], "abc"), Some(0));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let options = MatchOptions {
+            match_mode: MatchMode::Regex,
+            terms: vec![SearchTerm {
+                pattern: "(".to_string(),
+                negate: false,
+            }],
+            ..Default::default()
+        };
+        assert!(Matcher::new(&options, "").is_err());
+    }
+}
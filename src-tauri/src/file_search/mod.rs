@@ -0,0 +1,984 @@
+mod cleanup;
+mod content;
+mod duplicates;
+mod exclude;
+mod filter;
+mod index;
+mod matcher;
+mod results;
+mod saved;
+mod session;
+mod stats;
+mod usage;
+mod walker;
+
+use std::fs::Metadata;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Error, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+use tauri::{
+    async_runtime::JoinHandle,
+    plugin::{Builder, TauriPlugin},
+    Manager, State,
+};
+use tokio::sync::mpsc;
+use tokio::time;
+
+use crate::utils::archive;
+use crate::utils::file_type::{self, FileType};
+use crate::utils::output::{Message, MessageSender};
+
+use cleanup::{CleanReport, CleanTarget, CleanupCollector, CleanupOutput, CleanupPayload};
+use content::{ContentMatcher, ContentOptions};
+use duplicates::{DuplicateOutput, ResolveDuplicatesPayload, ResolveReport};
+use exclude::{ExcludeOptions, ExcludeRules, DEFAULT_EXCLUDE_GLOBS};
+use filter::{to_millis, EntryKind, SearchFilter};
+use index::{FileIndex, IndexConfig, IndexQueryPayload, IndexStatus};
+use matcher::{MatchOptions, Matcher};
+use results::{ExportPayload, ResultPage, ResultQuery, ResultStore};
+use saved::{SaveSearchPayload, SavedSearch, SearchHistoryEntry, SearchLibrary};
+use session::{SearchControl, SearchRegistry, SearchTaskInfo};
+use usage::{DiskUsageOutput, DiskUsagePayload, UsageCache, UsageNode, UsageTree};
+
+static SEARCH_STREAM_EVENT: &'static str = "search-disk-file-output";
+static SEARCH_PROGRESS_EVENT: &'static str = "search-disk-file-progress";
+static DUPLICATE_STREAM_EVENT: &'static str = "duplicate-file-output";
+static DISK_USAGE_EVENT: &'static str = "disk-usage-output";
+static CLEANUP_EVENT: &'static str = "cleanup-scan-output";
+
+/// 推送搜索进度的间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDiskFilePayload {
+    name: String,
+    concurrent: usize,
+    disks: Vec<String>,
+    /// 可由前端指定，避免在拿到返回值之前漏掉结果事件
+    search_id: Option<String>,
+    #[serde(flatten)]
+    match_options: MatchOptions,
+    #[serde(default)]
+    filter: SearchFilter,
+    #[serde(default)]
+    exclude: ExcludeOptions,
+    /// 设置后按文件内容搜索，文件名规则用于筛选候选文件
+    content: Option<ContentOptions>,
+    /// 同时按名称搜索 zip、tar、7z 压缩包内的条目，内容搜索时不生效
+    #[serde(default)]
+    archives: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultModel {
+    path: String,
+    is_dir: bool,
+    /// 目录为 0
+    size: u64,
+    /// 毫秒时间戳，平台不支持时为空
+    modified: Option<i64>,
+    created: Option<i64>,
+    /// 小写且不含点，目录为空字符串
+    extension: String,
+    parent: String,
+    /// 模糊匹配的评分，越高越接近
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<i64>,
+    /// 内容搜索命中的行号（从 1 开始）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<String>,
+}
+
+impl SearchResultModel {
+    fn new(
+        path: String,
+        is_dir: bool,
+        size: u64,
+        modified: Option<i64>,
+        created: Option<i64>,
+    ) -> Self {
+        let p = Path::new(&path);
+        let extension = if is_dir {
+            String::new()
+        } else {
+            p.extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default()
+        };
+        let parent = p
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Self {
+            path,
+            is_dir,
+            size: if is_dir { 0 } else { size },
+            modified,
+            created,
+            extension,
+            parent,
+            score: None,
+            line: None,
+            snippet: None,
+        }
+    }
+
+    fn from_metadata(path: String, metadata: &Metadata) -> Self {
+        Self::new(
+            path,
+            metadata.is_dir(),
+            metadata.len(),
+            to_millis(metadata.modified()),
+            to_millis(metadata.created()),
+        )
+    }
+}
+
+/// 遍历过程中共享的匹配与过滤规则
+struct SearchOptions {
+    matcher: Matcher,
+    filter: SearchFilter,
+    exclude: ExcludeRules,
+    content: Option<ContentMatcher>,
+    /// 结果路径形如 `archive.zip!/inner/path`
+    archives: bool,
+}
+
+/// 搜索结果事件，`results` 为空表示该搜索已结束
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SearchOutput {
+    search_id: String,
+    results: Option<Vec<SearchResultModel>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedFileType {
+    #[serde(flatten)]
+    file_type: FileType,
+    /// 扩展名与文件内容不符时为 false，可用于发现改过扩展名的文件
+    extension_matches: bool,
+}
+
+/// 单次搜索的结束状态
+#[derive(Default, Clone, Debug)]
+struct SearchOutcome {
+    result_count: u64,
+    cancelled: bool,
+    /// 需要时记录的全部结果路径，供保存的搜索对比新文件
+    paths: Vec<String>,
+}
+
+/// 遍历所选目录并定时推送进度（`sender` 为空时不推送），每批结果交给 `on_batch` 处理
+async fn walk_with_progress<F>(
+    search_id: &str,
+    disks: Vec<String>,
+    concurrent: usize,
+    options: Arc<SearchOptions>,
+    control: &Arc<SearchControl>,
+    sender: Option<&MessageSender>,
+    mut on_batch: F,
+) where
+    F: FnMut(Vec<SearchResultModel>),
+{
+    // 有界通道：前端处理不过来时遍历线程会等待，避免结果堆积
+    let (tx, mut rx) = mpsc::channel(concurrent.max(1) * 2);
+    walker::spawn(disks, options, concurrent, tx, control.clone());
+
+    let mut interval = time::interval(PROGRESS_INTERVAL);
+    loop {
+        tokio::select! {
+            received = rx.recv() => {
+                match received {
+                    Some(results) => on_batch(results),
+                    None => break,
+                }
+            }
+            _ = interval.tick() => {
+                if let Some(sender) = sender {
+                    let progress = control.stats().snapshot(search_id, false);
+                    sender.send(SEARCH_PROGRESS_EVENT, progress, false);
+                }
+            }
+        }
+    }
+}
+
+/// `store` 为空时是后台运行，结果不写入结果集，也不推送给前端
+async fn do_search_task(
+    search_id: String,
+    payload: SearchDiskFilePayload,
+    options: Arc<SearchOptions>,
+    control: Arc<SearchControl>,
+    sender: MessageSender,
+    store: Option<Arc<ResultStore>>,
+    collect_paths: bool,
+) -> SearchOutcome {
+    let SearchDiskFilePayload {
+        disks, concurrent, ..
+    } = payload;
+
+    if let Some(store) = &store {
+        store.create(&search_id);
+    }
+    let mut paths = vec![];
+    walk_with_progress(
+        &search_id,
+        disks,
+        concurrent,
+        options,
+        &control,
+        store.is_some().then_some(&sender),
+        |results| {
+            if collect_paths {
+                paths.extend(results.iter().map(|r| r.path.clone()));
+            }
+            if let Some(store) = &store {
+                store.append(&search_id, &results);
+                let output = SearchOutput {
+                    search_id: search_id.clone(),
+                    results: Some(results),
+                };
+                sender.send(SEARCH_STREAM_EVENT, output, false);
+            }
+        },
+    )
+    .await;
+
+    let progress = control.stats().snapshot(&search_id, true);
+    let outcome = SearchOutcome {
+        result_count: progress.matches,
+        cancelled: control.is_cancelled(),
+        paths,
+    };
+    if store.is_none() {
+        return outcome;
+    }
+    // 结束事件之前推送最终统计
+    sender.send(SEARCH_PROGRESS_EVENT, progress, false);
+
+    let output = SearchOutput {
+        search_id,
+        results: None,
+    };
+    sender.send(SEARCH_STREAM_EVENT, output, false);
+    outcome
+}
+
+/// 先遍历收集文件，再在阻塞线程中比较内容，每确认一组推送一次
+async fn do_duplicate_task(
+    search_id: String,
+    payload: SearchDiskFilePayload,
+    options: Arc<SearchOptions>,
+    control: Arc<SearchControl>,
+    sender: MessageSender,
+) {
+    let SearchDiskFilePayload {
+        disks, concurrent, ..
+    } = payload;
+
+    let mut files = vec![];
+    walk_with_progress(
+        &search_id,
+        disks,
+        concurrent,
+        options,
+        &control,
+        Some(&sender),
+        |results| {
+            files.extend(
+                results
+                    .into_iter()
+                    .filter(|r| !r.is_dir)
+                    .map(|r| (r.path, r.size)),
+            );
+        },
+    )
+    .await;
+
+    let id = search_id.clone();
+    let task_control = control.clone();
+    let task_sender = sender.clone();
+    let (group_count, total_reclaimable) = tauri::async_runtime::spawn_blocking(move || {
+        let mut group_count = 0;
+        let mut total_reclaimable = 0;
+        duplicates::find_duplicates(files, &task_control, |group| {
+            group_count += 1;
+            total_reclaimable += group.reclaimable;
+            let output = DuplicateOutput {
+                search_id: id.clone(),
+                group: Some(group),
+                group_count,
+                total_reclaimable,
+            };
+            task_sender.send(DUPLICATE_STREAM_EVENT, output, false);
+            true
+        });
+        (group_count, total_reclaimable)
+    })
+    .await
+    .unwrap_or_default();
+
+    let progress = control.stats().snapshot(&search_id, true);
+    sender.send(SEARCH_PROGRESS_EVENT, progress, false);
+
+    let output = DuplicateOutput {
+        search_id,
+        group: None,
+        group_count,
+        total_reclaimable,
+    };
+    sender.send(DUPLICATE_STREAM_EVENT, output, false);
+}
+
+/// 统计目录占用，扫描中定时推送根目录的部分结果，完成后写入缓存
+async fn do_disk_usage_task(
+    search_id: String,
+    payload: DiskUsagePayload,
+    options: Arc<SearchOptions>,
+    control: Arc<SearchControl>,
+    sender: MessageSender,
+    cache: Arc<UsageCache>,
+) {
+    let top = payload.top();
+    let mut tree = UsageTree::new(&payload.root, top);
+    let mut last_emit = Instant::now();
+    walk_with_progress(
+        &search_id,
+        vec![payload.root.clone()],
+        payload.concurrent,
+        options,
+        &control,
+        Some(&sender),
+        |results| {
+            for r in results {
+                tree.add(&r.path, r.is_dir, r.size);
+            }
+            if last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                let output = DiskUsageOutput {
+                    search_id: search_id.clone(),
+                    tree: tree.node(tree.root(), top),
+                    top_dirs: vec![],
+                    top_files: vec![],
+                    finished: false,
+                };
+                sender.send(DISK_USAGE_EVENT, output, false);
+            }
+        },
+    )
+    .await;
+
+    let progress = control.stats().snapshot(&search_id, true);
+    sender.send(SEARCH_PROGRESS_EVENT, progress, false);
+
+    let output = DiskUsageOutput {
+        search_id,
+        tree: tree.node(tree.root(), top),
+        top_dirs: tree.top_dirs(),
+        top_files: tree.top_files(),
+        finished: true,
+    };
+    sender.send(DISK_USAGE_EVENT, output, false);
+    // 取消的扫描结果不完整，不写入缓存
+    if !control.is_cancelled() {
+        cache.insert(tree);
+    }
+}
+
+/// 遍历结束后整理各类可清理项，一次性推送
+async fn do_cleanup_task(
+    search_id: String,
+    payload: CleanupPayload,
+    options: Arc<SearchOptions>,
+    control: Arc<SearchControl>,
+    sender: MessageSender,
+) {
+    let mut collector = CleanupCollector::new(&payload, chrono::Local::now().timestamp_millis());
+    walk_with_progress(
+        &search_id,
+        vec![payload.root.clone()],
+        payload.concurrent,
+        options,
+        &control,
+        Some(&sender),
+        |results| {
+            for r in &results {
+                collector.add(r);
+            }
+        },
+    )
+    .await;
+
+    let progress = control.stats().snapshot(&search_id, true);
+    sender.send(SEARCH_PROGRESS_EVENT, progress, false);
+
+    let groups = tauri::async_runtime::spawn_blocking(move || collector.finish())
+        .await
+        .unwrap_or_default();
+    let output = CleanupOutput { search_id, groups };
+    sender.send(CLEANUP_EVENT, output, false);
+}
+
+fn build_search_options(payload: &SearchDiskFilePayload) -> AnyResult<SearchOptions> {
+    Ok(SearchOptions {
+        matcher: Matcher::new(&payload.match_options, &payload.name)?,
+        filter: payload.filter.clone().normalize(),
+        exclude: ExcludeRules::new(&payload.exclude)?,
+        content: payload
+            .content
+            .as_ref()
+            .map(ContentMatcher::new)
+            .transpose()?,
+        archives: payload.archives && payload.content.is_none(),
+    })
+}
+
+/// 匹配全部条目、只应用排除规则的遍历参数，用于磁盘占用与清理扫描
+fn walk_all_options(exclude: &ExcludeOptions) -> AnyResult<SearchOptions> {
+    Ok(SearchOptions {
+        matcher: Matcher::new(&MatchOptions::default(), "")?,
+        filter: SearchFilter::default(),
+        exclude: ExcludeRules::new(exclude)?,
+        content: None,
+        archives: false,
+    })
+}
+
+/// 校验参数后在后台开始搜索，返回的任务在搜索结束后给出结果
+///
+/// `background` 为 true 时（定时运行保存的搜索）不占用结果集、不推送结果、不写入搜索历史，
+/// `collect_paths` 为 true 时在结果中附带所有路径
+fn start_search(
+    app: &tauri::AppHandle,
+    payload: SearchDiskFilePayload,
+    background: bool,
+    collect_paths: bool,
+) -> AnyResult<(String, JoinHandle<SearchOutcome>)> {
+    let options = Arc::new(build_search_options(&payload)?);
+    let (search_id, control) = app
+        .state::<SearchRegistry>()
+        .create(payload.search_id.clone(), &payload.name, &payload.disks)
+        .ok_or(Error::msg("搜索 ID 已存在"))?;
+
+    let sender = MessageSender::new(app.clone(), "file-search");
+    let store = (!background).then(|| app.state::<Arc<ResultStore>>().inner().clone());
+    let app = app.clone();
+    let id = search_id.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let started_at = chrono::Local::now().timestamp_millis();
+        let mut history_payload = payload.clone();
+        history_payload.search_id = None;
+
+        let outcome = do_search_task(
+            id.clone(),
+            payload,
+            options,
+            control,
+            sender,
+            store,
+            collect_paths,
+        )
+        .await;
+        app.state::<SearchRegistry>().remove(&id);
+        if background {
+            return outcome;
+        }
+
+        let library = app.state::<SearchLibrary>();
+        library
+            .push_history(SearchHistoryEntry {
+                search_id: id,
+                payload: history_payload,
+                started_at,
+                finished_at: chrono::Local::now().timestamp_millis(),
+                result_count: outcome.result_count,
+                cancelled: outcome.cancelled,
+            })
+            .await;
+        if let Err(e) = library.save_history(&app).await {
+            println!("[file-search] 保存搜索历史失败：{}", e);
+        }
+        outcome
+    });
+
+    Ok((search_id, handle))
+}
+
+/// 开始搜索并立即返回搜索 ID，结果通过事件推送
+#[tauri::command]
+pub async fn search_disk_file_real_time(
+    payload: SearchDiskFilePayload,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    match start_search(&app_handle, payload, false, false) {
+        Ok((search_id, _)) => Ok(Message::success(Some(search_id))),
+        Err(e) => Ok(Message::failure(&e.to_string())),
+    }
+}
+
+/// 最近运行过的搜索，最新的在前
+#[tauri::command]
+pub async fn list_search_history(
+    library: State<'_, SearchLibrary>,
+) -> Result<Message<Vec<SearchHistoryEntry>>, String> {
+    Ok(Message::success(Some(library.history().await)))
+}
+
+#[tauri::command]
+pub async fn clear_search_history(
+    library: State<'_, SearchLibrary>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    library.clear_history().await;
+    match library.save_history(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("已清除")))),
+        Err(e) => Ok(Message::failure(&format!("保存失败：{}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_saved_searches(
+    library: State<'_, SearchLibrary>,
+) -> Result<Message<Vec<SavedSearch>>, String> {
+    Ok(Message::success(Some(library.list().await)))
+}
+
+/// 新建或更新保存的搜索，可设置定时运行与新文件提醒
+#[tauri::command]
+pub async fn save_search(
+    payload: SaveSearchPayload,
+    library: State<'_, SearchLibrary>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<SavedSearch>, String> {
+    if let Err(e) = build_search_options(&payload.payload) {
+        return Ok(Message::failure(&e.to_string()));
+    }
+    let search = match library.upsert(payload, session::generate_search_id()).await {
+        Ok(val) => val,
+        Err(e) => return Ok(Message::failure(&e.to_string())),
+    };
+    match library.save_saved(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(search))),
+        Err(e) => Ok(Message::failure(&format!("保存失败：{}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_saved_search(
+    id: String,
+    library: State<'_, SearchLibrary>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    if !library.remove(&id).await {
+        return Ok(Message::failure("保存的搜索不存在"));
+    }
+    match library.save_saved(&app_handle).await {
+        Ok(_) => Ok(Message::success(Some(String::from("已删除")))),
+        Err(e) => Ok(Message::failure(&format!("保存失败：{}", e))),
+    }
+}
+
+/// 立即运行保存的搜索，返回本次的搜索 ID，结果与普通搜索一样通过事件推送
+#[tauri::command]
+pub async fn run_saved_search(
+    id: String,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    match saved::run(&app_handle, &id, false).await {
+        Ok(search_id) => Ok(Message::success(Some(search_id))),
+        Err(e) => Ok(Message::failure(&e.to_string())),
+    }
+}
+
+/// 按指定顺序分页查询某次搜索的结果，搜索进行中也可查询
+#[tauri::command]
+pub async fn query_search_results(
+    query: ResultQuery,
+    store: State<'_, Arc<ResultStore>>,
+) -> Result<Message<ResultPage>, String> {
+    // 结果较多时排序耗时，放到阻塞线程中执行
+    let store = store.inner().clone();
+    match tauri::async_runtime::spawn_blocking(move || store.query(&query)).await {
+        Ok(Ok(page)) => Ok(Message::success(Some(page))),
+        Ok(Err(e)) => Ok(Message::failure(&e.to_string())),
+        Err(e) => Ok(Message::failure(&e.to_string())),
+    }
+}
+
+/// 导出为 CSV、JSON 或路径列表，返回导出的条数
+#[tauri::command]
+pub async fn export_search_results(
+    payload: ExportPayload,
+    store: State<'_, Arc<ResultStore>>,
+) -> Result<Message<usize>, String> {
+    let store = store.inner().clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let items = store.snapshot(&payload.search_id, payload.sort_by, payload.descending)?;
+        results::export(&items, payload.format, &payload.path)
+    })
+    .await;
+    match result {
+        Ok(Ok(count)) => Ok(Message::success(Some(count))),
+        Ok(Err(e)) => Ok(Message::failure(&format!("导出失败：{}", e))),
+        Err(e) => Ok(Message::failure(&format!("导出失败：{}", e))),
+    }
+}
+
+/// 清除保存的搜索结果，不传 ID 时清除全部
+#[tauri::command]
+pub async fn clear_search_results(
+    search_id: Option<String>,
+    store: State<'_, Arc<ResultStore>>,
+) -> Result<Message<String>, String> {
+    store.clear(search_id.as_deref());
+    Ok(Message::success(Some(String::from("已清除"))))
+}
+
+/// 将搜索到的压缩包内文件（`archive.zip!/inner/path`）单独解压到指定目录，返回解压后的路径
+#[tauri::command]
+pub async fn extract_archive_entry(
+    path: String,
+    target_dir: String,
+) -> Result<Message<String>, String> {
+    let (archive_path, inner) = match archive::split_archive_path(&path) {
+        Some((archive_path, inner)) => (archive_path.to_string(), inner.to_string()),
+        None => return Ok(Message::failure("不是压缩包内的文件")),
+    };
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        archive::extract_entry(Path::new(&archive_path), &inner, Path::new(&target_dir))
+    })
+    .await;
+    match result {
+        Ok(Ok(out_path)) => Ok(Message::success(Some(
+            out_path.to_string_lossy().to_string(),
+        ))),
+        Ok(Err(e)) => Ok(Message::failure(&format!("解压失败：{}", e))),
+        Err(e) => Ok(Message::failure(&format!("解压失败：{}", e))),
+    }
+}
+
+/// 读取文件头识别真实类型，不依赖扩展名
+#[tauri::command]
+pub async fn detect_file_type(path: String) -> Result<Message<DetectedFileType>, String> {
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let path = Path::new(&path);
+        if !path.is_file() {
+            return Err(Error::msg("文件不存在"));
+        }
+        let file_type = file_type::detect_file(path)?;
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(DetectedFileType {
+            extension_matches: file_type::extension_matches(&file_type, &extension),
+            file_type,
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok(detected)) => Ok(Message::success(Some(detected))),
+        Ok(Err(e)) => Ok(Message::failure(&format!("识别失败：{}", e))),
+        Err(e) => Ok(Message::failure(&format!("识别失败：{}", e))),
+    }
+}
+
+/// 查找重复文件，文件名与过滤规则用于限定参与比较的文件，结果通过事件推送
+#[tauri::command]
+pub async fn find_duplicate_files(
+    mut payload: SearchDiskFilePayload,
+    registry: State<'_, SearchRegistry>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    payload.filter.kind = EntryKind::File;
+    payload.content = None;
+    payload.archives = false;
+    let options = match build_search_options(&payload) {
+        Ok(val) => Arc::new(val),
+        Err(e) => return Ok(Message::failure(&e.to_string())),
+    };
+
+    let (search_id, control) =
+        match registry.create(payload.search_id.clone(), &payload.name, &payload.disks) {
+            Some(val) => val,
+            None => return Ok(Message::failure("搜索 ID 已存在")),
+        };
+    let sender = MessageSender::new(app_handle.clone(), "file-search");
+    let id = search_id.clone();
+    tauri::async_runtime::spawn(async move {
+        do_duplicate_task(id.clone(), payload, options, control, sender).await;
+        app_handle.state::<SearchRegistry>().remove(&id);
+    });
+
+    Ok(Message::success(Some(search_id)))
+}
+
+/// 保留每组中的一个文件，其余删除、移到回收站或替换为硬链接；`dryRun` 时只返回预览
+#[tauri::command]
+pub async fn resolve_duplicate_files(
+    payload: ResolveDuplicatesPayload,
+) -> Result<Message<ResolveReport>, String> {
+    if payload.groups.is_empty() {
+        return Ok(Message::failure("请选择需要处理的重复文件"));
+    }
+    match tauri::async_runtime::spawn_blocking(move || duplicates::resolve(&payload)).await {
+        Ok(report) => Ok(Message::success(Some(report))),
+        Err(e) => Ok(Message::failure(&format!("处理失败：{}", e))),
+    }
+}
+
+/// 统计目录及其子目录的累计大小，返回搜索 ID，结果通过 `disk-usage-output` 事件推送
+#[tauri::command]
+pub async fn scan_disk_usage(
+    payload: DiskUsagePayload,
+    registry: State<'_, SearchRegistry>,
+    cache: State<'_, Arc<UsageCache>>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    if !Path::new(&payload.root).is_dir() {
+        return Ok(Message::failure("目录不存在"));
+    }
+    let options = match walk_all_options(&payload.exclude_options()) {
+        Ok(val) => Arc::new(val),
+        Err(e) => return Ok(Message::failure(&e.to_string())),
+    };
+
+    let disks = vec![payload.root.clone()];
+    let (search_id, control) = match registry.create(payload.search_id.clone(), "", &disks) {
+        Some(val) => val,
+        None => return Ok(Message::failure("搜索 ID 已存在")),
+    };
+    let sender = MessageSender::new(app_handle.clone(), "file-search");
+    let cache = cache.inner().clone();
+    let id = search_id.clone();
+    tauri::async_runtime::spawn(async move {
+        do_disk_usage_task(id.clone(), payload, options, control, sender, cache).await;
+        app_handle.state::<SearchRegistry>().remove(&id);
+    });
+
+    Ok(Message::success(Some(search_id)))
+}
+
+/// 查找可清理的大文件、空目录、零字节文件、过期临时文件、下载遗留文件与缓存，返回搜索 ID，
+/// 结果按类别通过 `cleanup-scan-output` 事件推送
+#[tauri::command]
+pub async fn scan_cleanup_candidates(
+    payload: CleanupPayload,
+    registry: State<'_, SearchRegistry>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    if !Path::new(&payload.root).is_dir() {
+        return Ok(Message::failure("目录不存在"));
+    }
+    let options = match walk_all_options(&payload.exclude_options()) {
+        Ok(val) => Arc::new(val),
+        Err(e) => return Ok(Message::failure(&e.to_string())),
+    };
+
+    let disks = vec![payload.root.clone()];
+    let (search_id, control) = match registry.create(payload.search_id.clone(), "", &disks) {
+        Some(val) => val,
+        None => return Ok(Message::failure("搜索 ID 已存在")),
+    };
+    let sender = MessageSender::new(app_handle.clone(), "file-search");
+    let id = search_id.clone();
+    tauri::async_runtime::spawn(async move {
+        do_cleanup_task(id.clone(), payload, options, control, sender).await;
+        app_handle.state::<SearchRegistry>().remove(&id);
+    });
+
+    Ok(Message::success(Some(search_id)))
+}
+
+/// 将选中的清理项移到回收站，处理前会再次确认空目录、零字节文件仍然为空
+#[tauri::command]
+pub async fn clean_up_items(items: Vec<CleanTarget>) -> Result<Message<CleanReport>, String> {
+    if items.is_empty() {
+        return Ok(Message::failure("请选择需要清理的项目"));
+    }
+    match tauri::async_runtime::spawn_blocking(move || cleanup::clean(&items)).await {
+        Ok(report) => Ok(Message::success(Some(report))),
+        Err(e) => Ok(Message::failure(&format!("清理失败：{}", e))),
+    }
+}
+
+/// 从已完成的扫描结果中读取目录的子项，不重新扫描
+#[tauri::command]
+pub async fn get_disk_usage(
+    path: String,
+    limit: Option<usize>,
+    cache: State<'_, Arc<UsageCache>>,
+) -> Result<Message<UsageNode>, String> {
+    let path = Path::new(&path);
+    let tree = match cache.find(path) {
+        Some(val) => val,
+        None => return Ok(Message::failure("该目录尚未扫描")),
+    };
+    let limit = limit.unwrap_or(usize::MAX);
+    let node = tauri::async_runtime::spawn_blocking({
+        let path = path.to_path_buf();
+        move || usage::drill_down(&tree, &path, limit)
+    })
+    .await
+    .ok()
+    .flatten();
+    match node {
+        Some(node) => Ok(Message::success(Some(node))),
+        None => Ok(Message::failure("扫描结果中没有该目录")),
+    }
+}
+
+/// 清除扫描缓存，不传根目录时清除全部
+#[tauri::command]
+pub async fn clear_disk_usage_cache(
+    root: Option<String>,
+    cache: State<'_, Arc<UsageCache>>,
+) -> Result<Message<String>, String> {
+    cache.clear(root.as_deref().map(Path::new));
+    Ok(Message::success(Some(String::from("已清除"))))
+}
+
+/// 获取默认的排除规则，供前端展示和编辑
+#[tauri::command]
+pub fn get_search_default_excludes() -> Message<Vec<String>> {
+    Message::success(Some(
+        DEFAULT_EXCLUDE_GLOBS
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    ))
+}
+
+/// 未指定 ID 时取消全部搜索
+#[tauri::command]
+pub async fn cancel_search_task(
+    search_id: Option<String>,
+    registry: State<'_, SearchRegistry>,
+) -> Result<Message<String>, String> {
+    if registry.cancel(search_id.as_deref()) == 0 {
+        return Ok(Message::failure("搜索任务不存在或已结束"));
+    }
+    Ok(Message::success(Some(String::from("停止成功"))))
+}
+
+#[tauri::command]
+pub async fn pause_search_task(
+    search_id: String,
+    registry: State<'_, SearchRegistry>,
+) -> Result<Message<String>, String> {
+    if !registry.set_paused(&search_id, true) {
+        return Ok(Message::failure("搜索任务不存在或已结束"));
+    }
+    Ok(Message::success(Some(String::from("已暂停"))))
+}
+
+#[tauri::command]
+pub async fn resume_search_task(
+    search_id: String,
+    registry: State<'_, SearchRegistry>,
+) -> Result<Message<String>, String> {
+    if !registry.set_paused(&search_id, false) {
+        return Ok(Message::failure("搜索任务不存在或已结束"));
+    }
+    Ok(Message::success(Some(String::from("已继续"))))
+}
+
+#[tauri::command]
+pub async fn list_search_tasks(
+    registry: State<'_, SearchRegistry>,
+) -> Result<Message<Vec<SearchTaskInfo>>, String> {
+    Ok(Message::success(Some(registry.list())))
+}
+
+/// 从索引中查询，毫秒级返回
+#[tauri::command]
+pub async fn query_file_index(
+    payload: IndexQueryPayload,
+    state: State<'_, IndexState>,
+) -> Result<Message<Vec<SearchResultModel>>, String> {
+    match state.index.query(&payload) {
+        Ok(results) => Ok(Message::success(Some(results))),
+        Err(e) => Ok(Message::failure(&e.to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn get_file_index_config(
+    state: State<'_, IndexState>,
+) -> Result<Message<IndexConfig>, String> {
+    Ok(Message::success(Some(state.index.config())))
+}
+
+/// 保存索引配置，重新监听并全量扫描
+#[tauri::command]
+pub async fn set_file_index_config(
+    config: IndexConfig,
+    state: State<'_, IndexState>,
+    app_handle: tauri::AppHandle,
+) -> Result<Message<String>, String> {
+    if config.enabled && config.roots.is_empty() {
+        return Ok(Message::failure("请选择需要建立索引的目录"));
+    }
+    if let Err(e) = state.index.apply_config(&app_handle, config, true).await {
+        return Ok(Message::failure(&e.to_string()));
+    }
+    if let Err(e) = state.index.save_config(&app_handle).await {
+        return Ok(Message::failure(&format!("保存配置失败：{}", e)));
+    }
+    Ok(Message::success(Some(String::from("设置成功"))))
+}
+
+#[tauri::command]
+pub async fn get_file_index_status(
+    state: State<'_, IndexState>,
+) -> Result<Message<IndexStatus>, String> {
+    Ok(Message::success(Some(state.index.status())))
+}
+
+#[tauri::command]
+pub async fn rebuild_file_index(state: State<'_, IndexState>) -> Result<Message<String>, String> {
+    if !state.index.config().enabled {
+        return Ok(Message::failure("索引未开启"));
+    }
+    let index = state.index.clone();
+    tauri::async_runtime::spawn(async move { index.rebuild().await });
+    Ok(Message::success(Some(String::from("已开始重建索引"))))
+}
+
+pub struct IndexState {
+    index: Arc<FileIndex>,
+}
+
+pub fn init() -> TauriPlugin<tauri::Wry> {
+    println!("file-search plugin init");
+
+    Builder::new("file-search")
+        .setup(|app, _| {
+            app.manage(SearchRegistry::default());
+            app.manage(Arc::new(UsageCache::default()));
+            app.manage(Arc::new(ResultStore::default()));
+            app.manage(SearchLibrary::default());
+            saved::start(app.clone());
+
+            let index = Arc::new(FileIndex::new(MessageSender::new(
+                app.clone(),
+                "file-search",
+            )));
+            app.manage(IndexState {
+                index: index.clone(),
+            });
+            index::start(app.clone(), index);
+
+            Ok(())
+        })
+        .build()
+}
@@ -0,0 +1,417 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{Error, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+
+use super::SearchResultModel;
+
+/// 最多保留的搜索结果集数量，超出时移除最早的
+const MAX_RESULT_SETS: usize = 10;
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortField {
+    Path,
+    Name,
+    Size,
+    Modified,
+    Created,
+    Extension,
+    Score,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultQuery {
+    pub search_id: String,
+    /// 为空时按结果到达的顺序
+    pub sort_by: Option<SortField>,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub offset: usize,
+    /// 默认 100
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultPage {
+    pub total: usize,
+    pub items: Vec<SearchResultModel>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    /// 每行一个路径，内容搜索的重复路径只保留一次
+    Paths,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPayload {
+    pub search_id: String,
+    pub format: ExportFormat,
+    /// 导出文件的保存路径
+    pub path: String,
+    pub sort_by: Option<SortField>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+fn compare(a: &SearchResultModel, b: &SearchResultModel, field: SortField) -> Ordering {
+    match field {
+        SortField::Path => a.path.cmp(&b.path),
+        SortField::Name => file_name(&a.path)
+            .to_lowercase()
+            .cmp(&file_name(&b.path).to_lowercase()),
+        SortField::Size => a.size.cmp(&b.size),
+        SortField::Modified => a.modified.cmp(&b.modified),
+        SortField::Created => a.created.cmp(&b.created),
+        SortField::Extension => a.extension.cmp(&b.extension),
+        SortField::Score => a.score.cmp(&b.score),
+    }
+    // 同一文件的多条内容命中按行号排列
+    .then_with(|| a.path.cmp(&b.path))
+    .then_with(|| a.line.cmp(&b.line))
+}
+
+/// 合并两个已按 `cmp` 排好的索引列表，相等时左侧在前
+fn merge_sorted(
+    left: Vec<usize>,
+    right: Vec<usize>,
+    cmp: impl Fn(usize, usize) -> Ordering,
+) -> Vec<usize> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut right = right.into_iter().peekable();
+    for l in left {
+        while let Some(&r) = right.peek() {
+            if cmp(r, l) == Ordering::Less {
+                merged.push(r);
+                right.next();
+            } else {
+                break;
+            }
+        }
+        merged.push(l);
+    }
+    merged.extend(right);
+    merged
+}
+
+/// 结果按到达顺序保存，排序时生成索引，追加的新结果排序后合并进已有索引
+#[derive(Default)]
+struct ResultSet {
+    items: Vec<SearchResultModel>,
+    order: Option<(SortField, bool, Vec<usize>)>,
+}
+
+impl ResultSet {
+    fn sorted(&mut self, sort_by: Option<SortField>, descending: bool) -> Vec<&SearchResultModel> {
+        let field = match sort_by {
+            Some(val) => val,
+            None => {
+                return if descending {
+                    self.items.iter().rev().collect()
+                } else {
+                    self.items.iter().collect()
+                };
+            }
+        };
+        let items = &self.items;
+        let cmp = |a: usize, b: usize| {
+            let ord = compare(&items[a], &items[b], field);
+            if descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        };
+        // 结果只会追加，已排序的索引覆盖 `0..order.len()`
+        let mut order = match self.order.take() {
+            Some((f, d, order)) if f == field && d == descending => order,
+            _ => vec![],
+        };
+        if order.len() < items.len() {
+            let mut added: Vec<usize> = (order.len()..items.len()).collect();
+            added.sort_by(|&a, &b| cmp(a, b));
+            order = merge_sorted(order, added, cmp);
+        }
+        let sorted = order.iter().map(|&i| &self.items[i]).collect();
+        self.order = Some((field, descending, order));
+        sorted
+    }
+}
+
+/// 每次搜索的完整结果，供前端分页查询和导出
+#[derive(Default)]
+pub struct ResultStore {
+    sets: Mutex<HashMap<String, ResultSet>>,
+    created: Mutex<VecDeque<String>>,
+}
+
+impl ResultStore {
+    pub fn create(&self, search_id: &str) {
+        let (mut sets, mut created) = match (self.sets.lock(), self.created.lock()) {
+            (Ok(sets), Ok(created)) => (sets, created),
+            _ => return,
+        };
+        sets.insert(search_id.to_string(), ResultSet::default());
+        created.push_back(search_id.to_string());
+        while created.len() > MAX_RESULT_SETS {
+            if let Some(id) = created.pop_front() {
+                sets.remove(&id);
+            }
+        }
+    }
+
+    pub fn append(&self, search_id: &str, results: &[SearchResultModel]) {
+        if let Ok(mut sets) = self.sets.lock() {
+            if let Some(set) = sets.get_mut(search_id) {
+                set.items.extend_from_slice(results);
+            }
+        }
+    }
+
+    pub fn query(&self, query: &ResultQuery) -> AnyResult<ResultPage> {
+        let mut sets = self.sets.lock().map_err(|_| Error::msg("搜索结果不可用"))?;
+        let set = sets
+            .get_mut(&query.search_id)
+            .ok_or_else(|| Error::msg("搜索结果不存在或已清除"))?;
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let sorted = set.sorted(query.sort_by, query.descending);
+        Ok(ResultPage {
+            total: sorted.len(),
+            items: sorted
+                .into_iter()
+                .skip(query.offset)
+                .take(limit)
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// 按导出顺序复制全部结果，写入文件时不占用锁
+    pub fn snapshot(
+        &self,
+        search_id: &str,
+        sort_by: Option<SortField>,
+        descending: bool,
+    ) -> AnyResult<Vec<SearchResultModel>> {
+        let mut sets = self.sets.lock().map_err(|_| Error::msg("搜索结果不可用"))?;
+        let set = sets
+            .get_mut(search_id)
+            .ok_or_else(|| Error::msg("搜索结果不存在或已清除"))?;
+        Ok(set
+            .sorted(sort_by, descending)
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    /// 未指定 ID 时清除全部
+    pub fn clear(&self, search_id: Option<&str>) {
+        let (mut sets, mut created) = match (self.sets.lock(), self.created.lock()) {
+            (Ok(sets), Ok(created)) => (sets, created),
+            _ => return,
+        };
+        match search_id {
+            Some(id) => {
+                sets.remove(id);
+                created.retain(|c| c != id);
+            }
+            None => {
+                sets.clear();
+                created.clear();
+            }
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_time(millis: Option<i64>) -> String {
+    millis
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn write_csv<W: Write>(writer: &mut W, items: &[SearchResultModel]) -> std::io::Result<()> {
+    // 带 BOM，Excel 打开时中文不乱码
+    writer.write_all("\u{feff}".as_bytes())?;
+    writeln!(
+        writer,
+        "path,name,isDir,size,modified,created,extension,parent,line,snippet"
+    )?;
+    for item in items {
+        let fields = [
+            csv_field(&item.path),
+            csv_field(file_name(&item.path)),
+            item.is_dir.to_string(),
+            item.size.to_string(),
+            format_time(item.modified),
+            format_time(item.created),
+            csv_field(&item.extension),
+            csv_field(&item.parent),
+            item.line.map(|l| l.to_string()).unwrap_or_default(),
+            csv_field(item.snippet.as_deref().unwrap_or_default()),
+        ];
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// 返回去重后实际写入的行数
+fn write_paths<W: Write>(writer: &mut W, items: &[SearchResultModel]) -> std::io::Result<usize> {
+    let mut written = HashSet::new();
+    for item in items {
+        if written.insert(item.path.as_str()) {
+            writeln!(writer, "{}", item.path)?;
+        }
+    }
+    Ok(written.len())
+}
+
+/// 阻塞写入导出文件，返回导出的条数
+pub fn export(items: &[SearchResultModel], format: ExportFormat, path: &str) -> AnyResult<usize> {
+    let file = std::fs::File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    let count = match format {
+        ExportFormat::Csv => {
+            write_csv(&mut writer, items)?;
+            items.len()
+        }
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, items)?;
+            items.len()
+        }
+        ExportFormat::Paths => write_paths(&mut writer, items)?,
+    };
+    writer.flush()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, size: u64) -> SearchResultModel {
+        SearchResultModel::new(path.to_string(), false, size, None, None)
+    }
+
+    #[test]
+    fn test_query_sort_and_page() {
+        let store = ResultStore::default();
+        store.create("s");
+        store.append("s", &[item("/a/b.txt", 30), item("/a/C.log", 10)]);
+        store.append("s", &[item("/z/a.md", 20)]);
+
+        let mut query = ResultQuery {
+            search_id: "s".to_string(),
+            sort_by: Some(SortField::Size),
+            descending: true,
+            offset: 0,
+            limit: Some(2),
+        };
+        let page = store.query(&query).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0].size, 30);
+        assert_eq!(page.items[1].size, 20);
+
+        query.sort_by = Some(SortField::Name);
+        query.descending = false;
+        query.offset = 1;
+        let page = store.query(&query).unwrap();
+        assert_eq!(page.items[0].path, "/a/b.txt");
+        assert_eq!(page.items[1].path, "/a/C.log");
+        assert_eq!(page.items[0].extension, "txt");
+        assert_eq!(page.items[0].parent, "/a");
+
+        store.clear(Some("s"));
+        assert!(store.query(&query).is_err());
+    }
+
+    #[test]
+    fn test_evicts_oldest_set() {
+        let store = ResultStore::default();
+        for i in 0..=MAX_RESULT_SETS {
+            store.create(&i.to_string());
+        }
+        assert!(store.snapshot("0", None, false).is_err());
+        assert!(store.snapshot("1", None, false).is_ok());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = vec![];
+        let mut hit = item("/a/x,y.txt", 1);
+        hit.line = Some(3);
+        hit.snippet = Some("say \"hi\"".to_string());
+        write_csv(&mut out, &[hit]).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let row = text.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "\"/a/x,y.txt\",\"x,y.txt\",false,1,,,txt,/a,3,\"say \"\"hi\"\"\""
+        );
+    }
+
+    #[test]
+    fn test_sort_merges_appended_results() {
+        let store = ResultStore::default();
+        store.create("s");
+        store.append("s", &[item("/a", 5), item("/b", 1), item("/c", 9)]);
+        let query = ResultQuery {
+            search_id: "s".to_string(),
+            sort_by: Some(SortField::Size),
+            descending: false,
+            offset: 0,
+            limit: None,
+        };
+        store.query(&query).unwrap();
+        store.append("s", &[item("/d", 7), item("/e", 0), item("/f", 5)]);
+
+        let paths: Vec<String> = store
+            .query(&query)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|i| i.path)
+            .collect();
+        assert_eq!(paths, vec!["/e", "/b", "/a", "/f", "/d", "/c"]);
+    }
+
+    #[test]
+    fn test_write_paths_counts_unique_rows() {
+        let mut out = vec![];
+        let items = [item("/a.txt", 1), item("/a.txt", 1), item("/b.txt", 1)];
+        assert_eq!(write_paths(&mut out, &items).unwrap(), 2);
+        assert_eq!(String::from_utf8(out).unwrap(), "/a.txt\n/b.txt\n");
+    }
+}
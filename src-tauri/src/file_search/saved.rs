@@ -0,0 +1,375 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Error, Result as AnyResult};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Mutex;
+use tokio::time;
+
+use crate::utils::output::MessageSender;
+
+use super::SearchDiskFilePayload;
+
+const HISTORY_FILE_NAME: &str = "search-history.json";
+const SAVED_FILE_NAME: &str = "saved-searches.json";
+
+static NEW_MATCHES_EVENT: &str = "saved-search-new-matches";
+
+/// 最多保留的搜索历史数量
+const MAX_HISTORY: usize = 100;
+/// 通知正文中最多列出的新文件数量
+const MAX_NOTIFY_PATHS: usize = 5;
+/// 检查定时搜索的间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHistoryEntry {
+    pub search_id: String,
+    pub payload: SearchDiskFilePayload,
+    /// 毫秒时间戳
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub result_count: u64,
+    pub cancelled: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    pub payload: SearchDiskFilePayload,
+    /// 定时运行的间隔（分钟），为空时只能手动运行
+    pub interval_minutes: Option<u64>,
+    /// 运行后与上次结果对比，出现新文件时发送通知
+    pub watch: bool,
+    pub created_at: i64,
+    pub last_run_at: Option<i64>,
+    pub last_result_count: Option<u64>,
+}
+
+impl SavedSearch {
+    fn is_due(&self, now: i64) -> bool {
+        let minutes = match self.interval_minutes {
+            Some(val) if val > 0 => val as i64,
+            _ => return false,
+        };
+        self.last_run_at
+            .is_none_or(|last| now - last >= minutes * 60 * 1000)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSearchPayload {
+    /// 传入已有 ID 时更新该搜索
+    pub id: Option<String>,
+    pub name: String,
+    pub payload: SearchDiskFilePayload,
+    pub interval_minutes: Option<u64>,
+    #[serde(default)]
+    pub watch: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NewMatchesOutput {
+    pub saved_id: String,
+    pub name: String,
+    pub paths: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SavedData {
+    searches: Vec<SavedSearch>,
+    /// 监视模式下已经见过的结果路径，按保存的搜索 ID 记录
+    known_paths: HashMap<String, HashSet<String>>,
+}
+
+/// 搜索历史与保存的搜索
+#[derive(Default)]
+pub struct SearchLibrary {
+    history: Mutex<Vec<SearchHistoryEntry>>,
+    saved: Mutex<SavedData>,
+    /// 正在运行的保存搜索，避免定时任务重复启动
+    running: Mutex<HashSet<String>>,
+}
+
+fn data_file_path(app: &AppHandle, name: &str) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(name))
+}
+
+async fn write_json<T: Serialize>(app: &AppHandle, name: &str, value: &T) -> AnyResult<()> {
+    let path = data_file_path(app, name).ok_or(Error::msg("无法获取应用数据目录"))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(&path, serde_json::to_string_pretty(value)?).await?;
+    Ok(())
+}
+
+async fn read_json<T: for<'de> Deserialize<'de>>(app: &AppHandle, name: &str) -> Option<T> {
+    let path = data_file_path(app, name)?;
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 返回不在已知集合中的路径，保持原有顺序，重复的路径只保留一次
+fn diff_new_paths(known: &HashSet<String>, paths: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    paths
+        .iter()
+        .filter(|p| !known.contains(*p) && seen.insert(p.as_str()))
+        .cloned()
+        .collect()
+}
+
+impl SearchLibrary {
+    pub async fn push_history(&self, entry: SearchHistoryEntry) {
+        let mut history = self.history.lock().await;
+        history.push(entry);
+        let overflow = history.len().saturating_sub(MAX_HISTORY);
+        history.drain(..overflow);
+    }
+
+    /// 最近的记录在前
+    pub async fn history(&self) -> Vec<SearchHistoryEntry> {
+        self.history.lock().await.iter().rev().cloned().collect()
+    }
+
+    pub async fn clear_history(&self) {
+        self.history.lock().await.clear();
+    }
+
+    pub async fn list(&self) -> Vec<SavedSearch> {
+        self.saved.lock().await.searches.clone()
+    }
+
+    pub async fn upsert(&self, payload: SaveSearchPayload, id: String) -> AnyResult<SavedSearch> {
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(Error::msg("请输入搜索名称"));
+        }
+        let mut search_payload = payload.payload;
+        search_payload.search_id = None;
+
+        let mut saved = self.saved.lock().await;
+        let now = chrono::Local::now().timestamp_millis();
+        let search = match payload.id {
+            Some(id) => {
+                let search = saved
+                    .searches
+                    .iter_mut()
+                    .find(|s| s.id == id)
+                    .ok_or(Error::msg("保存的搜索不存在"))?;
+                search.name = name.to_string();
+                search.payload = search_payload;
+                search.interval_minutes = payload.interval_minutes;
+                search.watch = payload.watch;
+                search.clone()
+            }
+            None => {
+                let search = SavedSearch {
+                    id,
+                    name: name.to_string(),
+                    payload: search_payload,
+                    interval_minutes: payload.interval_minutes,
+                    watch: payload.watch,
+                    created_at: now,
+                    last_run_at: None,
+                    last_result_count: None,
+                };
+                saved.searches.push(search.clone());
+                search
+            }
+        };
+        // 条件变化后旧的结果不再有参考意义
+        saved.known_paths.remove(&search.id);
+        Ok(search)
+    }
+
+    pub async fn remove(&self, id: &str) -> bool {
+        let mut saved = self.saved.lock().await;
+        let len = saved.searches.len();
+        saved.searches.retain(|s| s.id != id);
+        saved.known_paths.remove(id);
+        saved.searches.len() != len
+    }
+
+    /// 标记为运行中并返回搜索条件
+    async fn begin(&self, id: &str) -> AnyResult<SavedSearch> {
+        let search = self
+            .saved
+            .lock()
+            .await
+            .searches
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+            .ok_or(Error::msg("保存的搜索不存在"))?;
+        if !self.running.lock().await.insert(id.to_string()) {
+            return Err(Error::msg("该搜索正在运行"));
+        }
+        Ok(search)
+    }
+
+    /// 记录运行结果，监视模式下返回新出现的路径，首次运行只记录不提醒
+    async fn finish(&self, id: &str, result_count: u64, paths: Option<Vec<String>>) -> Vec<String> {
+        self.running.lock().await.remove(id);
+
+        let mut saved = self.saved.lock().await;
+        let watch = match saved.searches.iter_mut().find(|s| s.id == id) {
+            Some(search) => {
+                search.last_run_at = Some(chrono::Local::now().timestamp_millis());
+                search.last_result_count = Some(result_count);
+                search.watch
+            }
+            None => return vec![],
+        };
+        let paths = match paths {
+            Some(val) if watch => val,
+            _ => return vec![],
+        };
+        let new_paths = match saved.known_paths.get(id) {
+            Some(known) => diff_new_paths(known, &paths),
+            None => vec![],
+        };
+        saved
+            .known_paths
+            .insert(id.to_string(), paths.into_iter().collect());
+        new_paths
+    }
+
+    async fn due_searches(&self, now: i64) -> Vec<String> {
+        let running = self.running.lock().await;
+        self.saved
+            .lock()
+            .await
+            .searches
+            .iter()
+            .filter(|s| s.is_due(now) && !running.contains(&s.id))
+            .map(|s| s.id.clone())
+            .collect()
+    }
+
+    pub async fn save_history(&self, app: &AppHandle) -> AnyResult<()> {
+        let history = self.history.lock().await.clone();
+        write_json(app, HISTORY_FILE_NAME, &history).await
+    }
+
+    pub async fn save_saved(&self, app: &AppHandle) -> AnyResult<()> {
+        let saved = self.saved.lock().await;
+        write_json(app, SAVED_FILE_NAME, &*saved).await
+    }
+
+    async fn load(&self, app: &AppHandle) {
+        if let Some(history) = read_json(app, HISTORY_FILE_NAME).await {
+            *self.history.lock().await = history;
+        }
+        if let Some(saved) = read_json(app, SAVED_FILE_NAME).await {
+            *self.saved.lock().await = saved;
+        }
+    }
+}
+
+fn notify_new_matches(app: &AppHandle, name: &str, paths: &[String]) {
+    let mut body = paths
+        .iter()
+        .take(MAX_NOTIFY_PATHS)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if paths.len() > MAX_NOTIFY_PATHS {
+        body.push_str(&format!("\n等 {} 个文件", paths.len()));
+    }
+    let title = format!("{}：发现新文件", name);
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        println!("[file-search] 通知发送失败：{}", e);
+    }
+}
+
+/// 运行保存的搜索，返回本次的搜索 ID，结束后更新运行记录并检查新文件
+///
+/// 新文件与该搜索自己记录的路径对比，不依赖可能已被清除的结果集；
+/// 定时运行在后台进行，不占用结果集、不推送结果，也不写入搜索历史
+pub async fn run(app: &AppHandle, id: &str, scheduled: bool) -> AnyResult<String> {
+    let library = app.state::<SearchLibrary>();
+    let search = library.begin(id).await?;
+    let started = super::start_search(app, search.payload, scheduled, search.watch);
+    let (search_id, handle) = match started {
+        Ok(val) => val,
+        Err(e) => {
+            library.running.lock().await.remove(id);
+            return Err(e);
+        }
+    };
+
+    let app = app.clone();
+    let saved_id = search.id;
+    let name = search.name;
+    tauri::async_runtime::spawn(async move {
+        let outcome = handle.await.unwrap_or_default();
+        // 被取消时结果不完整，不参与对比
+        let paths = (!outcome.cancelled).then_some(outcome.paths);
+
+        let library = app.state::<SearchLibrary>();
+        let new_paths = library.finish(&saved_id, outcome.result_count, paths).await;
+        if let Err(e) = library.save_saved(&app).await {
+            println!("[file-search] 保存搜索记录失败：{}", e);
+        }
+        if !new_paths.is_empty() {
+            notify_new_matches(&app, &name, &new_paths);
+            let output = NewMatchesOutput {
+                saved_id,
+                name,
+                paths: new_paths,
+            };
+            MessageSender::new(app.clone(), "file-search").send(NEW_MATCHES_EVENT, output, false);
+        }
+    });
+
+    Ok(search_id)
+}
+
+/// 加载历史与保存的搜索，并定时运行到期的搜索
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let library = app.state::<SearchLibrary>();
+        library.load(&app).await;
+
+        let mut interval = time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = chrono::Local::now().timestamp_millis();
+            for id in library.due_searches(now).await {
+                if let Err(e) = run(&app, &id, true).await {
+                    println!("[file-search] 定时搜索启动失败：{}", e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_new_paths() {
+        let known: HashSet<String> = ["/a.dmp", "/b.dmp"].iter().map(|s| s.to_string()).collect();
+        let paths = vec![
+            "/c.dmp".to_string(),
+            "/a.dmp".to_string(),
+            "/d.dmp".to_string(),
+        ];
+        assert_eq!(diff_new_paths(&known, &paths), vec!["/c.dmp", "/d.dmp"]);
+        let repeated = vec!["/c.dmp".to_string(), "/c.dmp".to_string()];
+        assert_eq!(diff_new_paths(&known, &repeated), vec!["/c.dmp"]);
+        assert!(diff_new_paths(&known, &[]).is_empty());
+    }
+}
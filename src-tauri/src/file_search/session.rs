@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::stats::SearchStats;
+
+/// 暂停时检查状态的间隔
+const PAUSE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 单次搜索的取消与暂停标记及统计，由遍历线程轮询和更新
+#[derive(Default)]
+pub struct SearchControl {
+    cancelled: AtomicBool,
+    paused: AtomicBool,
+    stats: SearchStats,
+}
+
+impl SearchControl {
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// 暂停期间阻塞等待，返回是否已被取消
+    pub fn wait_if_paused(&self) -> bool {
+        while self.is_paused() && !self.is_cancelled() {
+            std::thread::sleep(PAUSE_INTERVAL);
+        }
+        self.is_cancelled()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTaskInfo {
+    pub id: String,
+    pub name: String,
+    pub disks: Vec<String>,
+    pub started_at: i64,
+    pub paused: bool,
+}
+
+struct SearchSession {
+    info: SearchTaskInfo,
+    control: Arc<SearchControl>,
+}
+
+/// 正在进行的搜索，搜索结束后移除
+#[derive(Default)]
+pub struct SearchRegistry {
+    sessions: Mutex<HashMap<String, SearchSession>>,
+}
+
+pub fn generate_search_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", chrono::Local::now().timestamp_millis(), seq)
+}
+
+impl SearchRegistry {
+    /// 未指定 ID 时自动生成，ID 已存在时返回 None
+    pub fn create(
+        &self,
+        id: Option<String>,
+        name: &str,
+        disks: &[String],
+    ) -> Option<(String, Arc<SearchControl>)> {
+        let id = id.unwrap_or_else(generate_search_id);
+        let control = Arc::new(SearchControl::default());
+        let mut sessions = self.sessions.lock().ok()?;
+        if sessions.contains_key(&id) {
+            return None;
+        }
+        sessions.insert(
+            id.clone(),
+            SearchSession {
+                info: SearchTaskInfo {
+                    id: id.clone(),
+                    name: name.to_string(),
+                    disks: disks.to_vec(),
+                    started_at: chrono::Local::now().timestamp_millis(),
+                    paused: false,
+                },
+                control: control.clone(),
+            },
+        );
+        Some((id, control))
+    }
+
+    pub fn remove(&self, id: &str) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.remove(id);
+        }
+    }
+
+    /// 未指定 ID 时取消全部搜索，返回取消的数量
+    pub fn cancel(&self, id: Option<&str>) -> usize {
+        let sessions = match self.sessions.lock() {
+            Ok(val) => val,
+            Err(_) => return 0,
+        };
+        let mut count = 0;
+        for session in sessions.values() {
+            if id.is_none_or(|id| id == session.info.id) {
+                session.control.cancel();
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub fn set_paused(&self, id: &str, paused: bool) -> bool {
+        let mut sessions = match self.sessions.lock() {
+            Ok(val) => val,
+            Err(_) => return false,
+        };
+        match sessions.get_mut(id) {
+            Some(session) => {
+                session.control.set_paused(paused);
+                session.info.paused = paused;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<SearchTaskInfo> {
+        let mut list: Vec<SearchTaskInfo> = self
+            .sessions
+            .lock()
+            .map(|s| s.values().map(|s| s.info.clone()).collect())
+            .unwrap_or_default();
+        list.sort_by_key(|info| info.started_at);
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let registry = SearchRegistry::default();
+        let (a, control_a) = registry.create(None, "a", &[]).unwrap();
+        let (b, control_b) = registry.create(Some("b".to_string()), "b", &[]).unwrap();
+        assert_ne!(a, b);
+        assert!(registry.create(Some(b.clone()), "b", &[]).is_none());
+        assert_eq!(registry.list().len(), 2);
+
+        assert!(registry.set_paused(&a, true));
+        assert!(control_a.is_paused());
+        assert!(!registry.set_paused("missing", true));
+
+        assert_eq!(registry.cancel(Some(&b)), 1);
+        assert!(control_b.is_cancelled());
+        assert!(!control_a.is_cancelled());
+
+        registry.remove(&b);
+        assert_eq!(registry.cancel(None), 1);
+        assert!(control_a.is_cancelled());
+        assert!(control_a.wait_if_paused());
+    }
+}
@@ -0,0 +1,143 @@
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// 保留的无法访问路径数量上限，超出后只计数
+const MAX_ERRORS: usize = 1000;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AccessErrorKind {
+    PermissionDenied,
+    NotFound,
+    Interrupted,
+    Other,
+}
+
+impl From<io::ErrorKind> for AccessErrorKind {
+    fn from(kind: io::ErrorKind) -> Self {
+        match kind {
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::Interrupted | io::ErrorKind::TimedOut => Self::Interrupted,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InaccessiblePath {
+    pub path: String,
+    pub kind: AccessErrorKind,
+    pub message: String,
+}
+
+/// 搜索进度事件，`new_errors` 只包含上次推送之后新增的路径
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProgress {
+    pub search_id: String,
+    pub dirs_scanned: u64,
+    pub files_scanned: u64,
+    pub matches: u64,
+    pub current_path: String,
+    pub error_count: u64,
+    pub new_errors: Vec<InaccessiblePath>,
+    pub finished: bool,
+}
+
+/// 单次搜索的统计，由遍历线程更新、定时推送给前端
+#[derive(Default)]
+pub struct SearchStats {
+    dirs_scanned: AtomicU64,
+    files_scanned: AtomicU64,
+    matches: AtomicU64,
+    error_count: AtomicU64,
+    current_path: Mutex<String>,
+    pending_errors: Mutex<Vec<InaccessiblePath>>,
+    /// 已记录的错误数，用于限制总量
+    kept_errors: AtomicU64,
+}
+
+impl SearchStats {
+    pub fn enter_dir(&self, path: &Path) {
+        self.dirs_scanned.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut current) = self.current_path.lock() {
+            *current = path.to_string_lossy().to_string();
+        }
+    }
+
+    pub fn add_files(&self, count: u64) {
+        self.files_scanned.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_matches(&self, count: u64) {
+        self.matches.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self, path: &Path, error: &io::Error) {
+        self.error_count.fetch_add(1, Ordering::Relaxed);
+        if self.kept_errors.fetch_add(1, Ordering::Relaxed) >= MAX_ERRORS as u64 {
+            return;
+        }
+        if let Ok(mut errors) = self.pending_errors.lock() {
+            errors.push(InaccessiblePath {
+                path: path.to_string_lossy().to_string(),
+                kind: error.kind().into(),
+                message: error.to_string(),
+            });
+        }
+    }
+
+    /// 生成当前进度并取出新增的错误
+    pub fn snapshot(&self, search_id: &str, finished: bool) -> SearchProgress {
+        SearchProgress {
+            search_id: search_id.to_string(),
+            dirs_scanned: self.dirs_scanned.load(Ordering::Relaxed),
+            files_scanned: self.files_scanned.load(Ordering::Relaxed),
+            matches: self.matches.load(Ordering::Relaxed),
+            current_path: self
+                .current_path
+                .lock()
+                .map(|p| p.clone())
+                .unwrap_or_default(),
+            error_count: self.error_count.load(Ordering::Relaxed),
+            new_errors: self
+                .pending_errors
+                .lock()
+                .map(|mut e| std::mem::take(&mut *e))
+                .unwrap_or_default(),
+            finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_drains_errors() {
+        let stats = SearchStats::default();
+        stats.enter_dir(Path::new("/a"));
+        stats.add_files(3);
+        stats.add_matches(1);
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        stats.record_error(Path::new("/a/secret"), &denied);
+
+        let first = stats.snapshot("id", false);
+        assert_eq!(first.dirs_scanned, 1);
+        assert_eq!(first.files_scanned, 3);
+        assert_eq!(first.current_path, "/a");
+        assert_eq!(first.new_errors.len(), 1);
+        assert_eq!(first.new_errors[0].kind, AccessErrorKind::PermissionDenied);
+
+        let second = stats.snapshot("id", true);
+        assert_eq!(second.error_count, 1);
+        assert!(second.new_errors.is_empty());
+    }
+}
@@ -0,0 +1,320 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::exclude::ExcludeOptions;
+
+const DEFAULT_TOP: usize = 20;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsagePayload {
+    pub root: String,
+    pub concurrent: usize,
+    pub search_id: Option<String>,
+    /// 返回的最大目录与文件数量，默认 20
+    pub top: Option<usize>,
+    /// 为空时不使用默认排除规则且不跨越文件系统，统计结果与实际占用一致
+    pub exclude: Option<ExcludeOptions>,
+}
+
+impl DiskUsagePayload {
+    pub fn top(&self) -> usize {
+        self.top.unwrap_or(DEFAULT_TOP).max(1)
+    }
+
+    pub fn exclude_options(&self) -> ExcludeOptions {
+        self.exclude.clone().unwrap_or(ExcludeOptions {
+            exclude_globs: Some(vec![]),
+            one_file_system: true,
+            ..Default::default()
+        })
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageEntry {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    /// 目录为所有子项的累计大小
+    pub size: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageNode {
+    pub path: String,
+    pub size: u64,
+    /// 累计的文件与子目录数量
+    pub file_count: u64,
+    pub dir_count: u64,
+    /// 按大小从大到小排列
+    pub children: Vec<UsageEntry>,
+}
+
+/// 磁盘占用事件，扫描中只包含部分结果，`finished` 时附带最大的目录与文件
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageOutput {
+    pub search_id: String,
+    pub tree: Option<UsageNode>,
+    pub top_dirs: Vec<UsageEntry>,
+    pub top_files: Vec<UsageEntry>,
+    pub finished: bool,
+}
+
+#[derive(Default)]
+struct DirUsage {
+    size: u64,
+    files: u64,
+    dirs: u64,
+    children: Vec<PathBuf>,
+}
+
+fn entry_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// 以根目录为起点的累计大小树，只保存目录，文件只保留最大的若干个
+pub struct UsageTree {
+    root: PathBuf,
+    dirs: HashMap<PathBuf, DirUsage>,
+    top_files: BinaryHeap<Reverse<(u64, PathBuf)>>,
+    top: usize,
+}
+
+impl UsageTree {
+    pub fn new(root: &str, top: usize) -> Self {
+        let root = PathBuf::from(root);
+        let mut dirs = HashMap::new();
+        dirs.insert(root.clone(), DirUsage::default());
+        Self {
+            root,
+            dirs,
+            top_files: BinaryHeap::new(),
+            top,
+        }
+    }
+
+    /// 遍历结果可能先于父目录到达，缺失的上级目录在这里补齐
+    fn ensure_dir(&mut self, dir: &Path) {
+        if self.dirs.contains_key(dir) {
+            return;
+        }
+        self.dirs.insert(dir.to_path_buf(), DirUsage::default());
+        if let Some(parent) = dir.parent().filter(|p| p.starts_with(&self.root)) {
+            self.ensure_dir(parent);
+            if let Some(usage) = self.dirs.get_mut(parent) {
+                usage.children.push(dir.to_path_buf());
+            }
+        }
+    }
+
+    /// 将大小和数量累加到所有上级目录
+    fn add_to_ancestors(&mut self, path: &Path, size: u64, is_dir: bool) {
+        let mut current = path.parent();
+        while let Some(dir) = current.filter(|d| d.starts_with(&self.root)) {
+            if let Some(usage) = self.dirs.get_mut(dir) {
+                usage.size += size;
+                if is_dir {
+                    usage.dirs += 1;
+                } else {
+                    usage.files += 1;
+                }
+            }
+            current = dir.parent();
+        }
+    }
+
+    pub fn add(&mut self, path: &str, is_dir: bool, size: u64) {
+        let path = PathBuf::from(path);
+        if is_dir {
+            self.ensure_dir(&path);
+            self.add_to_ancestors(&path, 0, true);
+            return;
+        }
+        if let Some(parent) = path.parent() {
+            self.ensure_dir(parent);
+        }
+        self.add_to_ancestors(&path, size, false);
+
+        self.top_files.push(Reverse((size, path)));
+        if self.top_files.len() > self.top {
+            self.top_files.pop();
+        }
+    }
+
+    /// 目录的子目录按大小排序，最多返回 `limit` 个
+    pub fn node(&self, path: &Path, limit: usize) -> Option<UsageNode> {
+        let usage = self.dirs.get(path)?;
+        let mut children: Vec<UsageEntry> = usage
+            .children
+            .iter()
+            .filter_map(|child| {
+                self.dirs.get(child).map(|c| UsageEntry {
+                    path: child.to_string_lossy().to_string(),
+                    name: entry_name(child),
+                    is_dir: true,
+                    size: c.size,
+                })
+            })
+            .collect();
+        children.sort_by_key(|c| Reverse(c.size));
+        children.truncate(limit);
+        Some(UsageNode {
+            path: path.to_string_lossy().to_string(),
+            size: usage.size,
+            file_count: usage.files,
+            dir_count: usage.dirs,
+            children,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn top_dirs(&self) -> Vec<UsageEntry> {
+        let mut dirs: Vec<UsageEntry> = self
+            .dirs
+            .iter()
+            .filter(|(path, _)| **path != self.root)
+            .map(|(path, usage)| UsageEntry {
+                path: path.to_string_lossy().to_string(),
+                name: entry_name(path),
+                is_dir: true,
+                size: usage.size,
+            })
+            .collect();
+        dirs.sort_by_key(|d| Reverse(d.size));
+        dirs.truncate(self.top);
+        dirs
+    }
+
+    pub fn top_files(&self) -> Vec<UsageEntry> {
+        let mut files: Vec<UsageEntry> = self
+            .top_files
+            .iter()
+            .map(|Reverse((size, path))| UsageEntry {
+                path: path.to_string_lossy().to_string(),
+                name: entry_name(path),
+                is_dir: false,
+                size: *size,
+            })
+            .collect();
+        files.sort_by_key(|f| Reverse(f.size));
+        files
+    }
+}
+
+/// 扫描完成的结果，下钻时直接读取，按根目录保存
+#[derive(Default)]
+pub struct UsageCache {
+    trees: Mutex<HashMap<PathBuf, Arc<UsageTree>>>,
+}
+
+impl UsageCache {
+    pub fn insert(&self, tree: UsageTree) {
+        if let Ok(mut trees) = self.trees.lock() {
+            trees.insert(tree.root.clone(), Arc::new(tree));
+        }
+    }
+
+    /// 查找包含该路径的缓存，多个时取最近的根目录
+    pub fn find(&self, path: &Path) -> Option<Arc<UsageTree>> {
+        let trees = self.trees.lock().ok()?;
+        trees
+            .values()
+            .filter(|tree| path.starts_with(&tree.root))
+            .max_by_key(|tree| tree.root.components().count())
+            .cloned()
+    }
+
+    /// 未指定根目录时清空全部
+    pub fn clear(&self, root: Option<&Path>) {
+        if let Ok(mut trees) = self.trees.lock() {
+            match root {
+                Some(root) => {
+                    trees.remove(root);
+                }
+                None => trees.clear(),
+            }
+        }
+    }
+}
+
+/// 下钻时读取目录中的文件，子目录大小取自缓存
+pub fn drill_down(tree: &UsageTree, path: &Path, limit: usize) -> Option<UsageNode> {
+    let mut node = tree.node(path, usize::MAX)?;
+    if let Ok(read_dir) = std::fs::read_dir(path) {
+        for entry in read_dir.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(val) => val,
+                Err(_) => continue,
+            };
+            if metadata.is_file() {
+                let file_path = entry.path();
+                node.children.push(UsageEntry {
+                    path: file_path.to_string_lossy().to_string(),
+                    name: entry_name(&file_path),
+                    is_dir: false,
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+    node.children.sort_by_key(|c| Reverse(c.size));
+    node.children.truncate(limit);
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(path: &str) -> String {
+        Path::new("/root").join(path).to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_usage_tree() {
+        let mut tree = UsageTree::new("/root", 2);
+        // 子目录中的文件先于目录本身到达
+        tree.add(&p("a/b/big.bin"), false, 100);
+        tree.add(&p("a"), true, 0);
+        tree.add(&p("a/b"), true, 0);
+        tree.add(&p("a/small.txt"), false, 5);
+        tree.add(&p("c"), true, 0);
+        tree.add(&p("c/mid.iso"), false, 50);
+        tree.add(&p("top.txt"), false, 1);
+
+        let root = tree.node(Path::new("/root"), 10).unwrap();
+        assert_eq!(root.size, 156);
+        assert_eq!(root.file_count, 4);
+        assert_eq!(root.dir_count, 3);
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].name, "a");
+        assert_eq!(root.children[0].size, 105);
+
+        let top_files = tree.top_files();
+        assert_eq!(top_files.len(), 2);
+        assert_eq!(top_files[0].size, 100);
+        assert_eq!(top_files[1].size, 50);
+
+        let top_dirs = tree.top_dirs();
+        assert_eq!(top_dirs[0].name, "a");
+        assert_eq!(top_dirs[1].name, "b");
+
+        let cache = UsageCache::default();
+        cache.insert(tree);
+        assert!(cache.find(Path::new("/root/a/b")).is_some());
+        assert!(cache.find(Path::new("/other")).is_none());
+    }
+}
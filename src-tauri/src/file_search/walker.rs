@@ -0,0 +1,411 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::utils::archive::{self, ArchiveEntry};
+
+use super::exclude::DirScope;
+use super::session::SearchControl;
+use super::{SearchOptions, SearchResultModel};
+
+/// 等待新任务时检查取消状态的间隔
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+struct DirJob {
+    path: PathBuf,
+    /// 该目录中条目所在的层级，所选目录的直接子项为 1
+    depth: usize,
+    scope: DirScope,
+}
+
+#[derive(Default)]
+struct QueueState {
+    /// 按栈的方式取出，深度优先以减少待处理目录的数量
+    jobs: Vec<DirJob>,
+    /// 正在处理的目录数，为 0 且没有待处理目录时遍历结束
+    active: usize,
+}
+
+#[derive(Default)]
+struct WorkQueue {
+    state: Mutex<QueueState>,
+    ready: Condvar,
+}
+
+impl WorkQueue {
+    fn push(&self, job: DirJob) {
+        if let Ok(mut state) = self.state.lock() {
+            state.jobs.push(job);
+            self.ready.notify_one();
+        }
+    }
+
+    fn pop(&self, control: &SearchControl) -> Option<DirJob> {
+        let mut state = self.state.lock().ok()?;
+        loop {
+            if control.is_cancelled() {
+                self.ready.notify_all();
+                return None;
+            }
+            // 暂停时不再取出新目录
+            if !control.is_paused() {
+                if let Some(job) = state.jobs.pop() {
+                    state.active += 1;
+                    return Some(job);
+                }
+            }
+            if state.active == 0 && state.jobs.is_empty() {
+                self.ready.notify_all();
+                return None;
+            }
+            state = self.ready.wait_timeout(state, WAIT_INTERVAL).ok()?.0;
+        }
+    }
+
+    fn done(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.active -= 1;
+        if state.active == 0 && state.jobs.is_empty() {
+            self.ready.notify_all();
+        }
+    }
+}
+
+/// 取出目录后持有，释放时减少正在处理的目录数，处理中途 panic 或提前退出也不会让其他线程一直等待
+struct ActiveGuard<'a>(&'a WorkQueue);
+
+impl Drop for ActiveGuard<'_> {
+    fn drop(&mut self) {
+        self.0.done();
+    }
+}
+
+/// 启动固定数量的线程遍历目录，所有线程结束后 `tx` 全部释放，接收端随之结束
+pub fn spawn(
+    roots: Vec<String>,
+    options: Arc<SearchOptions>,
+    concurrent: usize,
+    tx: mpsc::Sender<Vec<SearchResultModel>>,
+    control: Arc<SearchControl>,
+) {
+    let queue = Arc::new(WorkQueue::default());
+    for root in roots {
+        queue.push(DirJob {
+            path: PathBuf::from(root),
+            depth: 1,
+            scope: DirScope::default(),
+        });
+    }
+
+    for i in 0..concurrent.max(1) {
+        let queue = queue.clone();
+        let options = options.clone();
+        let tx = tx.clone();
+        let control = control.clone();
+        let spawned = thread::Builder::new()
+            .name(format!("file-search-{}", i))
+            .spawn(move || {
+                while let Some(job) = queue.pop(&control) {
+                    let _active = ActiveGuard(&queue);
+                    if !process_dir(job, &options, &queue, &tx, &control) {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            println!("[file-search] 创建搜索线程失败：{}", e);
+        }
+    }
+}
+
+/// 读取单个目录，发送匹配结果并将子目录加入队列；接收端关闭时返回 false
+fn process_dir(
+    job: DirJob,
+    options: &SearchOptions,
+    queue: &WorkQueue,
+    tx: &mpsc::Sender<Vec<SearchResultModel>>,
+    control: &SearchControl,
+) -> bool {
+    let stats = control.stats();
+    let scope = match options.exclude.enter(&job.path, &job.scope) {
+        Ok(Some(scope)) => scope,
+        Ok(None) => return true,
+        Err(e) => {
+            stats.record_error(&job.path, &e);
+            return true;
+        }
+    };
+    // 无法读取的目录只记录下来，不影响其他目录
+    let read_dir = match std::fs::read_dir(&job.path) {
+        Ok(val) => val,
+        Err(e) => {
+            stats.record_error(&job.path, &e);
+            return true;
+        }
+    };
+    stats.enter_dir(&job.path);
+    let descend = options.filter.can_descend(job.depth);
+
+    let mut search_result = vec![];
+    let mut content_files = vec![];
+    let mut archive_files = vec![];
+    let mut scanned = 0;
+    for entry in read_dir {
+        let file = match entry {
+            Ok(val) => val,
+            Err(e) => {
+                stats.record_error(&job.path, &e);
+                continue;
+            }
+        };
+        scanned += 1;
+        let file_name = match file.file_name().into_string() {
+            Ok(str) => str,
+            Err(_) => continue,
+        };
+        let file_path = file.path();
+        let metadata = match file.metadata() {
+            Ok(val) => val,
+            Err(e) => {
+                stats.record_error(&file_path, &e);
+                continue;
+            }
+        };
+        if options
+            .exclude
+            .is_excluded(&file_name, &file_path, &metadata, &scope)
+        {
+            continue;
+        }
+
+        let file_path_str = file_path.to_string_lossy().to_string();
+        let matched = options
+            .matcher
+            .matches(&file_name, &file_path_str)
+            .filter(|_| options.filter.accepts(&file_name, &metadata))
+            .filter(|_| metadata.is_dir() || options.filter.accepts_type(&file_path));
+        if let Some(score) = matched {
+            if let Some(content) = &options.content {
+                // 内容搜索时文件名规则只用于筛选候选文件
+                if metadata.is_file() && content.is_candidate(&file_name, metadata.len()) {
+                    content_files.push(SearchResultModel::from_metadata(file_path_str, &metadata));
+                }
+            } else {
+                search_result.push(SearchResultModel {
+                    score: options.matcher.is_fuzzy().then_some(score),
+                    ..SearchResultModel::from_metadata(file_path_str, &metadata)
+                });
+            }
+        }
+
+        if options.archives
+            && metadata.is_file()
+            && archive::detect_archive_kind(&file_name).is_some()
+        {
+            archive_files.push(file_path.to_string_lossy().to_string());
+        }
+
+        // 符号链接指向的目录只在开启跟随时进入，已访问的目录会在 enter 中跳过
+        let is_dir = metadata.is_dir()
+            || (metadata.is_symlink() && options.exclude.follow_symlinks() && file_path.is_dir());
+        if is_dir && descend {
+            queue.push(DirJob {
+                path: file_path,
+                depth: job.depth + 1,
+                scope: scope.clone(),
+            });
+        }
+    }
+
+    stats.add_files(scanned);
+    if !search_result.is_empty() {
+        stats.add_matches(search_result.len() as u64);
+        if tx.blocking_send(search_result).is_err() {
+            return false;
+        }
+    }
+    search_contents(options, content_files, tx, control)
+        && search_archives(options, archive_files, tx, control)
+}
+
+/// 包内条目按名称与过滤条件匹配，层级限制不作用于包内
+fn match_archive_entry(
+    options: &SearchOptions,
+    archive_path: &str,
+    entry: ArchiveEntry,
+) -> Option<SearchResultModel> {
+    if !options.filter.categories.is_empty() {
+        return None;
+    }
+    let name = entry.path.rsplit('/').next().unwrap_or(&entry.path);
+    let path = archive::join_archive_path(archive_path, &entry.path);
+    let score = options.matcher.matches(name, &path)?;
+    if !options
+        .filter
+        .accepts_entry(name, entry.is_dir, entry.size, entry.modified, None)
+    {
+        return None;
+    }
+    Some(SearchResultModel {
+        score: options.matcher.is_fuzzy().then_some(score),
+        ..SearchResultModel::new(path, entry.is_dir, entry.size, entry.modified, None)
+    })
+}
+
+/// 读取压缩包的条目列表，每个有命中的压缩包发送一次结果
+fn search_archives(
+    options: &SearchOptions,
+    files: Vec<String>,
+    tx: &mpsc::Sender<Vec<SearchResultModel>>,
+    control: &SearchControl,
+) -> bool {
+    for file in files {
+        if control.wait_if_paused() {
+            return false;
+        }
+        let entries = match archive::list_entries(Path::new(&file)) {
+            Ok(val) => val,
+            Err(e) => {
+                // 损坏的压缩包或缺少 7-Zip 程序同样记为无法访问
+                let error = match e.downcast::<std::io::Error>() {
+                    Ok(e) => e,
+                    Err(e) => std::io::Error::other(e.to_string()),
+                };
+                control.stats().record_error(Path::new(&file), &error);
+                continue;
+            }
+        };
+        control.stats().add_files(entries.len() as u64);
+        let results: Vec<_> = entries
+            .into_iter()
+            .filter_map(|entry| match_archive_entry(options, &file, entry))
+            .collect();
+        if results.is_empty() {
+            continue;
+        }
+        control.stats().add_matches(results.len() as u64);
+        if tx.blocking_send(results).is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// 逐个文件搜索内容，每个有命中的文件发送一次结果
+fn search_contents(
+    options: &SearchOptions,
+    files: Vec<SearchResultModel>,
+    tx: &mpsc::Sender<Vec<SearchResultModel>>,
+    control: &SearchControl,
+) -> bool {
+    let content = match &options.content {
+        Some(val) => val,
+        None => return true,
+    };
+    for file in files {
+        if control.wait_if_paused() {
+            return false;
+        }
+        let hits = match content.search_file(Path::new(&file.path)) {
+            Ok(val) => val,
+            Err(e) => {
+                if let Some(e) = e.downcast_ref::<std::io::Error>() {
+                    control.stats().record_error(Path::new(&file.path), e);
+                }
+                continue;
+            }
+        };
+        if hits.is_empty() {
+            continue;
+        }
+        control.stats().add_matches(hits.len() as u64);
+        let results = hits
+            .into_iter()
+            .map(|hit| SearchResultModel {
+                line: Some(hit.line),
+                snippet: Some(hit.snippet),
+                ..file.clone()
+            })
+            .collect();
+        if tx.blocking_send(results).is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_search::exclude::{ExcludeOptions, ExcludeRules};
+    use crate::file_search::filter::SearchFilter;
+    use crate::file_search::matcher::{MatchOptions, Matcher};
+
+    #[test]
+    fn test_walk_collects_all_matches() {
+        let root = std::env::temp_dir().join(format!("tool-box-walker-{}", std::process::id()));
+        for dir in ["a/b/c", "d/e", "f"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "a/x.log",
+            "a/b/y.log",
+            "a/b/c/z.log",
+            "d/e/w.log",
+            "f/skip.txt",
+        ] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+
+        let options = Arc::new(SearchOptions {
+            matcher: Matcher::new(&MatchOptions::default(), ".log").unwrap(),
+            filter: SearchFilter::default(),
+            exclude: ExcludeRules::new(&ExcludeOptions::default()).unwrap(),
+            content: None,
+            archives: false,
+        });
+        let (tx, mut rx) = mpsc::channel(2);
+        let control = Arc::new(SearchControl::default());
+        spawn(
+            vec![root.to_string_lossy().to_string()],
+            options,
+            3,
+            tx,
+            control.clone(),
+        );
+
+        let mut found = vec![];
+        while let Some(batch) = rx.blocking_recv() {
+            found.extend(batch.into_iter().map(|r| r.path));
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found.len(), 4);
+
+        let progress = control.stats().snapshot("walk", true);
+        assert_eq!(progress.matches, 4);
+        assert_eq!(progress.dirs_scanned, 7);
+        assert_eq!(progress.files_scanned, 11);
+    }
+
+    #[test]
+    fn test_active_released_on_panic() {
+        let queue = WorkQueue::default();
+        let control = SearchControl::default();
+        queue.push(DirJob {
+            path: PathBuf::from("/"),
+            depth: 1,
+            scope: DirScope::default(),
+        });
+        let job = queue.pop(&control).unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _active = ActiveGuard(&queue);
+            drop(job);
+            panic!("process_dir panicked");
+        }));
+        assert!(result.is_err());
+        assert!(queue.pop(&control).is_none());
+    }
+}
@@ -1,13 +1,34 @@
 use autostart::{is_auto_start_enabled, set_auto_start};
 use download::{
-    check_server_range_support, download_file, download_file_with_config, scan_unfinished_downloads,
+    cancel_scheduled_download, check_server_range_support, download_file,
+    download_file_with_config, download_files_batch, get_bandwidth_rules,
+    get_browser_bridge_config, get_download_notify_settings, get_host_policies,
+    list_scheduled_downloads, scan_unfinished_downloads, schedule_download, set_bandwidth_rules,
+    set_browser_bridge_config, set_download_notify_settings, set_host_policies,
+    set_post_action_rules,
+};
+use file_ops::{
+    apply_batch_rename, cancel_dir_sync, cancel_hash_task, check_file_exists,
+    clear_folder_rule_log, compare_directories, delete_file, delete_folder_rule, hash_files,
+    list_file_operations, list_folder_rule_log, list_folder_rules, list_rename_history,
+    preview_batch_rename, reveal_in_folder, run_file_operation, save_folder_rule, sync_directories,
+    test_folder_rule, undo_batch_rename, undo_file_operation, verify_checksum_file,
+};
+use file_search::{
+    cancel_search_task, clean_up_items, clear_disk_usage_cache, clear_search_history,
+    clear_search_results, delete_saved_search, detect_file_type, export_search_results,
+    extract_archive_entry, find_duplicate_files, get_disk_usage, get_file_index_config,
+    get_file_index_status, get_search_default_excludes, list_saved_searches, list_search_history,
+    list_search_tasks, pause_search_task, query_file_index, query_search_results,
+    rebuild_file_index, resolve_duplicate_files, resume_search_task, run_saved_search, save_search,
+    scan_cleanup_candidates, scan_disk_usage, search_disk_file_real_time, set_file_index_config,
 };
-use file_search::{cancel_search_task, search_disk_file_real_time};
 use font::get_system_fonts;
 use utils::os::{get_cpu_info, get_harddisk_info};
 
 mod autostart;
 mod download;
+mod file_ops;
 mod file_search;
 mod font;
 mod utils;
@@ -25,16 +46,80 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(file_search::init())
+        .plugin(file_ops::init())
+        .plugin(download::init())
         .invoke_handler(tauri::generate_handler![
             download_file,
             download_file_with_config,
+            download_files_batch,
             scan_unfinished_downloads,
             check_server_range_support,
+            set_post_action_rules,
+            get_download_notify_settings,
+            set_download_notify_settings,
+            schedule_download,
+            cancel_scheduled_download,
+            list_scheduled_downloads,
+            get_bandwidth_rules,
+            set_bandwidth_rules,
+            get_browser_bridge_config,
+            set_browser_bridge_config,
+            get_host_policies,
+            set_host_policies,
             get_cpu_info,
             get_harddisk_info,
             get_system_fonts,
             search_disk_file_real_time,
             cancel_search_task,
+            pause_search_task,
+            resume_search_task,
+            list_search_tasks,
+            query_search_results,
+            export_search_results,
+            clear_search_results,
+            extract_archive_entry,
+            detect_file_type,
+            get_search_default_excludes,
+            query_file_index,
+            get_file_index_config,
+            set_file_index_config,
+            get_file_index_status,
+            rebuild_file_index,
+            find_duplicate_files,
+            resolve_duplicate_files,
+            scan_disk_usage,
+            get_disk_usage,
+            clear_disk_usage_cache,
+            scan_cleanup_candidates,
+            clean_up_items,
+            list_search_history,
+            clear_search_history,
+            list_saved_searches,
+            save_search,
+            delete_saved_search,
+            run_saved_search,
+            run_file_operation,
+            undo_file_operation,
+            list_file_operations,
+            reveal_in_folder,
+            preview_batch_rename,
+            apply_batch_rename,
+            undo_batch_rename,
+            list_rename_history,
+            hash_files,
+            verify_checksum_file,
+            cancel_hash_task,
+            compare_directories,
+            sync_directories,
+            cancel_dir_sync,
+            list_folder_rules,
+            save_folder_rule,
+            delete_folder_rule,
+            test_folder_rule,
+            list_folder_rule_log,
+            clear_folder_rule_log,
+            check_file_exists,
+            delete_file,
             set_auto_start,
             is_auto_start_enabled,
         ])
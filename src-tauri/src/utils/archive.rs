@@ -0,0 +1,514 @@
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use anyhow::{Error, Result as AnyResult};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use flate2::read::GzDecoder;
+use xz2::read::XzDecoder;
+
+/// 压缩包内条目路径的分隔符，如 `logs.zip!/2024/app.log`
+pub const ARCHIVE_PATH_SEPARATOR: &str = "!/";
+
+/// 7z 格式没有纯 Rust 实现可用，通过系统中安装的 7-Zip 命令行程序读取
+const SEVEN_ZIP_PROGRAMS: [&str; 3] = ["7z", "7zz", "7za"];
+
+/// 列出条目时最多读取的条目数量，超出时放弃该压缩包
+const MAX_LIST_ENTRIES: usize = 100_000;
+/// tar 系列需要顺序读完整个压缩包才能列出条目，超过该大小的压缩包不读取
+const MAX_TAR_LIST_FILE_SIZE: u64 = 512 * 1024 * 1024;
+/// 列出 tar 条目时最多解压的数据量，防止高压缩比的压缩包耗尽时间
+const MAX_TAR_LIST_UNPACKED_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    SevenZ,
+}
+
+/// 压缩包中的单个条目，`path` 使用 `/` 分隔且不以 `/` 结尾
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    /// 毫秒时间戳
+    pub modified: Option<i64>,
+}
+
+/// 根据文件名后缀判断压缩包类型
+pub fn detect_archive_kind<P: AsRef<Path>>(path: P) -> Option<ArchiveKind> {
+    let name = path.as_ref().file_name()?.to_str()?.to_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+        Some(ArchiveKind::TarXz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else if name.ends_with(".7z") {
+        Some(ArchiveKind::SevenZ)
+    } else {
+        None
+    }
+}
+
+/// 去掉压缩包后缀后的文件名，用作默认解压目录名
+pub fn archive_stem<P: AsRef<Path>>(path: P) -> Option<String> {
+    let name = path.as_ref().file_name()?.to_str()?;
+    let lower = name.to_lowercase();
+    let suffix_len = [".tar.gz", ".tar.xz", ".tgz", ".txz", ".tar", ".zip", ".7z"]
+        .iter()
+        .find(|suffix| lower.ends_with(*suffix))
+        .map(|suffix| suffix.len())?;
+    Some(name[..name.len() - suffix_len].to_string())
+}
+
+/// 解压到指定目录，返回解压出的条目数量（阻塞操作）
+pub fn extract_archive(archive: &Path, target_dir: &Path) -> AnyResult<usize> {
+    let kind = detect_archive_kind(archive).ok_or(Error::msg("不支持的压缩格式"))?;
+    fs::create_dir_all(target_dir)?;
+
+    match kind {
+        ArchiveKind::Zip => extract_zip(archive, target_dir),
+        ArchiveKind::Tar => unpack_tar(File::open(archive)?, target_dir),
+        ArchiveKind::TarGz => unpack_tar(GzDecoder::new(File::open(archive)?), target_dir),
+        ArchiveKind::TarXz => unpack_tar(XzDecoder::new(File::open(archive)?), target_dir),
+        ArchiveKind::SevenZ => {
+            let mut output = OsString::from("-o");
+            output.push(target_dir);
+            run_seven_zip(&[
+                OsString::from("x"),
+                OsString::from("-y"),
+                output,
+                archive.as_os_str().to_owned(),
+            ])?;
+            Ok(list_seven_zip(archive)?.len())
+        }
+    }
+}
+
+fn extract_zip(archive: &Path, target_dir: &Path) -> AnyResult<usize> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+    let mut count = 0;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        // 拒绝包含 `..` 或绝对路径的条目，防止写出目标目录
+        let relative = match entry.enclosed_name() {
+            Some(p) => p,
+            None => continue,
+        };
+        let out_path = target_dir.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+        } else {
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut out = File::create(&out_path)?;
+            io::copy(&mut entry, &mut out)?;
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn unpack_tar<R: io::Read>(reader: R, target_dir: &Path) -> AnyResult<usize> {
+    let mut archive = tar::Archive::new(reader);
+    let mut count = 0;
+    for entry in archive.entries()? {
+        // unpack_in 会跳过越界路径
+        if entry?.unpack_in(target_dir)? {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// 拼接压缩包内条目的显示路径
+pub fn join_archive_path(archive: &str, inner: &str) -> String {
+    format!("{}{}{}", archive, ARCHIVE_PATH_SEPARATOR, inner)
+}
+
+/// 拆分为压缩包路径与包内路径，分隔符前必须是支持的压缩包
+pub fn split_archive_path(path: &str) -> Option<(&str, &str)> {
+    path.match_indices(ARCHIVE_PATH_SEPARATOR)
+        .map(|(index, _)| {
+            (
+                &path[..index],
+                &path[index + ARCHIVE_PATH_SEPARATOR.len()..],
+            )
+        })
+        .find(|(archive, inner)| !inner.is_empty() && detect_archive_kind(archive).is_some())
+}
+
+fn to_local_millis(time: Option<NaiveDateTime>) -> Option<i64> {
+    Local
+        .from_local_datetime(&time?)
+        .earliest()
+        .map(|t| t.timestamp_millis())
+}
+
+fn normalize_entry_path(path: &str) -> String {
+    path.replace('\\', "/").trim_matches('/').to_string()
+}
+
+/// 列出压缩包中的全部条目（阻塞操作），tar.gz 等流式格式需要完整解压一遍，
+/// 因此限制压缩包大小与解压的数据量，所有格式都限制条目数量
+pub fn list_entries(archive: &Path) -> AnyResult<Vec<ArchiveEntry>> {
+    let kind = detect_archive_kind(archive).ok_or(Error::msg("不支持的压缩格式"))?;
+    if matches!(
+        kind,
+        ArchiveKind::Tar | ArchiveKind::TarGz | ArchiveKind::TarXz
+    ) && fs::metadata(archive)?.len() > MAX_TAR_LIST_FILE_SIZE
+    {
+        return Err(Error::msg("压缩包过大，跳过读取条目"));
+    }
+    match kind {
+        ArchiveKind::Zip => list_zip(archive),
+        ArchiveKind::Tar => list_tar(File::open(archive)?),
+        ArchiveKind::TarGz => list_tar(GzDecoder::new(File::open(archive)?)),
+        ArchiveKind::TarXz => list_tar(XzDecoder::new(File::open(archive)?)),
+        ArchiveKind::SevenZ => list_seven_zip(archive),
+    }
+}
+
+fn list_zip(archive: &Path) -> AnyResult<Vec<ArchiveEntry>> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+    if zip.len() > MAX_LIST_ENTRIES {
+        return Err(Error::msg("压缩包条目过多，跳过读取条目"));
+    }
+    let mut entries = vec![];
+    for i in 0..zip.len() {
+        // 只读取条目信息，不解压数据
+        let entry = zip.by_index_raw(i)?;
+        let modified =
+            entry.last_modified().and_then(|t| {
+                NaiveDate::from_ymd_opt(t.year() as i32, t.month() as u32, t.day() as u32)?
+                    .and_hms_opt(t.hour() as u32, t.minute() as u32, t.second() as u32)
+            });
+        entries.push(ArchiveEntry {
+            path: normalize_entry_path(entry.name()),
+            is_dir: entry.is_dir(),
+            size: entry.size(),
+            modified: to_local_millis(modified),
+        });
+    }
+    Ok(entries)
+}
+
+fn list_tar<R: io::Read>(reader: R) -> AnyResult<Vec<ArchiveEntry>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = vec![];
+    let mut unpacked = 0u64;
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let size = header.size()?;
+        unpacked = unpacked.saturating_add(size);
+        if entries.len() >= MAX_LIST_ENTRIES || unpacked > MAX_TAR_LIST_UNPACKED_SIZE {
+            return Err(Error::msg("压缩包条目过多或内容过大，跳过读取条目"));
+        }
+        entries.push(ArchiveEntry {
+            path: normalize_entry_path(&entry.path()?.to_string_lossy()),
+            is_dir: header.entry_type().is_dir(),
+            size,
+            modified: header.mtime().ok().map(|t| t as i64 * 1000),
+        });
+    }
+    Ok(entries)
+}
+
+/// 依次尝试各个 7-Zip 程序名，都不存在时提示安装
+fn run_seven_zip(args: &[OsString]) -> AnyResult<Output> {
+    for program in SEVEN_ZIP_PROGRAMS {
+        let output = match Command::new(program).args(args).output() {
+            Ok(val) => val,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::msg(format!("7-Zip 执行失败：{}", stderr.trim())));
+        }
+        return Ok(output);
+    }
+    Err(Error::msg(
+        "未找到 7-Zip 命令行程序（7z / 7zz），无法读取 7z 压缩包",
+    ))
+}
+
+fn list_seven_zip(archive: &Path) -> AnyResult<Vec<ArchiveEntry>> {
+    let output = run_seven_zip(&[
+        OsString::from("l"),
+        OsString::from("-slt"),
+        archive.as_os_str().to_owned(),
+    ])?;
+    let entries = parse_seven_zip_list(&String::from_utf8_lossy(&output.stdout));
+    if entries.len() > MAX_LIST_ENTRIES {
+        return Err(Error::msg("压缩包条目过多，跳过读取条目"));
+    }
+    Ok(entries)
+}
+
+/// 解析 `7z l -slt` 的输出，分隔线之前是压缩包本身的信息
+fn parse_seven_zip_list(output: &str) -> Vec<ArchiveEntry> {
+    let body = match output.split_once("\n----------") {
+        Some((_, body)) => body,
+        None => return vec![],
+    };
+    let mut entries = vec![];
+    for block in body.replace("\r\n", "\n").split("\n\n") {
+        let mut entry = ArchiveEntry {
+            path: String::new(),
+            is_dir: false,
+            size: 0,
+            modified: None,
+        };
+        for line in block.lines() {
+            let (key, value) = match line.split_once(" = ") {
+                Some(val) => val,
+                None => continue,
+            };
+            match key {
+                "Path" => entry.path = normalize_entry_path(value),
+                "Size" => entry.size = value.parse().unwrap_or(0),
+                "Folder" => entry.is_dir = value == "+",
+                "Attributes" => entry.is_dir |= value.starts_with('D'),
+                // 较新版本会带上小数秒，只取到秒
+                "Modified" => {
+                    let time = value.get(..19).unwrap_or(value);
+                    entry.modified = to_local_millis(
+                        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S").ok(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        if !entry.path.is_empty() {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// 只解压压缩包中的一个文件到目标目录，返回解压后的路径（阻塞操作）
+pub fn extract_entry(archive: &Path, inner: &str, target_dir: &Path) -> AnyResult<PathBuf> {
+    let kind = detect_archive_kind(archive).ok_or(Error::msg("不支持的压缩格式"))?;
+    let inner = normalize_entry_path(inner);
+    let name = inner
+        .rsplit('/')
+        .next()
+        .filter(|n| !n.is_empty() && *n != "..")
+        .ok_or(Error::msg("无效的条目路径"))?;
+    fs::create_dir_all(target_dir)?;
+    let out_path = target_dir.join(name);
+    if out_path.exists() {
+        return Err(Error::msg(format!(
+            "目标位置已存在同名文件：{}",
+            out_path.display()
+        )));
+    }
+
+    let result = match kind {
+        ArchiveKind::Zip => extract_zip_entry(archive, &inner, &out_path),
+        ArchiveKind::Tar => extract_tar_entry(File::open(archive)?, &inner, &out_path),
+        ArchiveKind::TarGz => {
+            extract_tar_entry(GzDecoder::new(File::open(archive)?), &inner, &out_path)
+        }
+        ArchiveKind::TarXz => {
+            extract_tar_entry(XzDecoder::new(File::open(archive)?), &inner, &out_path)
+        }
+        ArchiveKind::SevenZ => {
+            // 7-Zip 会把 `*`、`?` 当作通配符，可能解压出其他文件
+            if inner.contains(['*', '?']) {
+                return Err(Error::msg("条目路径包含通配符，无法通过 7-Zip 提取"));
+            }
+            let mut output = OsString::from("-o");
+            output.push(target_dir);
+            run_seven_zip(&[
+                OsString::from("e"),
+                output,
+                archive.as_os_str().to_owned(),
+                OsString::from("--"),
+                OsString::from(&inner),
+            ])
+            .and_then(|_| {
+                out_path
+                    .exists()
+                    .then_some(())
+                    .ok_or(Error::msg("压缩包中找不到该文件"))
+            })
+        }
+    };
+    if result.is_err() {
+        let _ = fs::remove_file(&out_path);
+    }
+    result.map(|_| out_path)
+}
+
+fn extract_zip_entry(archive: &Path, inner: &str, out_path: &Path) -> AnyResult<()> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+    let mut entry = zip
+        .by_name(inner)
+        .map_err(|_| Error::msg("压缩包中找不到该文件"))?;
+    if entry.is_dir() {
+        return Err(Error::msg("只能提取压缩包中的文件"));
+    }
+    io::copy(&mut entry, &mut File::create(out_path)?)?;
+    Ok(())
+}
+
+fn extract_tar_entry<R: io::Read>(reader: R, inner: &str, out_path: &Path) -> AnyResult<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if normalize_entry_path(&entry.path()?.to_string_lossy()) != inner {
+            continue;
+        }
+        if entry.header().entry_type().is_dir() {
+            return Err(Error::msg("只能提取压缩包中的文件"));
+        }
+        io::copy(&mut entry, &mut File::create(out_path)?)?;
+        return Ok(());
+    }
+    Err(Error::msg("压缩包中找不到该文件"))
+}
+
+/// 在压缩包旁边生成默认解压目录，若已存在则追加序号
+pub fn default_extract_dir(archive: &Path) -> PathBuf {
+    let parent = archive.parent().unwrap_or(Path::new("."));
+    let stem = archive_stem(archive).unwrap_or_else(|| String::from("extracted"));
+    let base = parent.join(&stem);
+    if !base.exists() {
+        return base;
+    }
+    (1..=100)
+        .map(|i| parent.join(format!("{}({})", stem, i)))
+        .find(|p| !p.exists())
+        .unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_archive_kind() {
+        assert_eq!(detect_archive_kind("a/b.zip"), Some(ArchiveKind::Zip));
+        assert_eq!(detect_archive_kind("b.TAR.GZ"), Some(ArchiveKind::TarGz));
+        assert_eq!(detect_archive_kind("b.tgz"), Some(ArchiveKind::TarGz));
+        assert_eq!(detect_archive_kind("b.tar.xz"), Some(ArchiveKind::TarXz));
+        assert_eq!(detect_archive_kind("b.tar"), Some(ArchiveKind::Tar));
+        assert_eq!(detect_archive_kind("b.7z"), Some(ArchiveKind::SevenZ));
+        assert_eq!(detect_archive_kind("b.gz"), None);
+        assert_eq!(detect_archive_kind("b.txt"), None);
+    }
+
+    #[test]
+    fn test_archive_stem() {
+        assert_eq!(
+            archive_stem("dir/release-1.0.tar.gz").as_deref(),
+            Some("release-1.0")
+        );
+        assert_eq!(archive_stem("pack.ZIP").as_deref(), Some("pack"));
+        assert_eq!(archive_stem("notes.txt"), None);
+    }
+
+    #[test]
+    fn test_split_archive_path() {
+        assert_eq!(
+            split_archive_path("/logs/a.zip!/2024/app.log"),
+            Some(("/logs/a.zip", "2024/app.log"))
+        );
+        assert_eq!(
+            split_archive_path("/odd!/b.tar.gz!/x"),
+            Some(("/odd!/b.tar.gz", "x"))
+        );
+        assert_eq!(split_archive_path("/logs/a.txt!/x"), None);
+        assert_eq!(split_archive_path("/logs/a.zip!/"), None);
+        assert_eq!(
+            join_archive_path("/logs/a.zip", "2024/app.log"),
+            "/logs/a.zip!/2024/app.log"
+        );
+    }
+
+    #[test]
+    fn test_list_and_extract_tar_entry() {
+        let root = std::env::temp_dir().join(format!("tool-box-archive-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let archive = root.join("logs.tar");
+        let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mtime(1_700_000_000);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "2024/app.log", &b"hello"[..])
+            .unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        let entries = list_entries(&archive).unwrap();
+        assert_eq!(
+            entries,
+            vec![ArchiveEntry {
+                path: String::from("2024/app.log"),
+                is_dir: false,
+                size: 5,
+                modified: Some(1_700_000_000_000),
+            }]
+        );
+
+        let out = root.join("out");
+        let path = extract_entry(&archive, "2024/app.log", &out).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hello");
+        assert!(extract_entry(&archive, "2024/app.log", &out).is_err());
+        assert!(extract_entry(&archive, "missing.log", &out).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_parse_seven_zip_list() {
+        let output = "\
+Listing archive: a.7z
+
+--
+Path = a.7z
+Type = 7z
+
+----------
+Path = logs
+Size = 0
+Modified = 2024-01-02 03:04:05
+Attributes = D....
+
+Path = logs\\app.log
+Size = 12
+Modified = 2024-01-02 03:04:05.1234567
+Attributes = A....
+";
+        let entries = parse_seven_zip_list(output);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[1].path, "logs/app.log");
+        assert_eq!(entries[1].size, 12);
+        assert_eq!(entries[1].modified, entries[0].modified);
+        assert!(entries[1].modified.is_some());
+    }
+
+    #[test]
+    fn test_seven_zip_rejects_wildcards() {
+        let out = std::env::temp_dir().join(format!("tool-box-7z-{}", std::process::id()));
+        let err = extract_entry(Path::new("/missing/a.7z"), "logs/*.log", &out).unwrap_err();
+        assert!(err.to_string().contains("通配符"));
+        let _ = fs::remove_dir_all(&out);
+    }
+}
@@ -0,0 +1,430 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// 识别类型时读取的文件头长度，Office 文档需要查看压缩包内的前几个条目
+const HEADER_LEN: u64 = 8 * 1024;
+/// ISO 9660 的卷描述符位于 32 KB 之后
+const ISO_MAGIC_OFFSET: u64 = 0x8001;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum FileCategory {
+    Image,
+    Video,
+    Audio,
+    Archive,
+    Executable,
+    Document,
+    Text,
+    Unknown,
+}
+
+/// 按文件头识别出的类型，`extensions` 的第一项为常用扩展名
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileType {
+    pub category: FileCategory,
+    pub mime: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
+const fn kind(
+    category: FileCategory,
+    mime: &'static str,
+    extensions: &'static [&'static str],
+) -> FileType {
+    FileType {
+        category,
+        mime,
+        extensions,
+    }
+}
+
+use FileCategory::*;
+
+const TEXT: FileType = kind(Text, "text/plain", &[]);
+const UNKNOWN: FileType = kind(Unknown, "application/octet-stream", &[]);
+
+const ZIP: FileType = kind(Archive, "application/zip", &["zip"]);
+const DOCX: FileType = kind(
+    Document,
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    &["docx"],
+);
+const XLSX: FileType = kind(
+    Document,
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    &["xlsx"],
+);
+const PPTX: FileType = kind(
+    Document,
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    &["pptx"],
+);
+const EPUB: FileType = kind(Document, "application/epub+zip", &["epub"]);
+const ODF: FileType = kind(
+    Document,
+    "application/vnd.oasis.opendocument",
+    &["odt", "ods", "odp"],
+);
+const JAR: FileType = kind(Archive, "application/java-archive", &["jar", "apk"]);
+
+/// 固定偏移处的特征字节
+struct Signature {
+    offset: usize,
+    magic: &'static [u8],
+    kind: FileType,
+}
+
+const fn sig(offset: usize, magic: &'static [u8], kind: FileType) -> Signature {
+    Signature {
+        offset,
+        magic,
+        kind,
+    }
+}
+
+/// 按顺序匹配，较长、较具体的特征放在前面
+const SIGNATURES: &[Signature] = &[
+    // 图片
+    sig(0, b"\x89PNG\r\n\x1a\n", kind(Image, "image/png", &["png"])),
+    sig(
+        0,
+        b"\xff\xd8\xff",
+        kind(Image, "image/jpeg", &["jpg", "jpeg"]),
+    ),
+    sig(0, b"GIF87a", kind(Image, "image/gif", &["gif"])),
+    sig(0, b"GIF89a", kind(Image, "image/gif", &["gif"])),
+    sig(0, b"II*\0", kind(Image, "image/tiff", &["tif", "tiff"])),
+    sig(0, b"MM\0*", kind(Image, "image/tiff", &["tif", "tiff"])),
+    sig(
+        0,
+        b"8BPS",
+        kind(Image, "image/vnd.adobe.photoshop", &["psd"]),
+    ),
+    sig(0, b"\0\0\x01\0", kind(Image, "image/x-icon", &["ico"])),
+    sig(0, b"BM", kind(Image, "image/bmp", &["bmp"])),
+    // 视频
+    sig(
+        0,
+        b"\x1a\x45\xdf\xa3",
+        kind(Video, "video/x-matroska", &["mkv", "webm"]),
+    ),
+    sig(
+        0,
+        b"\x30\x26\xb2\x75\x8e\x66\xcf\x11",
+        kind(Video, "video/x-ms-asf", &["wmv", "asf", "wma"]),
+    ),
+    sig(0, b"FLV\x01", kind(Video, "video/x-flv", &["flv"])),
+    sig(
+        0,
+        b".RMF",
+        kind(Video, "application/vnd.rn-realmedia", &["rmvb", "rm"]),
+    ),
+    sig(
+        0,
+        b"\0\0\x01\xba",
+        kind(Video, "video/mpeg", &["mpg", "mpeg", "vob"]),
+    ),
+    // 音频
+    sig(0, b"ID3", kind(Audio, "audio/mpeg", &["mp3"])),
+    sig(0, b"fLaC", kind(Audio, "audio/flac", &["flac"])),
+    sig(
+        0,
+        b"OggS",
+        kind(Audio, "audio/ogg", &["ogg", "oga", "opus"]),
+    ),
+    sig(0, b"MThd", kind(Audio, "audio/midi", &["mid", "midi"])),
+    sig(0, b"#!AMR", kind(Audio, "audio/amr", &["amr"])),
+    // 压缩包
+    sig(
+        0,
+        b"Rar!\x1a\x07",
+        kind(Archive, "application/vnd.rar", &["rar"]),
+    ),
+    sig(
+        0,
+        b"7z\xbc\xaf\x27\x1c",
+        kind(Archive, "application/x-7z-compressed", &["7z"]),
+    ),
+    sig(
+        0,
+        b"\x1f\x8b",
+        kind(Archive, "application/gzip", &["gz", "tgz"]),
+    ),
+    sig(0, b"BZh", kind(Archive, "application/x-bzip2", &["bz2"])),
+    sig(
+        0,
+        b"\xfd7zXZ\0",
+        kind(Archive, "application/x-xz", &["xz", "txz"]),
+    ),
+    sig(
+        0,
+        b"\x28\xb5\x2f\xfd",
+        kind(Archive, "application/zstd", &["zst"]),
+    ),
+    sig(257, b"ustar", kind(Archive, "application/x-tar", &["tar"])),
+    // 可执行文件
+    sig(
+        0,
+        b"\x7fELF",
+        kind(Executable, "application/x-elf", &["so", "o"]),
+    ),
+    sig(
+        0,
+        b"MZ",
+        kind(
+            Executable,
+            "application/vnd.microsoft.portable-executable",
+            &["exe", "dll", "sys", "msi"],
+        ),
+    ),
+    sig(
+        0,
+        b"\xfe\xed\xfa\xce",
+        kind(Executable, "application/x-mach-binary", &["dylib"]),
+    ),
+    sig(
+        0,
+        b"\xfe\xed\xfa\xcf",
+        kind(Executable, "application/x-mach-binary", &["dylib"]),
+    ),
+    sig(
+        0,
+        b"\xce\xfa\xed\xfe",
+        kind(Executable, "application/x-mach-binary", &["dylib"]),
+    ),
+    sig(
+        0,
+        b"\xcf\xfa\xed\xfe",
+        kind(Executable, "application/x-mach-binary", &["dylib"]),
+    ),
+    sig(
+        0,
+        b"\xca\xfe\xba\xbe",
+        kind(Executable, "application/x-mach-binary", &["class"]),
+    ),
+    sig(0, b"\0asm", kind(Executable, "application/wasm", &["wasm"])),
+    sig(
+        0,
+        b"dex\n",
+        kind(Executable, "application/vnd.android.dex", &["dex"]),
+    ),
+    // 文档
+    sig(0, b"%PDF", kind(Document, "application/pdf", &["pdf"])),
+    sig(
+        0,
+        b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1",
+        kind(
+            Document,
+            "application/x-ole-storage",
+            &["doc", "xls", "ppt", "msg"],
+        ),
+    ),
+    sig(0, b"{\\rtf", kind(Document, "application/rtf", &["rtf"])),
+    sig(
+        0,
+        b"%!PS",
+        kind(Document, "application/postscript", &["ps", "eps"]),
+    ),
+];
+
+fn starts_at(data: &[u8], offset: usize, magic: &[u8]) -> bool {
+    data.get(offset..offset + magic.len()) == Some(magic)
+}
+
+fn contains(data: &[u8], needle: &[u8]) -> bool {
+    data.windows(needle.len()).any(|w| w == needle)
+}
+
+/// RIFF 容器按子类型区分
+fn detect_riff(data: &[u8]) -> Option<FileType> {
+    if !starts_at(data, 0, b"RIFF") {
+        return None;
+    }
+    match data.get(8..12)? {
+        b"WEBP" => Some(kind(Image, "image/webp", &["webp"])),
+        b"WAVE" => Some(kind(Audio, "audio/wav", &["wav"])),
+        b"AVI " => Some(kind(Video, "video/x-msvideo", &["avi"])),
+        _ => None,
+    }
+}
+
+/// ISO 基础媒体格式（MP4、MOV、HEIC 等）按主品牌区分
+fn detect_ftyp(data: &[u8]) -> Option<FileType> {
+    if !starts_at(data, 4, b"ftyp") {
+        return None;
+    }
+    Some(match data.get(8..12)? {
+        b"heic" | b"heix" | b"mif1" | b"msf1" => kind(Image, "image/heic", &["heic", "heif"]),
+        b"avif" => kind(Image, "image/avif", &["avif"]),
+        b"M4A " | b"M4B " => kind(Audio, "audio/mp4", &["m4a", "m4b"]),
+        b"qt  " => kind(Video, "video/quicktime", &["mov"]),
+        b"3gp4" | b"3gp5" | b"3g2a" => kind(Video, "video/3gpp", &["3gp", "3g2"]),
+        _ => kind(Video, "video/mp4", &["mp4", "m4v"]),
+    })
+}
+
+/// ZIP 格式的 Office 文档、EPUB 等通过前几个条目的名称区分
+fn detect_zip(data: &[u8]) -> Option<FileType> {
+    if !starts_at(data, 0, b"PK\x03\x04") && !starts_at(data, 0, b"PK\x05\x06") {
+        return None;
+    }
+    // mimetype 条目不压缩且位于第一个，内容紧跟在文件名之后
+    if starts_at(data, 30, b"mimetype") {
+        if starts_at(data, 38, b"application/epub+zip") {
+            return Some(EPUB);
+        }
+        if starts_at(data, 38, b"application/vnd.oasis.opendocument") {
+            return Some(ODF);
+        }
+    }
+    Some(if contains(data, b"word/") {
+        DOCX
+    } else if contains(data, b"xl/") {
+        XLSX
+    } else if contains(data, b"ppt/") {
+        PPTX
+    } else if contains(data, b"META-INF/MANIFEST.MF") || contains(data, b"AndroidManifest.xml") {
+        JAR
+    } else {
+        ZIP
+    })
+}
+
+/// MPEG 音频帧或 ADTS 的同步字
+fn detect_mpeg_audio(data: &[u8]) -> Option<FileType> {
+    match data.get(0..2)? {
+        [0xff, 0xf1] | [0xff, 0xf9] => Some(kind(Audio, "audio/aac", &["aac"])),
+        [0xff, b] if b & 0xe0 == 0xe0 && b & 0x06 != 0 => Some(kind(Audio, "audio/mpeg", &["mp3"])),
+        _ => None,
+    }
+}
+
+/// 没有 NUL 字节且为合法 UTF-8（允许末尾字符被截断）或带 UTF-16 BOM 时视为文本
+fn detect_text(data: &[u8]) -> Option<FileType> {
+    if data.starts_with(b"\xff\xfe") || data.starts_with(b"\xfe\xff") {
+        return Some(TEXT);
+    }
+    if data.contains(&0) {
+        return None;
+    }
+    let valid = match std::str::from_utf8(data) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    if !valid {
+        return None;
+    }
+    let head = String::from_utf8_lossy(&data[..data.len().min(512)]).to_lowercase();
+    let head = head.trim_start_matches('\u{feff}').trim_start();
+    Some(if head.contains("<svg") {
+        kind(Image, "image/svg+xml", &["svg"])
+    } else if head.starts_with("<!doctype html") || head.starts_with("<html") {
+        kind(Text, "text/html", &["html", "htm"])
+    } else if head.starts_with("<?xml") {
+        kind(Text, "application/xml", &["xml"])
+    } else {
+        TEXT
+    })
+}
+
+/// 根据文件头识别类型，空数据与无法识别的二进制数据返回 Unknown
+pub fn detect_bytes(data: &[u8]) -> FileType {
+    if data.is_empty() {
+        return UNKNOWN;
+    }
+    detect_zip(data)
+        .or_else(|| detect_riff(data))
+        .or_else(|| detect_ftyp(data))
+        .or_else(|| {
+            SIGNATURES
+                .iter()
+                .find(|s| starts_at(data, s.offset, s.magic))
+                .map(|s| s.kind)
+        })
+        .or_else(|| detect_mpeg_audio(data))
+        .or_else(|| detect_text(data))
+        .unwrap_or(UNKNOWN)
+}
+
+/// 读取文件头识别类型（阻塞操作）
+pub fn detect_file(path: &Path) -> io::Result<FileType> {
+    let mut file = File::open(path)?;
+    let mut data = vec![];
+    (&mut file).take(HEADER_LEN).read_to_end(&mut data)?;
+    let detected = detect_bytes(&data);
+    if detected.category != Unknown {
+        return Ok(detected);
+    }
+
+    // 光盘镜像开头通常全为 0，需要额外读取卷描述符
+    let mut magic = [0u8; 5];
+    if file.seek(SeekFrom::Start(ISO_MAGIC_OFFSET)).is_ok()
+        && file.read_exact(&mut magic).is_ok()
+        && &magic == b"CD001"
+    {
+        return Ok(kind(Archive, "application/x-iso9660-image", &["iso"]));
+    }
+    Ok(detected)
+}
+
+/// 扩展名是否与识别出的类型相符，文本类型只要扩展名不属于已知的二进制格式即可
+pub fn extension_matches(file_type: &FileType, extension: &str) -> bool {
+    let extension = extension.to_lowercase();
+    match file_type.category {
+        Unknown => true,
+        Text if file_type.extensions.is_empty() => !SIGNATURES
+            .iter()
+            .any(|s| s.kind.extensions.contains(&extension.as_str())),
+        _ => file_type.extensions.contains(&extension.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_bytes() {
+        assert_eq!(detect_bytes(b"\x89PNG\r\n\x1a\n....").mime, "image/png");
+        assert_eq!(detect_bytes(b"\xff\xd8\xff\xe0").category, Image);
+        assert_eq!(detect_bytes(b"RIFF\0\0\0\0WAVEfmt ").mime, "audio/wav");
+        assert_eq!(detect_bytes(b"\0\0\0\x18ftypmp42").mime, "video/mp4");
+        assert_eq!(detect_bytes(b"\0\0\0\x18ftypheic").category, Image);
+        assert_eq!(detect_bytes(b"\xff\xfb\x90\x00").mime, "audio/mpeg");
+        assert_eq!(detect_bytes(b"\x7fELF\x02\x01").category, Executable);
+        assert_eq!(detect_bytes(b"%PDF-1.7").category, Document);
+        assert_eq!(detect_bytes(b"hello, world\n").mime, "text/plain");
+        assert_eq!(detect_bytes("中文内容".as_bytes()).category, Text);
+        assert_eq!(
+            detect_bytes(b"<?xml version=\"1.0\"?><svg>").mime,
+            "image/svg+xml"
+        );
+        assert_eq!(detect_bytes(b"\x00\x01\x02\x03").category, Unknown);
+        assert_eq!(detect_bytes(b"").category, Unknown);
+
+        let mut docx = b"PK\x03\x04".to_vec();
+        docx.extend([0u8; 26]);
+        docx.extend(b"[Content_Types].xml....word/document.xml");
+        assert_eq!(detect_bytes(&docx).extensions, &["docx"]);
+
+        let mut epub = b"PK\x03\x04".to_vec();
+        epub.extend([0u8; 26]);
+        epub.extend(b"mimetypeapplication/epub+zip");
+        assert_eq!(detect_bytes(&epub).extensions, &["epub"]);
+    }
+
+    #[test]
+    fn test_extension_matches() {
+        let jpeg = detect_bytes(b"\xff\xd8\xff\xe0");
+        assert!(extension_matches(&jpeg, "JPG"));
+        assert!(!extension_matches(&jpeg, "png"));
+        assert!(extension_matches(&TEXT, "log"));
+        assert!(!extension_matches(&TEXT, "exe"));
+        assert!(extension_matches(&UNKNOWN, "bin"));
+    }
+}
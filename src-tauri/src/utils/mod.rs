@@ -1,2 +1,5 @@
+pub mod archive;
+pub mod file_type;
 pub mod os;
 pub mod output;
+pub mod trash;
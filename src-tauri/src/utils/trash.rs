@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Error, Result as AnyResult};
+
+/// 移到系统回收站，Linux 下遵循 XDG Trash 规范（`~/.local/share/Trash`，其他分区为 `.Trash-$uid`）
+pub fn move_to_trash(path: &Path) -> AnyResult<()> {
+    ::trash::delete(path).map_err(|e| Error::msg(format!("移到回收站失败：{}", e)))
+}
+
+/// 还原 `since`（秒级时间戳）之后移入回收站的文件，同名多次删除时还原最近的一个
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore_from_trash(paths: &[PathBuf], since: i64) -> AnyResult<usize> {
+    use ::trash::os_limited;
+    use std::collections::HashMap;
+
+    let items = os_limited::list().map_err(|e| Error::msg(format!("读取回收站失败：{}", e)))?;
+    let mut latest: HashMap<PathBuf, ::trash::TrashItem> = HashMap::new();
+    for item in items {
+        // 回收站记录的时间精度为秒
+        if item.time_deleted < since - 1 {
+            continue;
+        }
+        let original = item.original_path();
+        if !paths.contains(&original) {
+            continue;
+        }
+        if latest
+            .get(&original)
+            .is_none_or(|old| old.time_deleted < item.time_deleted)
+        {
+            latest.insert(original, item);
+        }
+    }
+
+    let count = latest.len();
+    if count == 0 {
+        return Err(Error::msg("回收站中找不到对应的文件"));
+    }
+    os_limited::restore_all(latest.into_values()).map_err(|e| match e {
+        ::trash::Error::RestoreCollision { path, .. } => {
+            Error::msg(format!("原位置已存在同名文件：{}", path.display()))
+        }
+        e => Error::msg(format!("还原失败：{}", e)),
+    })?;
+    Ok(count)
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn restore_from_trash(_paths: &[PathBuf], _since: i64) -> AnyResult<usize> {
+    Err(Error::msg("当前系统不支持从回收站还原，请在访达中手动还原"))
+}
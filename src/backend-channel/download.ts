@@ -1,5 +1,15 @@
 import { invoke } from '@tauri-apps/api/core'
-import { DownloadFilePayload, DownloadConfig } from './models/download'
+import {
+  DownloadFilePayload,
+  DownloadConfig,
+  PostActionRule,
+  NotifySettings,
+  BatchSummary,
+  BandwidthRules,
+  ScheduledTaskInfo,
+  BridgeConfig,
+  HostSettings
+} from './models/download'
 import { BackendResp } from '@/types/common'
 import type { ResumeDownloadInfo, RangeSupportResult } from '@/views/Download/types'
 
@@ -17,6 +27,14 @@ export async function downloadFileWithConfig(config: DownloadConfig) {
   })
 }
 
+/** 批量下载，结束后发送一条汇总通知 */
+export async function downloadFilesBatch(configs: DownloadConfig[], maxConcurrent?: number) {
+  return invoke<BackendResp<BatchSummary>>('download_files_batch', {
+    configs,
+    maxConcurrent
+  })
+}
+
 /** 扫描未完成的下载文件 */
 export async function scanUnfinishedDownloads(filePath: string) {
   return invoke<BackendResp<ResumeDownloadInfo[]>>('scan_unfinished_downloads', {
@@ -30,3 +48,75 @@ export async function checkServerRangeSupport(url: string) {
     url
   })
 }
+
+/** 设置全局下载后续操作规则 */
+export async function setPostActionRules(rules: PostActionRule[]) {
+  return invoke<BackendResp<string>>('set_post_action_rules', {
+    rules
+  })
+}
+
+/** 获取下载通知设置 */
+export async function getDownloadNotifySettings() {
+  return invoke<BackendResp<NotifySettings>>('get_download_notify_settings')
+}
+
+/** 设置下载通知 */
+export async function setDownloadNotifySettings(settings: NotifySettings) {
+  return invoke<BackendResp<string>>('set_download_notify_settings', {
+    settings
+  })
+}
+
+/** 加入计划下载队列，startAt 为毫秒时间戳 */
+export async function scheduleDownload(
+  config: DownloadConfig,
+  startAt?: number,
+  respectWindows?: boolean
+) {
+  return invoke<BackendResp<string>>('schedule_download', {
+    config,
+    startAt,
+    respectWindows
+  })
+}
+
+/** 取消计划下载 */
+export async function cancelScheduledDownload(id: string) {
+  return invoke<BackendResp<string>>('cancel_scheduled_download', { id })
+}
+
+/** 获取计划下载列表 */
+export async function listScheduledDownloads() {
+  return invoke<BackendResp<ScheduledTaskInfo[]>>('list_scheduled_downloads')
+}
+
+/** 获取下载时间段规则 */
+export async function getBandwidthRules() {
+  return invoke<BackendResp<BandwidthRules>>('get_bandwidth_rules')
+}
+
+/** 设置下载时间段规则 */
+export async function setBandwidthRules(rules: BandwidthRules) {
+  return invoke<BackendResp<string>>('set_bandwidth_rules', { rules })
+}
+
+/** 获取浏览器接收服务配置 */
+export async function getBrowserBridgeConfig() {
+  return invoke<BackendResp<BridgeConfig>>('get_browser_bridge_config')
+}
+
+/** 设置浏览器接收服务配置，保存后立即按新配置重启服务 */
+export async function setBrowserBridgeConfig(config: BridgeConfig) {
+  return invoke<BackendResp<string>>('set_browser_bridge_config', { config })
+}
+
+/** 获取各域名的下载规则 */
+export async function getHostPolicies() {
+  return invoke<BackendResp<HostSettings>>('get_host_policies')
+}
+
+/** 设置各域名的下载规则 */
+export async function setHostPolicies(settings: HostSettings) {
+  return invoke<BackendResp<string>>('set_host_policies', { settings })
+}
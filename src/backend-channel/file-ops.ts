@@ -0,0 +1,117 @@
+import { invoke } from '@tauri-apps/api/core'
+import {
+  BatchRenamePayload,
+  DirCompareReport,
+  DirSyncPayload,
+  DirSyncReport,
+  FileOperationPayload,
+  FolderRule,
+  HashFilesPayload,
+  HashReport,
+  OperationReport,
+  RenameBatch,
+  RenamePreview,
+  RuleLogEntry,
+  RuleTestItem,
+  UndoRecord,
+  VerifyReport
+} from './models/file-ops'
+import { BackendResp } from '@/types/common'
+
+/** 批量删除、移到回收站、移动或复制，进度通过 `file-operation-progress` 事件推送 */
+export function runFileOperation(payload: FileOperationPayload) {
+  return invoke<BackendResp<OperationReport>>('run_file_operation', { payload })
+}
+
+/** 撤销回收站或移动操作，不传 ID 时撤销最近一次 */
+export function undoFileOperation(operationId?: string) {
+  return invoke<BackendResp<OperationReport>>('undo_file_operation', { operationId })
+}
+
+/** 获取可撤销的操作 */
+export function listFileOperations() {
+  return invoke<BackendResp<UndoRecord[]>>('list_file_operations')
+}
+
+/** 在系统文件管理器中显示 */
+export function revealInFolder(path: string) {
+  return invoke<BackendResp<string>>('reveal_in_folder', { path })
+}
+
+/** 预览批量重命名并检查冲突 */
+export function previewBatchRename(payload: BatchRenamePayload) {
+  return invoke<BackendResp<RenamePreview>>('preview_batch_rename', { payload })
+}
+
+/** 执行批量重命名，有冲突时不执行，失败时全部还原 */
+export function applyBatchRename(payload: BatchRenamePayload) {
+  return invoke<BackendResp<RenameBatch>>('apply_batch_rename', { payload })
+}
+
+/** 撤销批量重命名，不传 ID 时撤销最近一次 */
+export function undoBatchRename(batchId?: string) {
+  return invoke<BackendResp<string>>('undo_batch_rename', { batchId })
+}
+
+/** 获取重命名记录 */
+export function listRenameHistory() {
+  return invoke<BackendResp<RenameBatch[]>>('list_rename_history')
+}
+
+/** 计算 MD5、SHA-1、SHA-256 与 CRC32，大文件的进度通过 `file-hash-progress` 事件推送 */
+export function hashFiles(payload: HashFilesPayload) {
+  return invoke<BackendResp<HashReport>>('hash_files', { payload })
+}
+
+/** 按 `.sha256sum`、`.md5` 等校验文件核对文件 */
+export function verifyChecksumFile(path: string, taskId?: string) {
+  return invoke<BackendResp<VerifyReport>>('verify_checksum_file', { path, taskId })
+}
+
+/** 取消哈希计算或校验任务 */
+export function cancelHashTask(taskId: string) {
+  return invoke<BackendResp<string>>('cancel_hash_task', { taskId })
+}
+
+/** 比较两个文件夹并生成同步计划，不修改文件 */
+export function compareDirectories(payload: DirSyncPayload) {
+  return invoke<BackendResp<DirCompareReport>>('compare_directories', { payload })
+}
+
+/** 重新比较后将源文件夹单向同步到目标文件夹，进度通过 `dir-sync-progress` 事件推送 */
+export function syncDirectories(payload: DirSyncPayload) {
+  return invoke<BackendResp<DirSyncReport>>('sync_directories', { payload })
+}
+
+/** 取消文件夹比较或同步 */
+export function cancelDirSync(taskId: string) {
+  return invoke<BackendResp<string>>('cancel_dir_sync', { taskId })
+}
+
+/** 获取文件夹监听规则 */
+export function listFolderRules() {
+  return invoke<BackendResp<FolderRule[]>>('list_folder_rules')
+}
+
+/** 新建或更新规则，保存后会立即检查文件夹中已有的文件 */
+export function saveFolderRule(rule: FolderRule) {
+  return invoke<BackendResp<FolderRule>>('save_folder_rule', { rule })
+}
+
+export function deleteFolderRule(id: string) {
+  return invoke<BackendResp<string>>('delete_folder_rule', { id })
+}
+
+/** 对文件夹中已有的文件试运行规则，不修改文件 */
+export function testFolderRule(rule: FolderRule) {
+  return invoke<BackendResp<RuleTestItem[]>>('test_folder_rule', { rule })
+}
+
+/** 获取规则的操作记录，新的记录也会通过 `folder-rule-action` 事件推送 */
+export function listFolderRuleLog() {
+  return invoke<BackendResp<RuleLogEntry[]>>('list_folder_rule_log')
+}
+
+export function clearFolderRuleLog() {
+  return invoke<BackendResp<string>>('clear_folder_rule_log')
+}
@@ -1,11 +1,166 @@
 import { invoke } from '@tauri-apps/api/core'
-import { FileSearchPayload } from './models/file-search'
+import {
+  CleanReport,
+  CleanTarget,
+  CleanupPayload,
+  DetectedFileType,
+  DiskUsagePayload,
+  ExportPayload,
+  FileSearchPayload,
+  IndexConfig,
+  IndexQueryPayload,
+  IndexStatus,
+  ResolveDuplicatesPayload,
+  ResolveReport,
+  ResultPage,
+  ResultQuery,
+  SavedSearch,
+  SaveSearchPayload,
+  SearchHistoryEntry,
+  SearchResultModel,
+  SearchTaskInfo,
+  UsageNode
+} from './models/file-search'
+import { BackendResp } from '@/types/common'
 
-/** 在磁盘中搜索文件 */
+/** 在磁盘中搜索文件，返回搜索 ID，结果通过 `search-disk-file-output` 事件推送 */
 export function searchHarddiskFile(payload: FileSearchPayload) {
-  return invoke('search_disk_file_real_time', { payload })
+  return invoke<BackendResp<string>>('search_disk_file_real_time', { payload })
 }
 
-export function cancelSearchTask() {
-  return invoke('cancel_search_task')
+/** 取消搜索，不传 ID 时取消全部 */
+export function cancelSearchTask(searchId?: string) {
+  return invoke<BackendResp<string>>('cancel_search_task', { searchId })
+}
+
+/** 暂停搜索 */
+export function pauseSearchTask(searchId: string) {
+  return invoke<BackendResp<string>>('pause_search_task', { searchId })
+}
+
+/** 继续搜索 */
+export function resumeSearchTask(searchId: string) {
+  return invoke<BackendResp<string>>('resume_search_task', { searchId })
+}
+
+/** 获取进行中的搜索 */
+export function listSearchTasks() {
+  return invoke<BackendResp<SearchTaskInfo[]>>('list_search_tasks')
+}
+
+/** 分页查询某次搜索的结果，可按字段排序 */
+export function querySearchResults(query: ResultQuery) {
+  return invoke<BackendResp<ResultPage>>('query_search_results', { query })
+}
+
+/** 导出搜索结果，返回导出的条数 */
+export function exportSearchResults(payload: ExportPayload) {
+  return invoke<BackendResp<number>>('export_search_results', { payload })
+}
+
+/** 清除保存的搜索结果，不传 ID 时清除全部 */
+export function clearSearchResults(searchId?: string) {
+  return invoke<BackendResp<string>>('clear_search_results', { searchId })
+}
+
+/** 获取默认的排除规则 */
+export function getSearchDefaultExcludes() {
+  return invoke<BackendResp<string[]>>('get_search_default_excludes')
+}
+
+/** 从文件名索引中查询 */
+export function queryFileIndex(payload: IndexQueryPayload) {
+  return invoke<BackendResp<SearchResultModel[]>>('query_file_index', { payload })
+}
+
+/** 获取索引配置 */
+export function getFileIndexConfig() {
+  return invoke<BackendResp<IndexConfig>>('get_file_index_config')
+}
+
+/** 设置索引配置，保存后重新扫描 */
+export function setFileIndexConfig(config: IndexConfig) {
+  return invoke<BackendResp<string>>('set_file_index_config', { config })
+}
+
+/** 获取索引状态 */
+export function getFileIndexStatus() {
+  return invoke<BackendResp<IndexStatus>>('get_file_index_status')
+}
+
+/** 重建索引 */
+export function rebuildFileIndex() {
+  return invoke<BackendResp<string>>('rebuild_file_index')
+}
+
+/** 将压缩包内的文件（`a.zip!/inner/path`）单独解压到指定目录，返回解压后的路径 */
+export function extractArchiveEntry(path: string, targetDir: string) {
+  return invoke<BackendResp<string>>('extract_archive_entry', { path, targetDir })
+}
+
+/** 读取文件头识别真实类型，不依赖扩展名 */
+export function detectFileType(path: string) {
+  return invoke<BackendResp<DetectedFileType>>('detect_file_type', { path })
+}
+
+/** 查找重复文件，返回搜索 ID，结果通过 `duplicate-file-output` 事件推送 */
+export function findDuplicateFiles(payload: FileSearchPayload) {
+  return invoke<BackendResp<string>>('find_duplicate_files', { payload })
+}
+
+/** 处理重复文件，`dryRun` 时只返回预览 */
+export function resolveDuplicateFiles(payload: ResolveDuplicatesPayload) {
+  return invoke<BackendResp<ResolveReport>>('resolve_duplicate_files', { payload })
+}
+
+/** 统计目录占用，返回搜索 ID，结果通过 `disk-usage-output` 事件推送 */
+export function scanDiskUsage(payload: DiskUsagePayload) {
+  return invoke<BackendResp<string>>('scan_disk_usage', { payload })
+}
+
+/** 从扫描缓存中读取目录的子项 */
+export function getDiskUsage(path: string, limit?: number) {
+  return invoke<BackendResp<UsageNode>>('get_disk_usage', { path, limit })
+}
+
+/** 清除扫描缓存，不传根目录时清除全部 */
+export function clearDiskUsageCache(root?: string) {
+  return invoke<BackendResp<string>>('clear_disk_usage_cache', { root })
+}
+
+/** 最近运行过的搜索，最新的在前 */
+export function listSearchHistory() {
+  return invoke<BackendResp<SearchHistoryEntry[]>>('list_search_history')
+}
+
+export function clearSearchHistory() {
+  return invoke<BackendResp<string>>('clear_search_history')
+}
+
+export function listSavedSearches() {
+  return invoke<BackendResp<SavedSearch[]>>('list_saved_searches')
+}
+
+/** 新建或更新保存的搜索 */
+export function saveSearch(payload: SaveSearchPayload) {
+  return invoke<BackendResp<SavedSearch>>('save_search', { payload })
+}
+
+export function deleteSavedSearch(id: string) {
+  return invoke<BackendResp<string>>('delete_saved_search', { id })
+}
+
+/** 立即运行保存的搜索，返回搜索 ID，结果与普通搜索一样通过事件推送 */
+export function runSavedSearch(id: string) {
+  return invoke<BackendResp<string>>('run_saved_search', { id })
+}
+
+/** 查找可清理的文件与目录，返回搜索 ID，结果通过 `cleanup-scan-output` 事件推送 */
+export function scanCleanupCandidates(payload: CleanupPayload) {
+  return invoke<BackendResp<string>>('scan_cleanup_candidates', { payload })
+}
+
+/** 将选中的项目移到回收站 */
+export function cleanUpItems(items: CleanTarget[]) {
+  return invoke<BackendResp<CleanReport>>('clean_up_items', { items })
 }
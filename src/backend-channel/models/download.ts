@@ -13,6 +13,38 @@ export interface DownloadConfig {
   fileName?: string
   eventType?: string
   speedLimitMbps?: number
+  postActions?: PostAction[]
+  /** 期望的 MD5 校验值 */
+  checksum?: string
+  /** 是否发送系统通知，未设置时使用全局配置 */
+  notify?: boolean
+  /** 附加请求头，如 Referer、Cookie */
+  headers?: Record<string, string>
+}
+
+export interface MoveRule {
+  extensions?: string[]
+  minSize?: number
+  maxSize?: number
+  targetDir: string
+}
+
+/** 下载完成后的后续操作，`runCommand` 支持 `{path}` `{dir}` `{name}` 占位符 */
+export type PostAction =
+  | { type: 'extract'; targetDir?: string; deleteArchive?: boolean }
+  | { type: 'move'; rules: MoveRule[] }
+  | { type: 'runCommand'; command: string }
+
+export interface PostActionRule {
+  extensions?: string[]
+  actions: PostAction[]
+}
+
+export interface PostActionResult {
+  action: PostAction['type']
+  success: boolean
+  message: string
+  outputPath: string | null
 }
 
 export interface DownloadProgress {
@@ -21,4 +53,68 @@ export interface DownloadProgress {
   percentage: number
   speedMbps: number
   status: 'starting' | 'downloading' | 'paused' | 'resumed' | 'completed' | 'failed'
+  postActions?: PostActionResult[]
+}
+
+export interface NotifySettings {
+  enabled: boolean
+  onCompleted: boolean
+  onFailed: boolean
+  onChecksumMismatch: boolean
+}
+
+export interface BatchSummary {
+  completed: number
+  failed: number
+  checksumMismatch: number
+}
+
+/** 每日时间段，`end` 不大于 `start` 时表示跨越零点，格式 HH:MM */
+export interface TimeWindow {
+  start: string
+  end: string
+  speedLimitMbps?: number
+}
+
+export interface BandwidthRules {
+  /** 为 true 时只在时间段内下载，否则时间段只用于限速 */
+  restrictToWindows: boolean
+  windows: TimeWindow[]
+}
+
+export type ScheduledStatus = 'waiting' | 'running' | 'paused' | 'completed' | { failed: string }
+
+export interface ScheduledTaskInfo {
+  id: string
+  url: string
+  startAt: number | null
+  respectWindows: boolean
+  status: ScheduledStatus
+  createdAt: number
+}
+
+/** 浏览器发送下载请求的本地接收服务配置 */
+export interface BridgeConfig {
+  enabled: boolean
+  port: number
+  /** 请求需通过 `Authorization: Bearer <token>` 或 `X-Toolbox-Token` 携带 */
+  token: string
+  /** 允许的 Origin，如 `chrome-extension://<id>` */
+  allowedOrigins: string[]
+  dirPath: string
+  concurrent: number
+}
+
+/** 单个域名的下载规则，同时作用于其子域名 */
+export interface HostPolicy {
+  host: string
+  maxConnections?: number | null
+  minDelayMs?: number | null
+  /** 为 false 时不使用分片下载 */
+  allowRanges?: boolean | null
+}
+
+export interface HostSettings {
+  defaultMaxConnections: number
+  policies: HostPolicy[]
 }
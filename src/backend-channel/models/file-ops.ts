@@ -0,0 +1,284 @@
+import { FileCategory } from './file-search'
+
+export type FileAction = 'delete' | 'trash' | 'move' | 'copy'
+
+/** 目标位置已存在同名文件时的处理方式，`rename` 会自动命名为 `name (1).ext` */
+export type ConflictPolicy = 'skip' | 'overwrite' | 'rename'
+
+export interface FileOperationPayload {
+  action: FileAction
+  paths: string[]
+  /** 移动、复制的目标文件夹 */
+  targetDir?: string
+  conflict?: ConflictPolicy
+  /** 建议前端生成后传入，用于过滤进度事件 */
+  operationId?: string
+}
+
+export interface OperationItem {
+  path: string
+  /** 移动、复制后的位置 */
+  target?: string
+  status: 'done' | 'skipped' | 'failed'
+  error?: string
+}
+
+export interface OperationReport {
+  operationId: string
+  action: FileAction
+  items: OperationItem[]
+  /** 回收站与移动操作可撤销 */
+  undoable: boolean
+}
+
+/** `file-operation-progress` 事件内容 */
+export interface OperationProgress {
+  operationId: string
+  /** 从 1 开始 */
+  index: number
+  total: number
+  item: OperationItem
+}
+
+export interface UndoRecord {
+  operationId: string
+  action: FileAction
+  executedAt: number
+  /** 原位置与操作后的位置，移到回收站时没有后者 */
+  items: [string, string | null][]
+}
+
+export type RenamePosition = 'prefix' | 'suffix'
+
+/** 重命名规则，按顺序依次作用于文件名（默认不含扩展名） */
+export type RenameRule =
+  | {
+      type: 'replace'
+      find: string
+      replace?: string
+      regex?: boolean
+      ignoreCase?: boolean
+      includeExtension?: boolean
+    }
+  | { type: 'case'; mode: 'lower' | 'upper' | 'title' | 'sentence'; includeExtension?: boolean }
+  | {
+      type: 'number'
+      start?: number
+      step?: number
+      /** 补零后的最小位数 */
+      padding?: number
+      position?: RenamePosition
+      separator?: string
+    }
+  | {
+      type: 'date'
+      /** `exif` 为照片拍摄时间，没有 EXIF 信息时使用修改时间 */
+      source?: 'modified' | 'exif'
+      /** chrono 格式，默认 `%Y%m%d` */
+      format?: string
+      position?: RenamePosition
+      separator?: string
+    }
+  /** 修改扩展名，为空时移除 */
+  | { type: 'extension'; ext: string }
+
+export interface BatchRenamePayload {
+  paths: string[]
+  rules: RenameRule[]
+}
+
+export interface RenamePreviewItem {
+  path: string
+  newName: string
+  newPath: string
+  changed: boolean
+  /** 名称无效或与其他文件冲突时的原因 */
+  conflict?: string
+}
+
+export interface RenamePreview {
+  items: RenamePreviewItem[]
+  changedCount: number
+  conflictCount: number
+}
+
+export interface RenameBatch {
+  id: string
+  executedAt: number
+  /** 原路径与新路径 */
+  items: [string, string][]
+}
+
+export type HashAlgorithm = 'md5' | 'sha1' | 'sha256' | 'crc32'
+
+export interface HashFilesPayload {
+  /** 文件或文件夹，文件夹会递归计算其中所有文件 */
+  paths: string[]
+  /** 为空时计算全部算法 */
+  algorithms?: HashAlgorithm[]
+  taskId?: string
+}
+
+export interface HashItem {
+  path: string
+  size: number
+  md5?: string
+  sha1?: string
+  sha256?: string
+  crc32?: string
+  error?: string
+}
+
+export interface HashReport {
+  taskId: string
+  items: HashItem[]
+  cancelled: boolean
+}
+
+export interface HashProgress {
+  taskId: string
+  /** 从 1 开始 */
+  index: number
+  total: number
+  path: string
+  /** 当前文件已处理的字节数 */
+  processed: number
+  size: number
+}
+
+export type VerifyStatus = 'ok' | 'mismatch' | 'missing' | 'error'
+
+export interface VerifyItem {
+  path: string
+  algorithm: HashAlgorithm
+  expected: string
+  actual?: string
+  status: VerifyStatus
+  error?: string
+}
+
+export interface VerifyReport {
+  taskId: string
+  checksumFile: string
+  items: VerifyItem[]
+  cancelled: boolean
+}
+
+/** `quick` 比较大小与修改时间，`content` 在大小相同时逐字节比较 */
+export type CompareMode = 'quick' | 'content'
+
+export interface DirSyncPayload {
+  /** 同步的源文件夹 */
+  left: string
+  /** 同步的目标文件夹 */
+  right: string
+  mode?: CompareMode
+  /** 将目标中多余的项目移到回收站 */
+  deleteExtra?: boolean
+  taskId?: string
+}
+
+export type CompareStatus = 'onlyLeft' | 'onlyRight' | 'same' | 'differs'
+
+export interface EntryMeta {
+  isDir: boolean
+  size: number
+  modified?: number
+}
+
+export interface CompareEntry {
+  /** 以 `/` 分隔的相对路径 */
+  relativePath: string
+  status: CompareStatus
+  left?: EntryMeta
+  right?: EntryMeta
+}
+
+export type SyncAction = 'copy' | 'update' | 'delete'
+
+export interface SyncPlanItem {
+  action: SyncAction
+  relativePath: string
+  isDir: boolean
+  size: number
+}
+
+export interface DirCompareReport {
+  taskId: string
+  entries: CompareEntry[]
+  /** 按比较结果生成的同步计划，不会执行 */
+  plan: SyncPlanItem[]
+  cancelled: boolean
+}
+
+export interface SyncItem extends SyncPlanItem {
+  status: 'done' | 'skipped' | 'failed'
+  error?: string
+}
+
+export interface DirSyncReport {
+  taskId: string
+  items: SyncItem[]
+  cancelled: boolean
+}
+
+export interface DirSyncProgress {
+  taskId: string
+  /** 从 1 开始 */
+  index: number
+  total: number
+  item: SyncItem
+}
+
+/** 文件夹规则的条件，需全部满足 */
+export type RuleCondition =
+  /** 匹配文件名，如 `*.pdf` 或 `*.{jpg,png}`，不区分大小写 */
+  | { type: 'glob'; pattern: string }
+  | { type: 'size'; min?: number; max?: number }
+  /** 按修改时间计算的天数 */
+  | { type: 'age'; minDays?: number; maxDays?: number }
+  /** 按文件头识别的类型 */
+  | { type: 'type'; categories: FileCategory[] }
+
+/** 按顺序执行，移动或重命名后的操作作用于新路径 */
+export type RuleAction =
+  | { type: 'move'; targetDir: string }
+  /** 目标已存在同名文件时跳过 */
+  | { type: 'copy'; targetDir: string }
+  /** 支持 `{name}` `{ext}` `{date}` 占位符 */
+  | { type: 'rename'; pattern: string }
+  | { type: 'extract'; targetDir?: string; deleteArchive?: boolean }
+  /** 修改时间超过指定天数后移到回收站 */
+  | { type: 'deleteAfter'; days: number }
+
+export interface FolderRule {
+  /** 新建时为空 */
+  id?: string
+  name: string
+  enabled?: boolean
+  folders: string[]
+  /** 包含子文件夹中的文件 */
+  recursive?: boolean
+  conditions?: RuleCondition[]
+  actions: RuleAction[]
+}
+
+export interface RuleActionResult {
+  action: RuleAction['type']
+  success: boolean
+  message: string
+  outputPath?: string
+}
+
+export interface RuleLogEntry {
+  ruleId: string
+  ruleName: string
+  path: string
+  executedAt: number
+  results: RuleActionResult[]
+}
+
+export interface RuleTestItem {
+  path: string
+  results: RuleActionResult[]
+}
@@ -1,5 +1,374 @@
+/**
+ * 匹配方式：区分大小写包含、不区分大小写包含、通配符、正则、模糊匹配
+ */
+export type MatchMode = 'substring' | 'ignoreCase' | 'glob' | 'regex' | 'fuzzy'
+
+export interface SearchTerm {
+  pattern: string
+  /** 为 true 时排除匹配该条件的结果 */
+  negate?: boolean
+}
+
 export interface FileSearchPayload {
   name: string
   disks: string[]
   concurrent: number
+  /** 不传时由后端生成，建议前端生成后传入以免漏掉早到的结果事件 */
+  searchId?: string
+  matchMode?: MatchMode
+  /** 为空时使用 `name` 作为唯一条件 */
+  terms?: SearchTerm[]
+  termLogic?: 'and' | 'or'
+  /** 匹配完整路径而非仅文件名 */
+  matchFullPath?: boolean
+  filter?: SearchFilter
+  exclude?: ExcludeOptions
+  content?: ContentOptions
+  /** 同时搜索 zip、tar、7z 压缩包内的条目，结果路径形如 `a.zip!/inner/path` */
+  archives?: boolean
+}
+
+/** 搜索过滤条件，时间为毫秒时间戳 */
+export interface SearchFilter {
+  kind?: 'all' | 'file' | 'dir'
+  minSize?: number
+  maxSize?: number
+  modifiedAfter?: number
+  modifiedBefore?: number
+  createdAfter?: number
+  createdBefore?: number
+  /** 扩展名不含点，不区分大小写 */
+  includeExtensions?: string[]
+  excludeExtensions?: string[]
+  /** 1 表示只搜索所选目录的直接子项 */
+  maxDepth?: number
+  /** 按文件头识别的真实类型，设置后只命中文件 */
+  categories?: FileCategory[]
+}
+
+export type FileCategory =
+  | 'image'
+  | 'video'
+  | 'audio'
+  | 'archive'
+  | 'executable'
+  | 'document'
+  | 'text'
+  | 'unknown'
+
+/** 按文件头识别出的类型 */
+export interface DetectedFileType {
+  category: FileCategory
+  mime: string
+  /** 第一项为常用扩展名，纯文本为空 */
+  extensions: string[]
+  /** 扩展名与文件内容不符时为 false */
+  extensionMatches: boolean
+}
+
+/** 排除规则与遍历行为 */
+export interface ExcludeOptions {
+  /** 不传时使用默认规则，传入空数组可关闭 */
+  excludeGlobs?: string[]
+  /** 遵循目录中的 `.gitignore`、`.ignore` */
+  respectIgnoreFiles?: boolean
+  skipHidden?: boolean
+  /** 不跨越所选目录所在的文件系统 */
+  oneFileSystem?: boolean
+  /** 默认开启 */
+  skipNetworkMounts?: boolean
+  followSymlinks?: boolean
+}
+
+export interface IndexConfig {
+  enabled: boolean
+  roots: string[]
+  exclude?: ExcludeOptions
+  /** 定期全量校对的间隔（分钟） */
+  reconcileIntervalMinutes: number
+}
+
+export type IndexPhase = 'disabled' | 'scanning' | 'ready' | { failed: string }
+
+/** 通过 `file-index-status` 事件推送 */
+export interface IndexStatus {
+  phase: IndexPhase
+  entries: number
+  lastScanAt: number | null
+  /** 为 false 时仅依靠定期校对更新 */
+  watching: boolean
+}
+
+export interface IndexQueryPayload {
+  name: string
+  matchMode?: MatchMode
+  terms?: SearchTerm[]
+  termLogic?: 'and' | 'or'
+  matchFullPath?: boolean
+  filter?: SearchFilter
+  /** 只返回这些目录下的结果 */
+  disks?: string[]
+  /** 默认 1000 */
+  limit?: number
+}
+
+export interface SearchResultModel {
+  path: string
+  isDir: boolean
+  /** 字节，目录为 0 */
+  size: number
+  /** 毫秒时间戳，平台不支持时为 null */
+  modified: number | null
+  created: number | null
+  /** 小写且不含点，目录为空字符串 */
+  extension: string
+  parent: string
+  score?: number
+  /** 内容搜索命中的行号（从 1 开始） */
+  line?: number
+  snippet?: string
+}
+
+/** 内容搜索选项，文件名规则仍用于筛选候选文件 */
+export interface ContentOptions {
+  query: string
+  regex?: boolean
+  ignoreCase?: boolean
+  /** 跳过的二进制扩展名，可直接传入 `excludeFileTypes` */
+  binaryExtensions?: string[]
+  /** 超过该大小（字节）的文件不搜索，默认 20 MB */
+  maxFileSize?: number
+  /** 命中位置前后保留的字符数，默认 60 */
+  contextChars?: number
+  maxHitsPerFile?: number
+}
+
+export interface SearchTaskInfo {
+  id: string
+  name: string
+  disks: string[]
+  startedAt: number
+  paused: boolean
+}
+
+/** `search-disk-file-output` 事件内容，`results` 为 null 表示该搜索已结束 */
+export interface SearchOutput<T = SearchResultModel> {
+  searchId: string
+  results: T[] | null
+}
+
+export type AccessErrorKind = 'permissionDenied' | 'notFound' | 'interrupted' | 'other'
+
+export interface InaccessiblePath {
+  path: string
+  kind: AccessErrorKind
+  message: string
+}
+
+/** `search-disk-file-progress` 事件内容，`newErrors` 只包含上次推送后新增的路径 */
+export interface SearchProgress {
+  searchId: string
+  dirsScanned: number
+  filesScanned: number
+  matches: number
+  currentPath: string
+  errorCount: number
+  newErrors: InaccessiblePath[]
+  finished: boolean
+}
+
+export interface DuplicateGroup {
+  /** 完整内容的 MD5 */
+  hash: string
+  /** 单个文件的大小（字节） */
+  size: number
+  files: string[]
+  /** 只保留一份时可释放的空间 */
+  reclaimable: number
+}
+
+/** `duplicate-file-output` 事件内容，`group` 为 null 表示查找已结束 */
+export interface DuplicateOutput {
+  searchId: string
+  group: DuplicateGroup | null
+  groupCount: number
+  totalReclaimable: number
+}
+
+export type ResolveAction = 'delete' | 'trash' | 'hardLink'
+
+export interface ResolveDuplicatesPayload {
+  groups: { keep: string; remove: string[] }[]
+  action: ResolveAction
+  dryRun?: boolean
+}
+
+export interface ResolveItem {
+  path: string
+  keep: string
+  size: number
+  done: boolean
+  error?: string
+}
+
+export interface ResolveReport {
+  dryRun: boolean
+  items: ResolveItem[]
+  /** 已释放（预览时为可释放）的空间 */
+  reclaimed: number
+}
+
+export interface DiskUsagePayload {
+  root: string
+  concurrent: number
+  searchId?: string
+  /** 返回的最大目录与文件数量，默认 20 */
+  top?: number
+  /** 不传时不使用默认排除规则且不跨越文件系统 */
+  exclude?: ExcludeOptions
+}
+
+export interface UsageEntry {
+  path: string
+  name: string
+  isDir: boolean
+  /** 目录为所有子项的累计大小 */
+  size: number
+}
+
+export interface UsageNode {
+  path: string
+  size: number
+  fileCount: number
+  dirCount: number
+  children: UsageEntry[]
+}
+
+/** `disk-usage-output` 事件内容，`finished` 时附带最大的目录与文件 */
+export interface DiskUsageOutput {
+  searchId: string
+  tree: UsageNode | null
+  topDirs: UsageEntry[]
+  topFiles: UsageEntry[]
+  finished: boolean
+}
+
+export type SortField = 'path' | 'name' | 'size' | 'modified' | 'created' | 'extension' | 'score'
+
+export interface ResultQuery {
+  searchId: string
+  /** 不传时按结果到达的顺序 */
+  sortBy?: SortField
+  descending?: boolean
+  offset?: number
+  /** 默认 100 */
+  limit?: number
+}
+
+export interface ResultPage<T = SearchResultModel> {
+  total: number
+  items: T[]
+}
+
+export type ExportFormat = 'csv' | 'json' | 'paths'
+
+export interface ExportPayload {
+  searchId: string
+  format: ExportFormat
+  /** 导出文件的保存路径 */
+  path: string
+  sortBy?: SortField
+  descending?: boolean
+}
+
+export interface SearchHistoryEntry {
+  searchId: string
+  payload: FileSearchPayload
+  /** 毫秒时间戳 */
+  startedAt: number
+  finishedAt: number
+  resultCount: number
+  cancelled: boolean
+}
+
+export interface SavedSearch {
+  id: string
+  name: string
+  payload: FileSearchPayload
+  /** 定时运行的间隔（分钟），为空时只能手动运行 */
+  intervalMinutes?: number | null
+  /** 出现新文件时发送通知和 `saved-search-new-matches` 事件 */
+  watch: boolean
+  createdAt: number
+  lastRunAt?: number | null
+  lastResultCount?: number | null
+}
+
+export interface SaveSearchPayload {
+  /** 传入已有 ID 时更新该搜索 */
+  id?: string
+  name: string
+  payload: FileSearchPayload
+  intervalMinutes?: number
+  watch?: boolean
+}
+
+export interface SavedSearchNewMatches {
+  savedId: string
+  name: string
+  paths: string[]
+}
+
+export interface CleanupPayload {
+  root: string
+  concurrent: number
+  searchId?: string
+  /** 列出的最大文件数量，默认 50 */
+  top?: number
+  /** 临时文件超过多少天未修改视为过期，默认 7 */
+  tempDays?: number
+  /** 缓存目录超过多少天未更新视为过期，默认 30 */
+  cacheDays?: number
+  /** 为空时使用默认排除规则 */
+  exclude?: ExcludeOptions
+}
+
+/** `downloadLeftover` 为本应用遗留的 `.download`、`.download.json` */
+export type CleanupCategory =
+  | 'largeFile'
+  | 'emptyDir'
+  | 'emptyFile'
+  | 'tempFile'
+  | 'downloadLeftover'
+  | 'cache'
+
+export interface CleanupItem {
+  path: string
+  isDir: boolean
+  size: number
+  modified?: number | null
+}
+
+export interface CleanupGroup {
+  category: CleanupCategory
+  items: CleanupItem[]
+  /** 可释放的空间 */
+  totalSize: number
+}
+
+/** `cleanup-scan-output` 事件，扫描结束后推送一次 */
+export interface CleanupOutput {
+  searchId: string
+  groups: CleanupGroup[]
+}
+
+export interface CleanTarget {
+  path: string
+  category: CleanupCategory
+}
+
+export interface CleanReport {
+  items: { path: string; size: number; done: boolean; error?: string | null }[]
+  /** 已移到回收站的总大小 */
+  reclaimed: number
 }
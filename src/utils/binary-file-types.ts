@@ -1,3 +1,4 @@
+/** 按扩展名快速排除的二进制文件，扩展名不可信时可用 `detectFileType` 读取文件头确认 */
 export const excludeFileTypes = [
   'png',
   'jpg',
@@ -1,4 +1,8 @@
-import { cancelSearchTask, searchHarddiskFile } from '@/backend-channel/file-search'
+import {
+  cancelSearchTask,
+  extractArchiveEntry,
+  searchHarddiskFile
+} from '@/backend-channel/file-search'
 import { getHarddiskInfo } from '@/backend-channel/utils'
 import { useRuntimeEvent } from '@/hooks/useRuntimeEvent'
 import { uniqBy } from 'lodash-es'
@@ -6,7 +10,13 @@ import { useDownloadConcurrent } from '../ReadFile/logic'
 import Big from 'big.js'
 import { platform } from '@tauri-apps/plugin-os'
 import { Command } from '@tauri-apps/plugin-shell'
-import { Nullable } from '@/types/common'
+import { open } from '@tauri-apps/plugin-dialog'
+import {
+  InaccessiblePath,
+  SearchOutput,
+  SearchProgress
+} from '@/backend-channel/models/file-search'
+import { generateId } from '@/utils/id'
 
 export function useInitDisk() {
   const selectedPoint = ref<string[]>([])
@@ -50,8 +60,13 @@ export enum SearchStatus {
 
 interface ResultFileModel {
   path: string
-  size: string
+  size: number
   isDir: boolean
+  /** 模糊匹配评分 */
+  score?: number
+  /** 内容搜索命中的行号与片段 */
+  line?: number
+  snippet?: string
 }
 
 export function useSearchFile(selectedPoint: Ref<string[]>) {
@@ -59,22 +74,43 @@ export function useSearchFile(selectedPoint: Ref<string[]>) {
   const searchResult = ref<ResultFileModel[]>([])
   const taskStatus = ref(SearchStatus.Default)
   const supportFolder = ref(false)
+  /** 同时搜索压缩包内的条目 */
+  const searchArchives = ref(false)
+  /** 当前页面发起的搜索，只处理该搜索的结果 */
+  const searchId = ref('')
+  const progress = ref<SearchProgress>()
+  /** 搜索过程中无法访问的路径 */
+  const inaccessiblePaths = ref<InaccessiblePath[]>([])
 
   const { concurrentCount } = useDownloadConcurrent()
 
-  function handleSearch() {
+  async function handleSearch() {
     searchResult.value = []
+    progress.value = undefined
+    inaccessiblePaths.value = []
     taskStatus.value = SearchStatus.Processing
+    searchId.value = generateId()
 
-    searchHarddiskFile({
+    const res = await searchHarddiskFile({
       name: searchText.value,
       disks: selectedPoint.value,
-      concurrent: concurrentCount.value
+      concurrent: concurrentCount.value,
+      searchId: searchId.value,
+      archives: searchArchives.value
     })
+    if (res.code !== 200) {
+      searchId.value = ''
+      taskStatus.value = SearchStatus.Default
+    }
   }
 
-  useRuntimeEvent<Nullable<ResultFileModel[]>>('search-disk-file-output', async ({ payload }) => {
-    if (!payload) {
+  useRuntimeEvent<SearchOutput<ResultFileModel>>('search-disk-file-output', async ({ payload }) => {
+    if (payload.searchId !== searchId.value) {
+      return
+    }
+    const results = payload.results
+    if (!results) {
+      searchId.value = ''
       taskStatus.value = SearchStatus.Shutdown
       setTimeout(() => {
         taskStatus.value = SearchStatus.Default
@@ -85,18 +121,28 @@ export function useSearchFile(selectedPoint: Ref<string[]>) {
       return
     }
 
-    const list = supportFolder.value ? payload : payload.filter(item => !item.isDir)
+    const list = supportFolder.value ? results : results.filter(item => !item.isDir)
     // 兼容 windows 路径中盘符的双斜杠
     const formatData = list.map(el => ({ ...el, path: el.path.replaceAll('\\\\', '\\') }))
     searchResult.value.push(...formatData)
     searchResult.value = uniqBy(searchResult.value, el => el.path)
   })
 
+  useRuntimeEvent<SearchProgress>('search-disk-file-progress', ({ payload }) => {
+    if (payload.searchId !== searchId.value) {
+      return
+    }
+    progress.value = payload
+    inaccessiblePaths.value.push(...payload.newErrors)
+  })
+
   const renderItems = useThrottle(searchResult, 500, true)
 
   function handleStopSearchTask() {
     taskStatus.value = SearchStatus.Shutdown
-    cancelSearchTask()
+    if (searchId.value) {
+      cancelSearchTask(searchId.value)
+    }
   }
 
   function clearResult() {
@@ -109,7 +155,10 @@ export function useSearchFile(selectedPoint: Ref<string[]>) {
     searchResult,
     renderItems,
     supportFolder,
+    searchArchives,
     taskStatus,
+    progress,
+    inaccessiblePaths,
     clearResult,
     handleSearch,
     handleStopSearchTask
@@ -130,7 +179,7 @@ function removeTrailingZero(numStr: string): string {
  * 获取正确的文件大小，最大单位为 GB
  * @param size 字节大小
  */
-export function getCorrectSize(size: string) {
+export function getCorrectSize(size: number | string) {
   const sizeObj = Big(size)
   const gbOffset = Big(1024).pow(3)
   const mbOffset = Big(1024).pow(2)
@@ -215,3 +264,28 @@ export function useViewFileInExplorer() {
 
   return { openInExplorer }
 }
+
+/** 压缩包内条目的路径形如 `a.zip!/inner/path` */
+export function isArchiveEntry(file: ResultFileModel) {
+  return !file.isDir && file.path.includes('!/')
+}
+
+/** 选择目录后单独解压压缩包内的文件 */
+export function useExtractArchiveEntry() {
+  const message = useMessage()
+
+  async function extractEntry(file: ResultFileModel) {
+    const targetDir = await open({ title: '选择解压目录', directory: true, multiple: false })
+    if (typeof targetDir !== 'string') {
+      return
+    }
+    const res = await extractArchiveEntry(file.path, targetDir)
+    if (res.code === 200) {
+      message.success(`已解压到 ${res.data}`)
+    } else {
+      message.error(res.message)
+    }
+  }
+
+  return { extractEntry }
+}